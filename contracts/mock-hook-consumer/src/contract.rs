@@ -40,6 +40,9 @@ pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> S
             TaskHookExecuteMsg::TaskTimeoutHook(task) => {
                 execute::task_timeout(deps, env, info, task)
             }
+            TaskHookExecuteMsg::TaskCancelledHook(task) => {
+                execute::task_cancelled(deps, env, info, task)
+            }
         },
     }
 }
@@ -49,11 +52,11 @@ mod execute {
     use lavs_apis::{
         id::TaskId,
         interfaces::task_hooks::TaskHookType,
-        tasks::{ConfigResponse, Requestor, TaskResponse},
+        tasks::{ConfigResponse, CustomExecuteMsg, Requestor},
     };
 
     use crate::{
-        msg::{TaskRequestData, TaskResponseData},
+        msg::{TaskQueueExecuteMsg, TaskRequestData, TaskResponse},
         state::{CREATED_COUNT, TASK_QUEUE},
     };
 
@@ -82,14 +85,8 @@ mod execute {
 
         TASK_QUEUE.save(deps.storage, &task_queue)?;
 
-        // Attempt to deserialize the task response
-        let response: TaskResponseData =
-            serde_json::from_value(task.result.expect("Result is not available")).map_err(|e| {
-                StdError::generic_err(format!(
-                    "Could not deserialize input request from JSON: {}",
-                    e
-                ))
-            })?;
+        // The task queue always sends the result on completion
+        let response = task.result.expect("Result is not available");
 
         // Query requestor config
         let config: ConfigResponse = deps.querier.query_wasm_smart(
@@ -109,6 +106,11 @@ mod execute {
                 }
             }
             Requestor::OpenPayment(coin) => Ok(vec![coin]),
+            // Membership is checked by the task queue
+            Requestor::Allowlist(_) => Ok(vec![]),
+            Requestor::AllowlistWithPayment { coins, .. } | Requestor::OpenPaymentAnyOf(coins) => {
+                Ok(coins.into_iter().take(1).collect())
+            }
         }?;
 
         // Construct a new request to square the square result
@@ -116,20 +118,13 @@ mod execute {
 
         let msg = CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: task_queue.to_string(),
-            msg: to_json_binary(&lavs_apis::tasks::ExecuteMsg::Custom(
-                lavs_apis::tasks::CustomExecuteMsg::Create {
-                    description: task.description,
-                    timeout: None,
-                    payload: serde_json::to_value(request).map_err(|e| {
-                        StdError::generic_err(format!(
-                            "Could not serialize request into JSON: {}",
-                            e
-                        ))
-                    })?,
-                    with_completed_hooks: None,
-                    with_timeout_hooks: None,
-                },
-            ))?,
+            msg: to_json_binary(&TaskQueueExecuteMsg::Custom(CustomExecuteMsg::Create {
+                description: task.description,
+                timeout: None,
+                payload: request,
+                with_completed_hooks: None,
+                with_timeout_hooks: None,
+            }))?,
             funds,
         });
 
@@ -148,6 +143,20 @@ mod execute {
         Err(StdError::generic_err("This is an error"))
     }
 
+    /// For a task cancelled, we no longer count it as created.
+    pub fn task_cancelled(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _task: TaskResponse,
+    ) -> StdResult<Response> {
+        CREATED_COUNT.update(deps.storage, |x| -> StdResult<_> {
+            Ok(x.saturating_sub(1))
+        })?;
+
+        Ok(Response::default().add_attribute("action", "task_cancelled"))
+    }
+
     /// This method is used to test the task-specific whitelist authorization
     pub fn register_hook(
         deps: DepsMut,
@@ -159,13 +168,11 @@ mod execute {
 
         let msg = CosmosMsg::Wasm(WasmMsg::Execute {
             contract_addr: task_queue.to_string(),
-            msg: to_json_binary(&lavs_apis::tasks::ExecuteMsg::Custom(
-                lavs_apis::tasks::CustomExecuteMsg::AddHooks {
-                    task_id: Some(task_id),
-                    hook_type,
-                    receivers: vec![env.contract.address.to_string()],
-                },
-            ))?,
+            msg: to_json_binary(&TaskQueueExecuteMsg::Custom(CustomExecuteMsg::AddHooks {
+                task_id: Some(task_id),
+                hook_type,
+                receivers: vec![env.contract.address.to_string()],
+            }))?,
             funds: vec![],
         });
 
@@ -13,7 +13,7 @@ pub enum ExecuteMsg {
         hook_type: TaskHookType,
     },
     #[serde(untagged)]
-    TaskHook(TaskHookExecuteMsg),
+    TaskHook(TaskHookExecuteMsg<TaskRequestData, TaskResponseData>),
 }
 
 #[cw_serde]
@@ -32,3 +32,9 @@ pub struct TaskRequestData {
 pub struct TaskResponseData {
     pub y: u64,
 }
+
+/// The task queue messages, with the payload types of this AVS
+pub type TaskQueueExecuteMsg = lavs_apis::tasks::ExecuteMsg<TaskRequestData, TaskResponseData>;
+
+/// The task details sent in hooks, with the payload types of this AVS
+pub type TaskResponse = lavs_apis::tasks::TaskResponse<TaskRequestData, TaskResponseData>;
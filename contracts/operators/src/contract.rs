@@ -0,0 +1,445 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Env, Event, MessageInfo, Response, StdResult,
+};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    CustomExecuteMsg, CustomQueryMsg, ExecuteMsg, InstantiateMsg, QueryMsg, SlashExecuteMsg,
+    VotingQueryMsg,
+};
+use crate::state::{Config, CONFIG, TOTAL_POWER};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let owner = msg.owner.unwrap_or(info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
+    let config = Config {
+        denom: msg.denom,
+        min_bond: msg.min_bond,
+        unbonding_period: msg.unbonding_period,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    TOTAL_POWER.save(deps.storage, &Default::default(), env.block.height)?;
+
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Api(api) => match api {
+            SlashExecuteMsg::Slash {
+                operator,
+                task_queue,
+                task_id,
+                amount,
+            } => execute::slash(deps, env, info, operator, task_queue, task_id, amount),
+        },
+        ExecuteMsg::Custom(custom) => match custom {
+            CustomExecuteMsg::Bond {} => execute::bond(deps, env, info),
+            CustomExecuteMsg::Unbond { amount } => execute::unbond(deps, env, info, amount),
+            CustomExecuteMsg::Claim {} => execute::claim(deps, env, info),
+            CustomExecuteMsg::SetWeight { operator, weight } => {
+                execute::set_weight(deps, env, info, operator, weight)
+            }
+            CustomExecuteMsg::UpdateSlashers { to_add, to_remove } => {
+                execute::update_slashers(deps, info, to_add, to_remove)
+            }
+            CustomExecuteMsg::UpdateOwnership(action) => {
+                let ownership =
+                    cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+
+                let event =
+                    Event::new("update_ownership").add_attributes(ownership.into_attributes());
+
+                Ok(Response::new().add_event(event))
+            }
+        },
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Api(api) => match api {
+            VotingQueryMsg::VotingPowerAtHeight { address, height } => {
+                to_json_binary(&query::voting_power(deps, env, address, height)?)
+            }
+            VotingQueryMsg::TotalPowerAtHeight { height } => {
+                to_json_binary(&query::total_power(deps, env, height)?)
+            }
+            VotingQueryMsg::AllVoters {} => to_json_binary(&query::all_voters(deps, env)?),
+        },
+        QueryMsg::Custom(custom) => match custom {
+            CustomQueryMsg::Config {} => to_json_binary(&query::config(deps)?),
+            CustomQueryMsg::Operator { address } => {
+                to_json_binary(&query::operator(deps, address)?)
+            }
+            CustomQueryMsg::Claims { address } => to_json_binary(&query::claims(deps, address)?),
+            CustomQueryMsg::Slashers { start_after, limit } => {
+                to_json_binary(&query::slashers(deps, start_after, limit)?)
+            }
+            CustomQueryMsg::Ownership {} => {
+                to_json_binary(&cw_ownable::get_ownership(deps.storage)?)
+            }
+        },
+    }
+}
+
+mod execute {
+    use cosmwasm_std::{coins, BankMsg, Decimal, Uint128};
+    use cw_ownable::assert_owner;
+    use cw_utils::{must_pay, nonpayable};
+    use lavs_apis::id::TaskId;
+
+    use crate::msg::{Claim, SlashAmount};
+    use crate::state::{save_stake, CLAIMS, SLASHERS, STAKES};
+
+    use super::*;
+
+    pub fn bond(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        let config = CONFIG.load(deps.storage)?;
+        let amount = must_pay(&info, &config.denom)?;
+
+        let mut stake = STAKES
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        stake.bonded += amount;
+        let power = save_stake(
+            deps.storage,
+            &config,
+            &info.sender,
+            &stake,
+            env.block.height,
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "bond")
+            .add_attribute("operator", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("power", power))
+    }
+
+    pub fn unbond(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        amount: Uint128,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        if amount.is_zero() {
+            return Err(ContractError::ZeroAmount);
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut stake = STAKES
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        if stake.bonded < amount {
+            return Err(ContractError::InsufficientBond {
+                bonded: stake.bonded,
+                requested: amount,
+            });
+        }
+        stake.bonded -= amount;
+        let power = save_stake(
+            deps.storage,
+            &config,
+            &info.sender,
+            &stake,
+            env.block.height,
+        )?;
+
+        let release_at = env
+            .block
+            .time
+            .plus_nanos(config.unbonding_period.as_nanos());
+        CLAIMS.update(deps.storage, &info.sender, |claims| -> StdResult<_> {
+            let mut claims = claims.unwrap_or_default();
+            claims.push(Claim { amount, release_at });
+            Ok(claims)
+        })?;
+
+        Ok(Response::new()
+            .add_attribute("action", "unbond")
+            .add_attribute("operator", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("release_at", release_at.to_string())
+            .add_attribute("power", power))
+    }
+
+    pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let claims = CLAIMS
+            .may_load(deps.storage, &info.sender)?
+            .unwrap_or_default();
+        let (ready, pending): (Vec<_>, Vec<_>) = claims
+            .into_iter()
+            .partition(|claim| claim.release_at <= env.block.time);
+
+        let amount: Uint128 = ready.iter().map(|claim| claim.amount).sum();
+        if amount.is_zero() {
+            return Err(ContractError::NothingToClaim);
+        }
+
+        if pending.is_empty() {
+            CLAIMS.remove(deps.storage, &info.sender);
+        } else {
+            CLAIMS.save(deps.storage, &info.sender, &pending)?;
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        Ok(Response::new()
+            .add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins(amount.u128(), config.denom),
+            })
+            .add_attribute("action", "claim")
+            .add_attribute("operator", info.sender)
+            .add_attribute("amount", amount))
+    }
+
+    pub fn set_weight(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        weight: Decimal,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        assert_owner(deps.storage, &info.sender)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let operator = deps.api.addr_validate(&operator)?;
+        let mut stake = STAKES
+            .may_load(deps.storage, &operator)?
+            .unwrap_or_default();
+        stake.weight = weight;
+        let power = save_stake(deps.storage, &config, &operator, &stake, env.block.height)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "set_weight")
+            .add_attribute("operator", operator)
+            .add_attribute("weight", weight.to_string())
+            .add_attribute("power", power))
+    }
+
+    /// Slashed tokens are burned, and the operator's power is reduced from the next block on.
+    /// Unbonding tokens stay slashable until released: the slash applies to them along with the bonded tokens,
+    /// and is taken from the bonded tokens first, then from the latest claims.
+    /// Slashing an operator with nothing bonded or unbonding is a no-op, so it never blocks the verifier.
+    pub fn slash(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        operator: String,
+        task_queue: String,
+        task_id: TaskId,
+        amount: SlashAmount,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        if !SLASHERS.has(deps.storage, &info.sender) {
+            return Err(ContractError::Unauthorized);
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let operator = deps.api.addr_validate(&operator)?;
+        let mut stake = STAKES
+            .may_load(deps.storage, &operator)?
+            .unwrap_or_default();
+        let mut claims = CLAIMS
+            .may_load(deps.storage, &operator)?
+            .unwrap_or_default();
+        let unbonding: Uint128 = claims
+            .iter()
+            .filter(|claim| claim.release_at > env.block.time)
+            .map(|claim| claim.amount)
+            .sum();
+        let slashed = amount.apply(stake.bonded + unbonding);
+
+        let from_bonded = slashed.min(stake.bonded);
+        stake.bonded -= from_bonded;
+        let power = save_stake(deps.storage, &config, &operator, &stake, env.block.height)?;
+
+        // Claims are stored in release order, so the latest ones come last
+        let mut from_claims = slashed - from_bonded;
+        if !from_claims.is_zero() {
+            for claim in claims
+                .iter_mut()
+                .rev()
+                .filter(|claim| claim.release_at > env.block.time)
+            {
+                let cut = from_claims.min(claim.amount);
+                claim.amount -= cut;
+                from_claims -= cut;
+            }
+            claims.retain(|claim| !claim.amount.is_zero());
+            if claims.is_empty() {
+                CLAIMS.remove(deps.storage, &operator);
+            } else {
+                CLAIMS.save(deps.storage, &operator, &claims)?;
+            }
+        }
+
+        let mut res = Response::new();
+        if !slashed.is_zero() {
+            res = res.add_message(BankMsg::Burn {
+                amount: coins(slashed.u128(), config.denom),
+            });
+        }
+
+        Ok(res
+            .add_attribute("action", "slash")
+            .add_attribute("operator", operator)
+            .add_attribute("slasher", info.sender)
+            .add_attribute("task_queue", task_queue)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("amount", slashed)
+            .add_attribute("power", power))
+    }
+
+    pub fn update_slashers(
+        deps: DepsMut,
+        info: MessageInfo,
+        to_add: Option<Vec<String>>,
+        to_remove: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        assert_owner(deps.storage, &info.sender)?;
+
+        for addr in to_add.unwrap_or_default() {
+            let addr = deps.api.addr_validate(&addr)?;
+            SLASHERS.save(deps.storage, &addr, &())?;
+        }
+        for addr in to_remove.unwrap_or_default() {
+            let addr = deps.api.addr_validate(&addr)?;
+            SLASHERS.remove(deps.storage, &addr);
+        }
+
+        Ok(Response::new().add_attribute("action", "update_slashers"))
+    }
+}
+
+// Power is checkpointed at the height it changes, so a query at height `h`
+// returns the power at the start of block `h`, before any changes made in it.
+mod query {
+    use super::*;
+
+    use cosmwasm_std::Order;
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        AllVotersResponse, ClaimsResponse, ConfigResponse, OperatorResponse, SlashersResponse,
+        TotalPowerResponse, VoterInfo, VotingPowerResponse,
+    };
+    use crate::state::{CLAIMS, POWER, SLASHERS, STAKES};
+
+    pub fn voting_power(
+        deps: Deps,
+        env: Env,
+        address: String,
+        height: Option<u64>,
+    ) -> StdResult<VotingPowerResponse> {
+        let addr = deps.api.addr_validate(&address)?;
+        let power = match height {
+            Some(height) => POWER.may_load_at_height(deps.storage, &addr, height)?,
+            None => POWER.may_load(deps.storage, &addr)?,
+        }
+        .unwrap_or_default();
+        let height = height.unwrap_or(env.block.height);
+        Ok(VotingPowerResponse { power, height })
+    }
+
+    pub fn total_power(deps: Deps, env: Env, height: Option<u64>) -> StdResult<TotalPowerResponse> {
+        let power = match height {
+            Some(height) => TOTAL_POWER.may_load_at_height(deps.storage, height)?,
+            None => TOTAL_POWER.may_load(deps.storage)?,
+        }
+        .unwrap_or_default();
+        let height = height.unwrap_or(env.block.height);
+        Ok(TotalPowerResponse { power, height })
+    }
+
+    pub fn all_voters(deps: Deps, _env: Env) -> StdResult<AllVotersResponse> {
+        let voters = POWER
+            .range(deps.storage, None, None, Order::Ascending)
+            .map(|r| {
+                r.map(|(addr, power)| VoterInfo {
+                    power,
+                    address: addr.into_string(),
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(AllVotersResponse { voters })
+    }
+
+    pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
+        let Config {
+            denom,
+            min_bond,
+            unbonding_period,
+        } = CONFIG.load(deps.storage)?;
+        Ok(ConfigResponse {
+            denom,
+            min_bond,
+            unbonding_period,
+        })
+    }
+
+    pub fn operator(deps: Deps, address: String) -> StdResult<OperatorResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let stake = STAKES.may_load(deps.storage, &address)?.unwrap_or_default();
+        let power = POWER.may_load(deps.storage, &address)?.unwrap_or_default();
+        Ok(OperatorResponse {
+            address,
+            bonded: stake.bonded,
+            weight: stake.weight,
+            power,
+        })
+    }
+
+    pub fn claims(deps: Deps, address: String) -> StdResult<ClaimsResponse> {
+        let address = deps.api.addr_validate(&address)?;
+        let claims = CLAIMS.may_load(deps.storage, &address)?.unwrap_or_default();
+        Ok(ClaimsResponse { claims })
+    }
+
+    pub fn slashers(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<SlashersResponse> {
+        let limit = limit.unwrap_or(30);
+        let binding = start_after
+            .map(|x| deps.api.addr_validate(&x))
+            .transpose()?;
+        let start_after = binding.as_ref().map(Bound::exclusive);
+
+        let addrs = SLASHERS
+            .keys(deps.storage, start_after, None, Order::Ascending)
+            .take(limit as usize)
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(SlashersResponse { addrs })
+    }
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::{StdError, Uint128};
+use cw_ownable::OwnershipError;
+use cw_utils::PaymentError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
+    #[error("Unauthorized")]
+    Unauthorized,
+
+    #[error("Cannot unbond {requested}, only {bonded} is bonded")]
+    InsufficientBond { bonded: Uint128, requested: Uint128 },
+
+    #[error("Amount must be greater than zero")]
+    ZeroAmount,
+
+    #[error("No claims are ready to be released")]
+    NothingToClaim,
+}
@@ -0,0 +1,150 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Decimal, Timestamp, Uint128};
+use cw_orch::{ExecuteFns, QueryFns};
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+use lavs_apis::time::Duration;
+
+// This pulls in the slashing API for verifiers
+pub use lavs_apis::interfaces::slashing::{SlashAmount, SlashExecuteMsg, SlashExecuteMsgFns};
+// This pulls in the queries every operator group must support
+pub use lavs_apis::interfaces::voting::{
+    AllVotersResponse, QueryMsg as VotingQueryMsg, QueryMsgFns as VotingQueryMsgFns,
+    TotalPowerResponse, VoterInfo, VotingPowerResponse,
+};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The native denom operators must bond to join
+    pub denom: String,
+    /// The minimum amount an operator must have bonded to get any voting power
+    pub min_bond: Uint128,
+    /// How long unbonded tokens are locked before they can be claimed
+    pub unbonding_period: Duration,
+    /// The address that can adjust operator weights.
+    ///
+    /// Defaults to the message sender during initialization.
+    pub owner: Option<String>,
+}
+
+#[cw_serde]
+#[serde(untagged)]
+pub enum ExecuteMsg {
+    /// Slash and any other public APIs in the interface
+    Api(SlashExecuteMsg),
+    /// The messages unique to this contract implementation
+    Custom(CustomExecuteMsg),
+}
+
+#[cw_ownable_execute]
+#[cw_serde]
+#[derive(ExecuteFns)]
+#[cw_orch(disable_fields_sorting)]
+pub enum CustomExecuteMsg {
+    /// Bond the sent tokens, joining the operator set if `min_bond` is reached
+    #[cw_orch(payable)]
+    Bond {},
+    /// Start unbonding tokens. They stop counting towards voting power immediately,
+    /// and can be claimed once the unbonding period has passed.
+    /// Unbonding below `min_bond` leaves the operator set.
+    Unbond { amount: Uint128 },
+    /// Withdraw all unbonded tokens whose unbonding period has passed
+    Claim {},
+    /// Owner-only: set the multiplier applied to an operator's bonded tokens.
+    /// A weight of zero removes the operator's voting power without touching its stake.
+    SetWeight { operator: String, weight: Decimal },
+    /// Owner-only: update the contracts allowed to slash operators
+    UpdateSlashers {
+        to_add: Option<Vec<String>>,
+        to_remove: Option<Vec<String>>,
+    },
+}
+
+impl From<SlashExecuteMsg> for ExecuteMsg {
+    fn from(value: SlashExecuteMsg) -> Self {
+        Self::Api(value)
+    }
+}
+
+impl From<CustomExecuteMsg> for ExecuteMsg {
+    fn from(value: CustomExecuteMsg) -> Self {
+        Self::Custom(value)
+    }
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[query_responses(nested)]
+#[serde(untagged)]
+pub enum QueryMsg {
+    /// The voting APIs shared by all operator contracts
+    Api(VotingQueryMsg),
+    /// The messages unique to this implementation
+    Custom(CustomQueryMsg),
+}
+
+#[cw_ownable_query]
+#[cw_serde]
+#[derive(QueryFns)]
+#[cw_orch(disable_fields_sorting)]
+#[derive(QueryResponses)]
+pub enum CustomQueryMsg {
+    /// Get the contract configuration
+    #[returns(ConfigResponse)]
+    Config {},
+    /// Get the bonded stake, weight and current power of an operator
+    #[returns(OperatorResponse)]
+    Operator { address: String },
+    /// List the pending claims of an address
+    #[returns(ClaimsResponse)]
+    Claims { address: String },
+    /// List the contracts allowed to slash operators
+    #[returns(SlashersResponse)]
+    Slashers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+impl From<VotingQueryMsg> for QueryMsg {
+    fn from(value: VotingQueryMsg) -> Self {
+        Self::Api(value)
+    }
+}
+
+impl From<CustomQueryMsg> for QueryMsg {
+    fn from(value: CustomQueryMsg) -> Self {
+        Self::Custom(value)
+    }
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub denom: String,
+    pub min_bond: Uint128,
+    pub unbonding_period: Duration,
+}
+
+#[cw_serde]
+pub struct OperatorResponse {
+    pub address: Addr,
+    pub bonded: Uint128,
+    pub weight: Decimal,
+    /// The current voting power
+    pub power: Uint128,
+}
+
+#[cw_serde]
+pub struct ClaimsResponse {
+    pub claims: Vec<Claim>,
+}
+
+#[cw_serde]
+pub struct Claim {
+    pub amount: Uint128,
+    pub release_at: Timestamp,
+}
+
+#[cw_serde]
+pub struct SlashersResponse {
+    pub addrs: Vec<Addr>,
+}
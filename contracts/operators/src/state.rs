@@ -0,0 +1,96 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotItem, SnapshotMap, Strategy};
+use lavs_apis::time::Duration;
+
+use crate::msg::Claim;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+/// Bonded tokens and weight of every operator that ever bonded
+pub const STAKES: Map<&Addr, Stake> = Map::new("stakes");
+
+/// Pending unbonded tokens, per address
+pub const CLAIMS: Map<&Addr, Vec<Claim>> = Map::new("claims");
+
+/// Contracts allowed to slash operators
+pub const SLASHERS: Map<&Addr, ()> = Map::new("slashers");
+
+/// Voting power of each operator, checkpointed on every change so it can be
+/// queried at the height a task was created.
+pub const POWER: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "power",
+    "power__checkpoints",
+    "power__changelog",
+    Strategy::EveryBlock,
+);
+
+/// Sum of all entries in `POWER`, checkpointed the same way
+pub const TOTAL_POWER: SnapshotItem<Uint128> = SnapshotItem::new(
+    "total_power",
+    "total_power__checkpoints",
+    "total_power__changelog",
+    Strategy::EveryBlock,
+);
+
+#[cw_serde]
+pub struct Config {
+    pub denom: String,
+    pub min_bond: Uint128,
+    pub unbonding_period: Duration,
+}
+
+#[cw_serde]
+pub struct Stake {
+    pub bonded: Uint128,
+    pub weight: Decimal,
+}
+
+impl Default for Stake {
+    fn default() -> Self {
+        Stake {
+            bonded: Uint128::zero(),
+            weight: Decimal::one(),
+        }
+    }
+}
+
+impl Stake {
+    /// Voting power derived from this stake. Anything below `min_bond` doesn't count.
+    pub fn power(&self, min_bond: Uint128) -> Uint128 {
+        if self.bonded.is_zero() || self.bonded < min_bond {
+            Uint128::zero()
+        } else {
+            self.bonded.mul_floor(self.weight)
+        }
+    }
+}
+
+/// Stores the stake and updates the power snapshots at the given height.
+/// Returns the new voting power of the operator.
+pub fn save_stake(
+    storage: &mut dyn Storage,
+    config: &Config,
+    addr: &Addr,
+    stake: &Stake,
+    height: u64,
+) -> StdResult<Uint128> {
+    STAKES.save(storage, addr, stake)?;
+
+    let old_power = POWER.may_load(storage, addr)?.unwrap_or_default();
+    let new_power = stake.power(config.min_bond);
+    if old_power == new_power {
+        return Ok(new_power);
+    }
+
+    if new_power.is_zero() {
+        POWER.remove(storage, addr, height)?;
+    } else {
+        POWER.save(storage, addr, &new_power, height)?;
+    }
+
+    let total = TOTAL_POWER.may_load(storage)?.unwrap_or_default();
+    TOTAL_POWER.save(storage, &(total - old_power + new_power), height)?;
+
+    Ok(new_power)
+}
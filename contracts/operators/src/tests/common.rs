@@ -0,0 +1,337 @@
+use cosmwasm_std::{coins, Decimal, Uint128};
+use cw_orch::environment::{CwEnv, QueryHandler};
+use cw_orch::prelude::*;
+use cw_ownable::OwnershipError;
+use lavs_apis::id::TaskId;
+use lavs_apis::time::Duration;
+
+use lavs_orch::{Addressable, AltSigner};
+
+use crate::error::ContractError;
+use crate::interface::Contract;
+use crate::msg::{
+    CustomExecuteMsgFns, CustomQueryMsgFns, InstantiateMsg, SlashAmount, SlashExecuteMsgFns,
+    VotingQueryMsgFns,
+};
+
+pub const BECH_PREFIX: &str = "layer";
+pub const DENOM: &str = "uslay";
+
+const MIN_BOND: u128 = 100;
+const UNBONDING_SECONDS: u64 = 100;
+
+pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> Contract<Chain> {
+    let contract = Contract::new(chain);
+    contract.upload().unwrap();
+    contract.instantiate(&msg, None, &[]).unwrap();
+    contract
+}
+
+fn default_setup<Chain: CwEnv>(chain: Chain) -> Contract<Chain> {
+    let msg = InstantiateMsg {
+        denom: DENOM.to_string(),
+        min_bond: Uint128::new(MIN_BOND),
+        unbonding_period: Duration::new_seconds(UNBONDING_SECONDS),
+        owner: None,
+    };
+    setup(chain, msg)
+}
+
+/// Bonding and unbonding change the power from the next block on,
+/// while historical queries keep returning the power at that height.
+pub fn power_is_height_indexed<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let op2 = chain.alt_signer(2);
+    let contract = default_setup(chain.clone());
+
+    let start = current_height(&chain);
+
+    // op2 bonds below the minimum, so it gets no power
+    contract.call_as(&op1).bond(&coins(200, DENOM)).unwrap();
+    contract.call_as(&op2).bond(&coins(50, DENOM)).unwrap();
+
+    let total = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total.power, Uint128::new(200));
+    let power = contract
+        .voting_power_at_height(op2.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::zero());
+
+    // changes only count from the start of the next block
+    let total = contract.total_power_at_height(Some(start)).unwrap();
+    assert_eq!(total.power, Uint128::zero());
+    assert_eq!(total.height, start);
+
+    chain.next_block().unwrap();
+    let one = current_height(&chain);
+
+    // op2 tops up and joins the set
+    contract.call_as(&op2).bond(&coins(100, DENOM)).unwrap();
+    chain.next_block().unwrap();
+    let two = current_height(&chain);
+
+    // op1 leaves by unbonding below the minimum
+    contract.call_as(&op1).unbond(150u128).unwrap();
+    chain.next_block().unwrap();
+
+    let total = contract.total_power_at_height(Some(one)).unwrap();
+    assert_eq!(total.power, Uint128::new(200));
+    let total = contract.total_power_at_height(Some(two)).unwrap();
+    assert_eq!(total.power, Uint128::new(350));
+    let total = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total.power, Uint128::new(150));
+
+    let power = contract
+        .voting_power_at_height(op1.addr().into_string(), Some(two))
+        .unwrap();
+    assert_eq!(power.power, Uint128::new(200));
+    assert_eq!(power.height, two);
+    let power = contract
+        .voting_power_at_height(op1.addr().into_string(), None)
+        .unwrap();
+    assert_eq!(power.power, Uint128::zero());
+
+    // only the remaining operator is listed
+    let voters = contract.all_voters().unwrap().voters;
+    assert_eq!(voters.len(), 1);
+    assert_eq!(voters[0].address, op2.addr().into_string());
+    assert_eq!(voters[0].power, Uint128::new(150));
+}
+
+/// Unbonded tokens are locked for the unbonding period before they can be claimed
+pub fn unbonding_period_is_enforced<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let contract = default_setup(chain.clone());
+
+    contract.call_as(&op1).bond(&coins(300, DENOM)).unwrap();
+
+    let err = contract.call_as(&op1).unbond(500u128).unwrap_err();
+    assert!(
+        err.root().to_string().contains(
+            &ContractError::InsufficientBond {
+                bonded: Uint128::new(300),
+                requested: Uint128::new(500)
+            }
+            .to_string()
+        ),
+        "Unexpected error: {}",
+        err.root()
+    );
+
+    contract.call_as(&op1).unbond(100u128).unwrap();
+    let claims = contract.claims(op1.addr().into_string()).unwrap().claims;
+    assert_eq!(claims.len(), 1);
+    assert_eq!(claims[0].amount, Uint128::new(100));
+
+    let err = contract.call_as(&op1).claim().unwrap_err();
+    assert!(
+        err.root()
+            .to_string()
+            .contains(&ContractError::NothingToClaim.to_string()),
+        "Unexpected error: {}",
+        err.root()
+    );
+
+    let before = balance(&chain, &op1.addr());
+    chain.wait_seconds(UNBONDING_SECONDS).unwrap();
+    contract.call_as(&op1).claim().unwrap();
+    let after = balance(&chain, &op1.addr());
+    assert_eq!(after, before + Uint128::new(100));
+
+    let claims = contract.claims(op1.addr().into_string()).unwrap().claims;
+    assert!(claims.is_empty());
+
+    // the rest is still bonded
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::new(200));
+    assert_eq!(operator.power, Uint128::new(200));
+}
+
+/// Only the owner can adjust the weight applied to an operator's stake
+pub fn owner_sets_weights<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let contract = default_setup(chain.clone());
+
+    contract.call_as(&op1).bond(&coins(200, DENOM)).unwrap();
+
+    let err = contract
+        .call_as(&op1)
+        .set_weight(op1.addr().into_string(), Decimal::percent(300))
+        .unwrap_err();
+    assert!(
+        err.root()
+            .to_string()
+            .contains(&ContractError::Ownership(OwnershipError::NotOwner).to_string()),
+        "Unexpected error: {}",
+        err.root()
+    );
+
+    contract
+        .set_weight(op1.addr().into_string(), Decimal::percent(150))
+        .unwrap();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::new(200));
+    assert_eq!(operator.weight, Decimal::percent(150));
+    assert_eq!(operator.power, Uint128::new(300));
+    let total = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total.power, Uint128::new(300));
+
+    // a zero weight removes the operator without touching its stake
+    contract
+        .set_weight(op1.addr().into_string(), Decimal::zero())
+        .unwrap();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::new(200));
+    assert_eq!(operator.power, Uint128::zero());
+    let total = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total.power, Uint128::zero());
+    assert!(contract.all_voters().unwrap().voters.is_empty());
+}
+
+/// Only registered slashers can slash, and slashing reduces both stake and power
+pub fn slashers_can_slash<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let slasher = chain.alt_signer(3);
+    let contract = default_setup(chain.clone());
+
+    contract.call_as(&op1).bond(&coins(400, DENOM)).unwrap();
+
+    let err = contract
+        .call_as(&slasher)
+        .slash(
+            op1.addr().into_string(),
+            slasher.addr().into_string(),
+            TaskId::new(1),
+            SlashAmount::Fraction(Decimal::percent(50)),
+        )
+        .unwrap_err();
+    assert!(
+        err.root()
+            .to_string()
+            .contains(&ContractError::Unauthorized.to_string()),
+        "Unexpected error: {}",
+        err.root()
+    );
+
+    contract
+        .update_slashers(Some(vec![slasher.addr().into_string()]), None)
+        .unwrap();
+    let slashers = contract.slashers(None, None).unwrap().addrs;
+    assert_eq!(slashers, vec![slasher.addr()]);
+
+    contract
+        .call_as(&slasher)
+        .slash(
+            op1.addr().into_string(),
+            slasher.addr().into_string(),
+            TaskId::new(1),
+            SlashAmount::Fraction(Decimal::percent(50)),
+        )
+        .unwrap();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::new(200));
+    assert_eq!(operator.power, Uint128::new(200));
+
+    // fixed amounts are capped at the stake, which drops the operator below the minimum
+    contract
+        .call_as(&slasher)
+        .slash(
+            op1.addr().into_string(),
+            slasher.addr().into_string(),
+            TaskId::new(2),
+            SlashAmount::Fixed(Uint128::new(1_000)),
+        )
+        .unwrap();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::zero());
+    assert_eq!(operator.power, Uint128::zero());
+    let total = contract.total_power_at_height(None).unwrap();
+    assert_eq!(total.power, Uint128::zero());
+}
+
+/// Unbonding tokens can be slashed until they are released, after the bonded ones
+pub fn unbonding_tokens_are_slashable<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op1 = chain.alt_signer(1);
+    let slasher = chain.alt_signer(3);
+    let contract = default_setup(chain.clone());
+    contract
+        .update_slashers(Some(vec![slasher.addr().into_string()]), None)
+        .unwrap();
+
+    // the first claim is released by the time of the slash, the second one isn't
+    contract.call_as(&op1).bond(&coins(400, DENOM)).unwrap();
+    contract.call_as(&op1).unbond(100u128).unwrap();
+    chain.wait_seconds(UNBONDING_SECONDS / 2).unwrap();
+    contract.call_as(&op1).unbond(100u128).unwrap();
+    chain.wait_seconds(UNBONDING_SECONDS / 2 + 10).unwrap();
+
+    contract
+        .call_as(&slasher)
+        .slash(
+            op1.addr().into_string(),
+            slasher.addr().into_string(),
+            TaskId::new(1),
+            SlashAmount::Fixed(Uint128::new(250)),
+        )
+        .unwrap();
+    let operator = contract.operator(op1.addr().into_string()).unwrap();
+    assert_eq!(operator.bonded, Uint128::zero());
+    let claims = contract.claims(op1.addr().into_string()).unwrap().claims;
+    let amounts: Vec<_> = claims.iter().map(|claim| claim.amount.u128()).collect();
+    assert_eq!(amounts, vec![100, 50]);
+
+    // a fraction applies to the bonded and unbonding tokens together
+    contract
+        .call_as(&slasher)
+        .slash(
+            op1.addr().into_string(),
+            slasher.addr().into_string(),
+            TaskId::new(2),
+            SlashAmount::Fraction(Decimal::one()),
+        )
+        .unwrap();
+    let claims = contract.claims(op1.addr().into_string()).unwrap().claims;
+    let amounts: Vec<_> = claims.iter().map(|claim| claim.amount.u128()).collect();
+    assert_eq!(amounts, vec![100]);
+
+    // only the released claim is left to withdraw
+    let before = balance(&chain, &op1.addr());
+    chain.wait_seconds(UNBONDING_SECONDS).unwrap();
+    contract.call_as(&op1).claim().unwrap();
+    assert_eq!(balance(&chain, &op1.addr()), before + Uint128::new(100));
+}
+
+#[track_caller]
+fn current_height(chain: &impl QueryHandler) -> u64 {
+    chain.block_info().unwrap().height
+}
+
+#[track_caller]
+fn balance<C: CwEnv>(chain: &C, addr: &Addr) -> Uint128 {
+    chain
+        .balance(addr, Some(DENOM.to_string()))
+        .unwrap()
+        .first()
+        .map(|c| c.amount)
+        .unwrap_or_default()
+}
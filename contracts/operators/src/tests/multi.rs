@@ -0,0 +1,45 @@
+use cosmwasm_std::coins;
+use cw_orch::prelude::*;
+use lavs_orch::AltSigner;
+
+use super::common::{BECH_PREFIX, DENOM};
+
+fn funded_chain() -> MockBech32 {
+    let chain = MockBech32::new(BECH_PREFIX);
+    for i in 1..=2 {
+        chain
+            .add_balance(&chain.alt_signer(i), coins(10_000, DENOM))
+            .unwrap();
+    }
+    chain
+}
+
+#[test]
+fn power_is_height_indexed() {
+    let chain = funded_chain();
+    super::common::power_is_height_indexed(chain);
+}
+
+#[test]
+fn unbonding_period_is_enforced() {
+    let chain = funded_chain();
+    super::common::unbonding_period_is_enforced(chain);
+}
+
+#[test]
+fn owner_sets_weights() {
+    let chain = funded_chain();
+    super::common::owner_sets_weights(chain);
+}
+
+#[test]
+fn slashers_can_slash() {
+    let chain = funded_chain();
+    super::common::slashers_can_slash(chain);
+}
+
+#[test]
+fn unbonding_tokens_are_slashable() {
+    let chain = funded_chain();
+    super::common::unbonding_tokens_are_slashable(chain);
+}
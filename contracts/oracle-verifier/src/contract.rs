@@ -1,25 +1,29 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Order, Response,
-    StdResult,
+    from_json, to_json_binary, Addr, Binary, Decimal, Deps, DepsMut, Env, Event, MessageInfo,
+    Reply, Response, StdResult,
 };
 use cw2::set_contract_version;
 use lavs_apis::verifier_simple::OperatorVoteInfoResponse;
+use lavs_helpers::json_schema;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::{Config, CONFIG, SLASHED_OPERATORS, VOTES};
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, SlashAmount};
+use crate::state::{AggregationStrategy, Config, CONFIG, MAX_TWAP_TASKS, VOTES};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Slashes are sent as submessages replying on error, so a failed slash doesn't revert the vote
+const SLASH_REPLY_ID: u64 = 0;
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let fields = [
@@ -44,6 +48,23 @@ pub fn instantiate(
             msg.allowed_spread,
         ));
     }
+    match msg.slash_amount {
+        Some(SlashAmount::Fixed(amount)) if amount.is_zero() => {
+            return Err(ContractError::InvalidSlashAmount)
+        }
+        Some(SlashAmount::Fraction(fraction))
+            if fraction.is_zero() || fraction > Decimal::one() =>
+        {
+            return Err(ContractError::InvalidSlashAmount)
+        }
+        _ => {}
+    }
+    let aggregation = msg.aggregation.unwrap_or_default();
+    validate_aggregation(&aggregation)?;
+    if let Some(schema) = &msg.result_schema {
+        json_schema::check_schema(schema)?;
+    }
+
     let op_addr = deps.api.addr_validate(&msg.operator_contract)?;
     let config = Config {
         operator_contract: op_addr,
@@ -51,15 +72,36 @@ pub fn instantiate(
         allowed_spread: msg.allowed_spread,
         slashable_spread: msg.slashable_spread,
         required_percentage: msg.required_percentage,
+        slash_amount: msg.slash_amount,
+        aggregation,
+        max_result_bytes: msg.max_result_bytes,
+        result_schema: msg.result_schema,
     };
 
     CONFIG.save(deps.storage, &config)?;
 
+    let owner = msg.owner.unwrap_or(info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::new())
 }
 
+fn validate_aggregation(aggregation: &AggregationStrategy) -> Result<(), ContractError> {
+    match *aggregation {
+        AggregationStrategy::TrimmedMean { trim_percent }
+            if trim_percent >= Decimal::percent(50) =>
+        {
+            Err(ContractError::InvalidTrimPercent(trim_percent))
+        }
+        AggregationStrategy::Twap { tasks } if tasks == 0 || tasks > MAX_TWAP_TASKS => {
+            Err(ContractError::InvalidTwapTasks(MAX_TWAP_TASKS))
+        }
+        _ => Ok(()),
+    }
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
@@ -73,6 +115,21 @@ pub fn execute(
             task_id,
             result,
         } => execute::executed_task(deps, env, info, task_queue_contract, task_id, result),
+        ExecuteMsg::UpdateAggregation { aggregation } => {
+            execute::update_aggregation(deps, info, aggregation)
+        }
+        ExecuteMsg::RecordMissed {
+            task_queue_contract,
+            task_id,
+            operators,
+        } => execute::record_missed(deps, info, task_queue_contract, task_id, operators),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+
+            let event = Event::new("update_ownership").add_attributes(ownership.into_attributes());
+
+            Ok(Response::new().add_event(event))
+        }
     }
 }
 
@@ -92,6 +149,31 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             task_contract,
             task_id,
         )?)?),
+        QueryMsg::ListTaskVotes {
+            task_contract,
+            task_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query::list_task_votes(
+            deps,
+            task_contract,
+            task_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ListTasks {
+            task_contract,
+            status,
+            start_after,
+            limit,
+        } => to_json_binary(&query::list_tasks(
+            deps,
+            env,
+            task_contract,
+            status,
+            start_after,
+            limit,
+        )?),
         QueryMsg::OperatorVote {
             task_contract,
             task_id,
@@ -102,27 +184,50 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             task_id,
             operator,
         )?)?),
-        QueryMsg::SlashableOperators {} => {
-            let slashed_operators: Vec<Addr> = SLASHED_OPERATORS
-                .keys(deps.storage, None, None, Order::Ascending)
-                .collect::<StdResult<Vec<_>>>()?;
-            to_json_binary(&slashed_operators)
+        QueryMsg::SlashableOperators { start_after, limit } => Ok(to_json_binary(
+            &query::slashable_operators(deps, start_after, limit)?,
+        )?),
+        QueryMsg::InvalidSubmissions { operator } => {
+            to_json_binary(&query::invalid_submissions(deps, operator)?)
+        }
+        QueryMsg::OperatorStats { operator } => {
+            to_json_binary(&query::operator_stats(deps, operator)?)
         }
+        QueryMsg::ListOperatorStats { start_after, limit } => {
+            to_json_binary(&query::list_operator_stats(deps, start_after, limit)?)
+        }
+        QueryMsg::LatestPrice { feed } => to_json_binary(&query::latest_price(deps, feed)?),
+        QueryMsg::AllPrices { start_after, limit } => {
+            to_json_binary(&query::all_prices(deps, start_after, limit)?)
+        }
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
     }
 }
 
 mod execute {
 
-    use cosmwasm_std::{to_json_binary, Decimal, Order, Uint128, WasmMsg};
+    use std::collections::BTreeSet;
+
+    use cosmwasm_std::{to_json_binary, Decimal, Order, SubMsg, Timestamp, Uint128, WasmMsg};
+    use cw_ownable::assert_owner;
     use cw_utils::nonpayable;
     use lavs_apis::{
-        events::oracle_executed_event::{OracleExecutedEvent, OracleExecutionStatus},
+        events::{
+            invalid_result_event::InvalidResultEvent,
+            oracle_executed_event::{OracleExecutedEvent, OracleExecutionStatus},
+        },
         id::TaskId,
+        interfaces::slashing::SlashExecuteMsg,
         tasks::{TaskExecuteMsg, TaskStatus},
+        verifier_simple::VerifierError,
     };
-    use lavs_helpers::verifier::ensure_valid_vote;
+    use lavs_helpers::verifier::{ensure_valid_vote, had_voting_power};
 
-    use crate::state::{record_vote, OperatorVote, SLASHED_OPERATORS, TASKS, VOTES};
+    use crate::state::{
+        record_invalid_submission, record_price, record_vote, save_slash, LatestPrice,
+        OperatorPrices, OperatorVote, PricePoint, PriceResult, SlashRecord, LATEST_PRICES,
+        OPERATOR_STATS, PRICE_HISTORY, TASKS, VOTES,
+    };
 
     use super::*;
 
@@ -142,7 +247,12 @@ mod execute {
 
         let config = CONFIG.load(deps.storage)?;
 
-        // operator allowed to vote and hasn't voted yet
+        // operator hasn't voted yet
+        if VOTES.has(deps.storage, (&task_queue, task_id, &operator)) {
+            return Err(ContractError::OperatorAlreadyVoted(operator.to_string()));
+        }
+
+        // operator allowed to vote
         let (mut task_data, power) = match ensure_valid_vote(
             deps.branch(),
             &env,
@@ -156,17 +266,21 @@ mod execute {
             None => return Ok(Response::default()),
         };
 
+        let prices = match config.parse_prices(&result) {
+            Ok(prices) => prices,
+            Err(err) => return reject_result(deps, &operator, task_queue_contract, task_id, err),
+        };
+
         // Update the vote and check the total power on this result, also recording the operators vote
-        let tally = record_vote(
-            deps.storage,
-            &task_queue,
-            task_id,
-            &operator,
-            &result,
+        let vote = OperatorPrices {
             power,
-        )?;
+            prices,
+            result,
+        };
+        record_vote(deps.storage, &task_queue, task_id, &operator, &vote)?;
+        OPERATOR_STATS.record_vote(deps.storage, &operator, task_data.latency(&env))?;
 
-        let all_votes: Vec<(Addr, OperatorVote)> = VOTES
+        let all_votes: Vec<(Addr, OperatorPrices)> = VOTES
             .prefix((&task_queue, task_id))
             .range(deps.storage, None, None, Order::Ascending)
             .collect::<StdResult<Vec<_>>>()?;
@@ -179,6 +293,7 @@ mod execute {
             let event = OracleExecutedEvent {
                 task_id,
                 status: OracleExecutionStatus::VoteStored,
+                feed: None,
                 new_price: None,
                 task_queue_contract: task_queue_contract.clone(),
             };
@@ -187,42 +302,126 @@ mod execute {
 
         let config = CONFIG.load(deps.storage)?;
 
-        let (median, slashable_operators, is_threshold_met) =
-            process_votes(&all_votes, tally, &config)?;
+        // every feed is aggregated over the operators who submitted a price for it, and measured
+        // against the power of all votes on the task. Feeds submitted by less than the threshold
+        // are left out, so a single operator cannot publish a feed of its own, and the task is
+        // only completed once the threshold is met on all the others
+        let feeds: BTreeSet<&String> = all_votes
+            .iter()
+            .flat_map(|(_, vote)| vote.prices.keys())
+            .collect();
+        let mut results = Vec::with_capacity(feeds.len());
+        let mut all_thresholds_met = true;
+        for feed in feeds {
+            let votes = feed_votes(&all_votes, feed);
+            let feed_power: Uint128 = votes.iter().map(|(_, vote)| vote.power).sum();
+            if !is_threshold_met(feed_power, total_power, config.threshold_percent) {
+                continue;
+            }
+            let history = PRICE_HISTORY
+                .may_load(deps.storage, (&task_queue, feed.as_str()))?
+                .unwrap_or_default();
+            let (aggregate, slashable_operators, feed_threshold_met) =
+                process_votes(&votes, total_power, &config, &history, env.block.time)?;
+            all_thresholds_met &= feed_threshold_met;
+            results.push((feed, votes, aggregate, slashable_operators));
+        }
+
+        if all_thresholds_met && !results.is_empty() {
+            // operators are slashed once per task, with a record for every feed they were off on
+            let mut slashed: Vec<Addr> = vec![];
+            let mut outside_spread: BTreeSet<Addr> = BTreeSet::new();
+            let mut response = PriceResult::new();
+            for (feed, votes, aggregate, slashable_operators) in results {
+                let (allowed_minimum, allowed_maximum) =
+                    calculate_allowed_range(aggregate, config.allowed_spread);
+                outside_spread.extend(
+                    votes
+                        .iter()
+                        .filter(|(_, vote)| {
+                            vote.result < allowed_minimum || vote.result > allowed_maximum
+                        })
+                        .map(|(operator, _)| operator.clone()),
+                );
+
+                for operator in slashable_operators {
+                    // every slashable operator voted, so the price is always found
+                    let price = votes
+                        .iter()
+                        .find(|(addr, _)| *addr == operator)
+                        .map(|(_, vote)| vote.result)
+                        .unwrap_or_default();
+                    let record = SlashRecord {
+                        operator,
+                        task_queue: task_queue.clone(),
+                        task_id,
+                        feed: feed.clone(),
+                        price,
+                        median: aggregate,
+                        spread: calculate_spread(price, aggregate),
+                    };
+                    save_slash(deps.storage, &record)?;
+                    if !slashed.contains(&record.operator) {
+                        slashed.push(record.operator);
+                    }
+                }
 
-        if is_threshold_met {
-            for operator in slashable_operators {
-                noop_slash_validator(&mut deps, &operator)?;
+                // the history keeps the median of each task rather than the aggregate,
+                // so a TWAP doesn't average over its own previous results
+                let mut all_prices: Vec<Decimal> =
+                    votes.iter().map(|(_, vote)| vote.result).collect();
+                let point = PricePoint {
+                    price: calculate_median(&mut all_prices),
+                    time: env.block.time,
+                };
+                record_price(deps.storage, &task_queue, feed, point)?;
+
+                let latest = LatestPrice {
+                    price: aggregate,
+                    task_queue: task_queue.clone(),
+                    task_id,
+                    updated: env.block.time,
+                };
+                LATEST_PRICES.save(deps.storage, feed, &latest)?;
+
+                let event = OracleExecutedEvent {
+                    task_id,
+                    status: OracleExecutionStatus::ThresholdMet,
+                    feed: Some(feed.clone()),
+                    new_price: Some(aggregate),
+                    task_queue_contract: task_queue_contract.clone(),
+                };
+                resp = resp.add_event(event);
+
+                response.insert(feed.clone(), aggregate.to_string());
+            }
+
+            for operator in slashed {
+                if let Some(msg) = slash_operator(&config, operator, &task_queue, task_id)? {
+                    resp = resp.add_submessage(msg);
+                }
             }
 
             task_data.status = TaskStatus::Completed;
             TASKS.save(deps.storage, (&task_queue, task_id), &task_data)?;
-
-            let response = serde_json::json!(crate::state::PriceResult {
-                price: median.to_string()
-            });
+            record_completed_stats(deps.branch(), &all_votes, &outside_spread)?;
 
             let msg = WasmMsg::Execute {
                 contract_addr: task_queue.to_string(),
-                msg: to_json_binary(&TaskExecuteMsg::Complete { task_id, response })?,
+                msg: to_json_binary(&TaskExecuteMsg::Complete {
+                    task_id,
+                    response: serde_json::to_value(response)?,
+                })?,
                 funds: vec![],
             };
 
             resp = resp.add_message(msg);
-
-            let event = OracleExecutedEvent {
-                task_id,
-                status: OracleExecutionStatus::ThresholdMet,
-                new_price: Some(median),
-                task_queue_contract: task_queue_contract.clone(),
-            };
-
-            resp = resp.add_event(event);
         } else {
             // this event and the one above can be DRY'ed, will leave it for later
             let event = OracleExecutedEvent {
                 task_id,
                 status: OracleExecutionStatus::ThresholdNotMet,
+                feed: None,
                 new_price: None,
                 task_queue_contract: task_queue_contract.clone(),
             };
@@ -235,6 +434,93 @@ mod execute {
         Ok(resp)
     }
 
+    /// Counts the votes on the completed task as matching, unless outside the allowed spread on any feed.
+    /// The operators who didn't vote are counted separately, with `RecordMissed`.
+    fn record_completed_stats(
+        deps: DepsMut,
+        votes: &[(Addr, OperatorPrices)],
+        outside_spread: &BTreeSet<Addr>,
+    ) -> Result<(), ContractError> {
+        for (operator, _) in votes {
+            let matched = !outside_spread.contains(operator);
+            OPERATOR_STATS.record_result(deps.storage, operator, matched)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_missed(
+        deps: DepsMut,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+        operators: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let task_data = TASKS
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(VerifierError::TaskNotCompleted)?;
+        if task_data.status != TaskStatus::Completed {
+            return Err(VerifierError::TaskNotCompleted.into());
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut recorded = 0u32;
+        for operator in operators {
+            let operator = deps.api.addr_validate(&operator)?;
+            if VOTES.has(deps.storage, (&task_queue, task_id, &operator))
+                || !had_voting_power(
+                    deps.as_ref(),
+                    &config.operator_contract,
+                    &task_data,
+                    &operator,
+                )?
+            {
+                continue;
+            }
+            if OPERATOR_STATS.record_missed(deps.storage, &task_queue, task_id, &operator)? {
+                recorded += 1;
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "record_missed")
+            .add_attribute("task_queue", task_queue_contract)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("recorded", recorded.to_string()))
+    }
+
+    /// Counts the rejected result against the operator. This doesn't fail, as that would revert the count.
+    fn reject_result(
+        deps: DepsMut,
+        operator: &Addr,
+        task_queue: String,
+        task_id: TaskId,
+        err: ContractError,
+    ) -> Result<Response, ContractError> {
+        let invalid_submissions = record_invalid_submission(deps.storage, operator)?;
+        let event = InvalidResultEvent {
+            task_id,
+            task_queue,
+            operator: operator.to_string(),
+            reason: err.to_string(),
+            invalid_submissions,
+        };
+        Ok(Response::new().add_event(event))
+    }
+
+    /// The votes of the operators who submitted a price for the feed
+    pub(crate) fn feed_votes(
+        votes: &[(Addr, OperatorPrices)],
+        feed: &str,
+    ) -> Vec<(Addr, OperatorVote)> {
+        votes
+            .iter()
+            .filter_map(|(operator, vote)| Some((operator.clone(), vote.feed_vote(feed)?)))
+            .collect()
+    }
+
     pub(crate) fn calculate_median(values: &mut [Decimal]) -> Decimal {
         if values.is_empty() {
             return Decimal::zero();
@@ -251,6 +537,110 @@ mod execute {
         }
     }
 
+    /// Like `calculate_median`, with every price counting as much as its power
+    pub(crate) fn calculate_weighted_median(values: &mut [(Decimal, Uint128)]) -> Decimal {
+        let total_power: Uint128 = values.iter().map(|(_, power)| *power).sum();
+        if total_power.is_zero() {
+            let mut prices: Vec<Decimal> = values.iter().map(|(price, _)| *price).collect();
+            return calculate_median(&mut prices);
+        }
+
+        values.sort();
+
+        let mut power_below = Uint128::zero();
+        for (index, (price, power)) in values.iter().enumerate() {
+            power_below += *power;
+            if power_below * Uint128::new(2) > total_power {
+                return *price;
+            }
+            if power_below * Uint128::new(2) == total_power {
+                // exactly half of the power on each side, like an even number of equal votes,
+                // so take the middle of this price and the next one with power
+                let next = values[index + 1..]
+                    .iter()
+                    .find(|(_, power)| !power.is_zero())
+                    .map_or(*price, |(next, _)| *next);
+                return (*price + next) / Uint128::new(2u128);
+            }
+        }
+        // unreachable, the loop returns at the latest on the last price
+        values[values.len() - 1].0
+    }
+
+    /// The mean of the values after dropping `trim_percent` of them from each end, rounding down
+    pub(crate) fn calculate_trimmed_mean(values: &mut [Decimal], trim_percent: Decimal) -> Decimal {
+        if values.is_empty() {
+            return Decimal::zero();
+        }
+
+        values.sort();
+
+        let trimmed = (Decimal::from_ratio(values.len() as u128, 1u128) * trim_percent)
+            .to_uint_floor()
+            .u128() as usize;
+        // trim_percent is below 50%, so at least one value is kept
+        let kept = &values[trimmed..values.len() - trimmed];
+        let sum: Decimal = kept.iter().sum();
+        sum / Uint128::new(kept.len() as u128)
+    }
+
+    /// The average of the prices from `history` (oldest first) until `latest`, each weighted by the
+    /// time since the previous price. Falls back to the latest price if no time passed.
+    pub(crate) fn calculate_twap(history: &[PricePoint], latest: &PricePoint) -> Decimal {
+        let mut weighted_sum = Decimal::zero();
+        let mut total_seconds = 0u64;
+        let mut previous_time = match history.first() {
+            Some(point) => point.time,
+            None => return latest.price,
+        };
+        for point in history[1..].iter().chain(std::iter::once(latest)) {
+            let seconds = point.time.seconds().saturating_sub(previous_time.seconds());
+            weighted_sum += point.price * Decimal::from_ratio(seconds, 1u64);
+            total_seconds += seconds;
+            previous_time = point.time;
+        }
+
+        if total_seconds == 0 {
+            return latest.price;
+        }
+        weighted_sum / Decimal::from_ratio(total_seconds, 1u64)
+    }
+
+    /// Combines the votes into a price with the strategy. `history` is the median price of the
+    /// previous completed tasks of the task queue, oldest first.
+    pub(crate) fn aggregate_votes(
+        votes: &[(Addr, OperatorVote)],
+        aggregation: &AggregationStrategy,
+        history: &[PricePoint],
+        now: Timestamp,
+    ) -> Decimal {
+        let mut all_prices: Vec<Decimal> = votes.iter().map(|(_, vote)| vote.result).collect();
+
+        match *aggregation {
+            AggregationStrategy::Median => calculate_median(&mut all_prices),
+            AggregationStrategy::WeightedMedian => {
+                let mut weighted: Vec<(Decimal, Uint128)> = votes
+                    .iter()
+                    .map(|(_, vote)| (vote.result, vote.power))
+                    .collect();
+                calculate_weighted_median(&mut weighted)
+            }
+            AggregationStrategy::TrimmedMean { trim_percent } => {
+                calculate_trimmed_mean(&mut all_prices, trim_percent)
+            }
+            AggregationStrategy::Twap { tasks } => {
+                let latest = PricePoint {
+                    price: calculate_median(&mut all_prices),
+                    time: now,
+                };
+                // this task is one of the averaged tasks
+                let previous = (tasks as usize).saturating_sub(1);
+                let start = history.len().saturating_sub(previous);
+                calculate_twap(&history[start..], &latest)
+            }
+        }
+    }
+
     pub(crate) fn calculate_allowed_range(median: Decimal, spread: Decimal) -> (Decimal, Decimal) {
         let allowed_minimum = median * (Decimal::one() - spread);
         let allowed_maximum = median * (Decimal::one() + spread);
@@ -297,23 +687,65 @@ mod execute {
             .collect()
     }
 
-    fn noop_slash_validator(deps: &mut DepsMut, operator: &Addr) -> Result<(), ContractError> {
-        SLASHED_OPERATORS.save(deps.storage, operator, &true)?;
-        //TODO: this should make an actual call to slash
-        Ok(())
+    /// Relative distance of the price from the aggregate, or None if the aggregate is zero
+    pub(crate) fn calculate_spread(price: Decimal, aggregate: Decimal) -> Option<Decimal> {
+        price.abs_diff(aggregate).checked_div(aggregate).ok()
+    }
+
+    /// The message to slash the operator in the operator contract, if a slash amount is configured
+    fn slash_operator(
+        config: &Config,
+        operator: Addr,
+        task_queue: &Addr,
+        task_id: TaskId,
+    ) -> Result<Option<SubMsg>, ContractError> {
+        let amount = match config.slash_amount {
+            Some(amount) => amount,
+            None => return Ok(None),
+        };
+        let msg = WasmMsg::Execute {
+            contract_addr: config.operator_contract.to_string(),
+            msg: to_json_binary(&SlashExecuteMsg::Slash {
+                operator: operator.to_string(),
+                task_queue: task_queue.to_string(),
+                task_id,
+                amount,
+            })?,
+            funds: vec![],
+        };
+        let msg =
+            SubMsg::reply_on_error(msg, SLASH_REPLY_ID).with_payload(to_json_binary(&operator)?);
+        Ok(Some(msg))
+    }
+
+    pub fn update_aggregation(
+        deps: DepsMut,
+        info: MessageInfo,
+        aggregation: AggregationStrategy,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        assert_owner(deps.storage, &info.sender)?;
+        validate_aggregation(&aggregation)?;
+
+        CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+            config.aggregation = aggregation;
+            Ok(config)
+        })?;
+
+        Ok(Response::new().add_attribute("action", "update_aggregation"))
     }
 
     pub(crate) fn process_votes(
         votes: &[(Addr, OperatorVote)],
         total_power: Uint128,
         config: &Config,
+        history: &[PricePoint],
+        now: Timestamp,
     ) -> Result<(Decimal, Vec<Addr>, bool), ContractError> {
-        let mut all_prices: Vec<Decimal> = votes.iter().map(|(_, vote)| vote.result).collect();
-
-        let median = calculate_median(&mut all_prices);
+        let aggregate = aggregate_votes(votes, &config.aggregation, history, now);
 
         let (allowed_minimum, allowed_maximum) =
-            calculate_allowed_range(median, config.allowed_spread);
+            calculate_allowed_range(aggregate, config.allowed_spread);
 
         let valid_votes = filter_valid_votes(votes, allowed_minimum, allowed_maximum);
 
@@ -322,12 +754,12 @@ mod execute {
         let is_threshold_met = is_threshold_met(valid_power, total_power, config.threshold_percent);
 
         let (slashable_minimum, slashable_maximum) =
-            calculate_allowed_range(median, config.slashable_spread);
+            calculate_allowed_range(aggregate, config.slashable_spread);
 
         let slashable_operators =
             identify_slashable_operators(votes, slashable_minimum, slashable_maximum);
 
-        Ok((median, slashable_operators, is_threshold_met))
+        Ok((aggregate, slashable_operators, is_threshold_met))
     }
 }
 
@@ -335,13 +767,55 @@ mod query {
     use lavs_apis::{
         id::TaskId,
         tasks::TaskStatus,
-        verifier_simple::{TaskInfoResponse, TaskTally},
+        verifier_simple::{
+            InvalidSubmissionsResponse, ListOperatorStatsResponse, ListTaskVotesResponse,
+            ListTasksResponse, OperatorStatsResponse, TaskInfoResponse, TaskTally,
+            TaskVoteResponse, TaskVoter,
+        },
     };
 
-    use crate::state::{OPTIONS, TASKS};
+    use cosmwasm_std::Order;
+    use cw_storage_plus::Bound;
+
+    use crate::msg::{
+        AllPricesResponse, PriceResponse, SlashRecordResponse, SlashableOperatorsResponse,
+    };
+    use crate::state::{
+        LatestPrice, INVALID_SUBMISSIONS, LATEST_PRICES, OPERATOR_STATS, OPTIONS, SLASHES, TASKS,
+    };
 
     use super::*;
 
+    pub fn slashable_operators(
+        deps: Deps,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<SlashableOperatorsResponse> {
+        let limit = limit.unwrap_or(30);
+        let slashes = SLASHES
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|r| {
+                r.map(|(id, record)| SlashRecordResponse {
+                    id,
+                    operator: record.operator.into_string(),
+                    task_queue: record.task_queue.into_string(),
+                    task_id: record.task_id,
+                    feed: record.feed,
+                    price: record.price,
+                    median: record.median,
+                    spread: record.spread,
+                })
+            })
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(SlashableOperatorsResponse { slashes })
+    }
+
     pub(crate) fn query_operator_vote(
         deps: Deps,
         task_contract: String,
@@ -352,13 +826,137 @@ mod query {
         let operator = deps.api.addr_validate(&operator)?;
         let vote = VOTES
             .may_load(deps.storage, (&task_contract, task_id, &operator))?
-            .map(|v| OperatorVoteInfoResponse {
-                power: v.power,
-                result: v.result.to_string(),
-            });
+            .map(|v| -> StdResult<_> {
+                Ok(OperatorVoteInfoResponse {
+                    power: v.power,
+                    result: v.result,
+                })
+            })
+            .transpose()?;
         Ok(vote)
     }
 
+    pub fn list_task_votes(
+        deps: Deps,
+        task_contract: String,
+        task_id: TaskId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTaskVotesResponse> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let start_after = start_after
+            .map(|operator| deps.api.addr_validate(&operator))
+            .transpose()?;
+        let limit = limit.unwrap_or(30);
+        let votes = VOTES
+            .prefix((&task_contract, task_id))
+            .range(
+                deps.storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|r| {
+                r.map(|(operator, vote)| TaskVoteResponse {
+                    operator: operator.into_string(),
+                    power: vote.power,
+                    result: vote.result,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(ListTaskVotesResponse { votes })
+    }
+
+    pub fn list_tasks(
+        deps: Deps,
+        env: Env,
+        task_contract: String,
+        status: Option<TaskStatus>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTasksResponse> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let limit = limit.unwrap_or(30);
+        let tasks = TASKS
+            .prefix(&task_contract)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .map(|r| r.map(|(task_id, task)| task.summary(&env, task_id)))
+            .filter(|r| match (r, &status) {
+                (Ok(task), Some(status)) => task.status == *status,
+                _ => true,
+            })
+            .take(limit as usize)
+            .collect::<StdResult<_>>()?;
+        Ok(ListTasksResponse { tasks })
+    }
+
+    pub fn invalid_submissions(
+        deps: Deps,
+        operator: String,
+    ) -> StdResult<InvalidSubmissionsResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        let count = INVALID_SUBMISSIONS
+            .may_load(deps.storage, &operator)?
+            .unwrap_or_default();
+        Ok(InvalidSubmissionsResponse { count })
+    }
+
+    pub fn operator_stats(deps: Deps, operator: String) -> StdResult<OperatorStatsResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        OPERATOR_STATS.query(deps.storage, operator)
+    }
+
+    pub fn list_operator_stats(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListOperatorStatsResponse> {
+        let start_after = start_after
+            .map(|operator| deps.api.addr_validate(&operator))
+            .transpose()?;
+        OPERATOR_STATS.query_list(deps.storage, start_after, limit)
+    }
+
+    pub fn latest_price(deps: Deps, feed: String) -> StdResult<Option<PriceResponse>> {
+        let latest = LATEST_PRICES.may_load(deps.storage, &feed)?;
+        Ok(latest.map(|latest| price_response(feed, latest)))
+    }
+
+    pub fn all_prices(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<AllPricesResponse> {
+        let limit = limit.unwrap_or(30);
+        let prices = LATEST_PRICES
+            .range(
+                deps.storage,
+                start_after.as_deref().map(Bound::exclusive),
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|r| r.map(|(feed, latest)| price_response(feed, latest)))
+            .collect::<StdResult<Vec<_>>>()?;
+        Ok(AllPricesResponse { prices })
+    }
+
+    fn price_response(feed: String, latest: LatestPrice) -> PriceResponse {
+        PriceResponse {
+            feed,
+            price: latest.price,
+            task_queue: latest.task_queue.into_string(),
+            task_id: latest.task_id,
+            updated: latest.updated,
+        }
+    }
+
     pub fn task_info(
         deps: Deps,
         env: Env,
@@ -369,17 +967,30 @@ mod query {
         let info = TASKS.may_load(deps.storage, (&task_contract, task_id))?;
         if let Some(i) = info {
             // Check current time and update the status if it expired
-            let status = match i.status {
-                TaskStatus::Open if i.is_expired(&env) => TaskStatus::Expired,
-                x => x,
-            };
-            // Collect the running tallies on the options
+            let status = i.current_status(&env);
+            let votes = VOTES
+                .prefix((&task_contract, task_id))
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            // Collect the running tallies on the options of this task, along with who voted for them
             let tallies: Result<Vec<_>, _> = OPTIONS
-                .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .prefix((&task_contract, task_id))
+                .range(deps.storage, None, None, Order::Ascending)
                 .map(|r| {
-                    r.map(|((_, _, result), v)| TaskTally {
-                        result,
-                        power: v.power,
+                    r.map(|(result, v)| {
+                        let voters = votes
+                            .iter()
+                            .filter(|(_, vote)| vote.result == result)
+                            .map(|(operator, vote)| TaskVoter {
+                                operator: operator.to_string(),
+                                power: vote.power,
+                            })
+                            .collect();
+                        TaskTally {
+                            result,
+                            power: v.power,
+                            voters,
+                        }
                     })
                 })
                 .collect();
@@ -395,16 +1006,35 @@ mod query {
     }
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(_deps: DepsMut, _env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        SLASH_REPLY_ID => {
+            // The slash is still recorded here, only the operator contract failed to apply it
+            let operator: Addr = from_json(&msg.payload)?;
+            let error = msg.result.into_result().err().unwrap_or_default();
+            Ok(Response::new()
+                .add_attribute("action", "slash_failed")
+                .add_attribute("operator", operator)
+                .add_attribute("error", error))
+        }
+        _ => Err(ContractError::UnknownReplyId { id: msg.id }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::state::OperatorVote;
+    use crate::state::{OperatorPrices, OperatorVote, PricePoint};
 
     use super::*;
-    use cosmwasm_std::{Decimal, Uint128};
+    use cosmwasm_std::{Decimal, Timestamp, Uint128};
     use execute::{
-        calculate_allowed_range, calculate_median, filter_valid_votes,
-        identify_slashable_operators, is_threshold_met, process_votes,
+        aggregate_votes, calculate_allowed_range, calculate_median, calculate_spread,
+        calculate_trimmed_mean, calculate_twap, calculate_weighted_median, feed_votes,
+        filter_valid_votes, identify_slashable_operators, is_threshold_met, process_votes,
     };
+    use lavs_helpers::verifier::ResultError;
+    use std::str::FromStr;
 
     mod calculate_median {
 
@@ -525,6 +1155,466 @@ mod tests {
         }
     }
 
+    mod calculate_weighted_median {
+
+        use super::*;
+
+        #[test]
+        fn weighted_median_follows_power() {
+            let mut values = vec![
+                (Decimal::one(), Uint128::new(10)),
+                (Decimal::percent(300), Uint128::new(10)),
+                (Decimal::percent(500), Uint128::new(100)),
+            ];
+            let median = calculate_weighted_median(&mut values);
+            // the operator with 100 power outweighs the other two
+            assert_eq!(median, Decimal::percent(500));
+        }
+
+        #[test]
+        fn weighted_median_equal_power_odd_length() {
+            let mut values = vec![
+                (Decimal::percent(500), Uint128::new(1)),
+                (Decimal::one(), Uint128::new(1)),
+                (Decimal::percent(300), Uint128::new(1)),
+            ];
+            let median = calculate_weighted_median(&mut values);
+            // same as the unweighted median of 1, 3 and 5
+            assert_eq!(median, Decimal::percent(300));
+        }
+
+        #[test]
+        fn weighted_median_equal_power_even_length() {
+            let mut values = vec![
+                (Decimal::one(), Uint128::new(25)),
+                (Decimal::percent(300), Uint128::new(25)),
+                (Decimal::percent(500), Uint128::new(25)),
+                (Decimal::percent(700), Uint128::new(25)),
+            ];
+            let median = calculate_weighted_median(&mut values);
+            // half of the power on each side of 3 and 5, so (3 + 5) / 2 = 4
+            assert_eq!(median, Decimal::percent(400));
+        }
+
+        #[test]
+        fn weighted_median_exact_half_skips_zero_power() {
+            let mut values = vec![
+                (Decimal::one(), Uint128::new(50)),
+                (Decimal::percent(150), Uint128::zero()),
+                (Decimal::percent(300), Uint128::new(50)),
+            ];
+            let median = calculate_weighted_median(&mut values);
+            // the price without power doesn't count, so (1 + 3) / 2 = 2
+            assert_eq!(median, Decimal::percent(200));
+        }
+
+        #[test]
+        fn weighted_median_zero_power() {
+            let mut values = vec![
+                (Decimal::one(), Uint128::zero()),
+                (Decimal::percent(300), Uint128::zero()),
+                (Decimal::percent(500), Uint128::zero()),
+            ];
+            let median = calculate_weighted_median(&mut values);
+            // falls back to the unweighted median
+            assert_eq!(median, Decimal::percent(300));
+        }
+
+        #[test]
+        fn weighted_median_empty() {
+            let mut values: Vec<(Decimal, Uint128)> = vec![];
+            let median = calculate_weighted_median(&mut values);
+            assert_eq!(median, Decimal::zero());
+        }
+    }
+
+    mod calculate_trimmed_mean {
+
+        use super::*;
+
+        #[test]
+        fn trimmed_mean_drops_outliers() {
+            let mut values = vec![
+                Decimal::percent(10000),
+                Decimal::percent(100),
+                Decimal::percent(102),
+                Decimal::percent(98),
+                Decimal::zero(),
+            ];
+            let mean = calculate_trimmed_mean(&mut values, Decimal::percent(20));
+            // one value dropped from each end, leaving 0.98, 1 and 1.02
+            assert_eq!(mean, Decimal::one());
+        }
+
+        #[test]
+        fn trimmed_mean_rounds_trimmed_count_down() {
+            let mut values = vec![
+                Decimal::one(),
+                Decimal::percent(200),
+                Decimal::percent(300),
+                Decimal::percent(1000),
+            ];
+            let mean = calculate_trimmed_mean(&mut values, Decimal::percent(20));
+            // 20% of 4 values is 0.8, so nothing is dropped: (1 + 2 + 3 + 10) / 4 = 4
+            assert_eq!(mean, Decimal::percent(400));
+        }
+
+        #[test]
+        fn trimmed_mean_no_trim() {
+            let mut values = vec![Decimal::percent(300), Decimal::one(), Decimal::percent(200)];
+            let mean = calculate_trimmed_mean(&mut values, Decimal::zero());
+            assert_eq!(mean, Decimal::percent(200));
+        }
+
+        #[test]
+        fn trimmed_mean_keeps_middle_value() {
+            let mut values = vec![Decimal::percent(500), Decimal::one(), Decimal::percent(300)];
+            let mean = calculate_trimmed_mean(&mut values, Decimal::percent(49));
+            // 49% of 3 values is 1.47, so one value is dropped from each end
+            assert_eq!(mean, Decimal::percent(300));
+        }
+
+        #[test]
+        fn trimmed_mean_empty() {
+            let mut values: Vec<Decimal> = vec![];
+            let mean = calculate_trimmed_mean(&mut values, Decimal::percent(10));
+            assert_eq!(mean, Decimal::zero());
+        }
+    }
+
+    mod calculate_twap {
+
+        use super::*;
+
+        fn point(price: u64, seconds: u64) -> PricePoint {
+            PricePoint {
+                price: Decimal::percent(price),
+                time: Timestamp::from_seconds(seconds),
+            }
+        }
+
+        #[test]
+        fn twap_weights_by_time() {
+            let history = vec![point(100, 0), point(200, 10)];
+            let twap = calculate_twap(&history, &point(400, 40));
+            // 2 over 10 seconds and 4 over 30 seconds: (2 * 10 + 4 * 30) / 40 = 3.5
+            assert_eq!(twap, Decimal::percent(350));
+        }
+
+        #[test]
+        fn twap_equal_intervals() {
+            let history = vec![point(100, 100), point(300, 160)];
+            let twap = calculate_twap(&history, &point(500, 220));
+            // the first price only starts the window: (3 + 5) / 2 = 4
+            assert_eq!(twap, Decimal::percent(400));
+        }
+
+        #[test]
+        fn twap_without_history() {
+            let twap = calculate_twap(&[], &point(250, 10));
+            assert_eq!(twap, Decimal::percent(250));
+        }
+
+        #[test]
+        fn twap_same_block() {
+            let history = vec![point(100, 10)];
+            let twap = calculate_twap(&history, &point(300, 10));
+            // no time passed, so the latest price is used
+            assert_eq!(twap, Decimal::percent(300));
+        }
+    }
+
+    mod aggregate_votes {
+
+        use super::*;
+
+        fn votes() -> Vec<(Addr, OperatorVote)> {
+            [(100, 10), (110, 10), (150, 80)]
+                .into_iter()
+                .enumerate()
+                .map(|(index, (price, power))| {
+                    (
+                        Addr::unchecked(format!("operator{index}")),
+                        OperatorVote {
+                            power: Uint128::new(power),
+                            result: Decimal::percent(price),
+                        },
+                    )
+                })
+                .collect()
+        }
+
+        #[test]
+        fn aggregate_with_each_strategy() {
+            let votes = votes();
+            let now = Timestamp::from_seconds(100);
+            let aggregate =
+                |aggregation: AggregationStrategy| aggregate_votes(&votes, &aggregation, &[], now);
+
+            assert_eq!(
+                aggregate(AggregationStrategy::Median),
+                Decimal::percent(110)
+            );
+            assert_eq!(
+                aggregate(AggregationStrategy::WeightedMedian),
+                Decimal::percent(150)
+            );
+            assert_eq!(
+                aggregate(AggregationStrategy::TrimmedMean {
+                    trim_percent: Decimal::zero()
+                }),
+                Decimal::percent(120)
+            );
+            // without history, the TWAP is this task's median
+            assert_eq!(
+                aggregate(AggregationStrategy::Twap { tasks: 3 }),
+                Decimal::percent(110)
+            );
+        }
+
+        #[test]
+        fn twap_uses_last_tasks() {
+            let votes = votes();
+            let history = vec![
+                PricePoint {
+                    price: Decimal::percent(1000),
+                    time: Timestamp::from_seconds(0),
+                },
+                PricePoint {
+                    price: Decimal::percent(90),
+                    time: Timestamp::from_seconds(50),
+                },
+            ];
+            let aggregate = aggregate_votes(
+                &votes,
+                &AggregationStrategy::Twap { tasks: 2 },
+                &history,
+                Timestamp::from_seconds(100),
+            );
+            // only the previous task starts the window, and this task's median 1.1 covers it
+            assert_eq!(aggregate, Decimal::percent(110));
+        }
+    }
+
+    mod validate_aggregation {
+        use super::*;
+
+        #[test]
+        fn invalid_strategies() {
+            let err = validate_aggregation(&AggregationStrategy::TrimmedMean {
+                trim_percent: Decimal::percent(50),
+            })
+            .unwrap_err();
+            assert!(matches!(err, ContractError::InvalidTrimPercent(_)));
+
+            for tasks in [0, MAX_TWAP_TASKS + 1] {
+                let err = validate_aggregation(&AggregationStrategy::Twap { tasks }).unwrap_err();
+                assert!(matches!(err, ContractError::InvalidTwapTasks(_)));
+            }
+
+            validate_aggregation(&AggregationStrategy::TrimmedMean {
+                trim_percent: Decimal::percent(49),
+            })
+            .unwrap();
+            validate_aggregation(&AggregationStrategy::Twap {
+                tasks: MAX_TWAP_TASKS,
+            })
+            .unwrap();
+        }
+    }
+
+    mod update_aggregation {
+        use super::*;
+        use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env};
+
+        #[test]
+        fn only_owner_updates() {
+            let mut deps = mock_dependencies();
+            let owner = deps.api.addr_make("owner");
+            let other = deps.api.addr_make("other");
+
+            let msg = InstantiateMsg {
+                operator_contract: deps.api.addr_make("operators").into_string(),
+                threshold_percentage: Decimal::percent(50),
+                allowed_spread: Decimal::percent(10),
+                slashable_spread: Decimal::percent(20),
+                required_percentage: 70,
+                slash_amount: None,
+                aggregation: None,
+                owner: None,
+                max_result_bytes: None,
+                result_schema: None,
+            };
+            instantiate(deps.as_mut(), mock_env(), message_info(&owner, &[]), msg).unwrap();
+            assert_eq!(
+                CONFIG.load(&deps.storage).unwrap().aggregation,
+                AggregationStrategy::Median
+            );
+
+            let update = ExecuteMsg::UpdateAggregation {
+                aggregation: AggregationStrategy::WeightedMedian,
+            };
+            let err = execute(
+                deps.as_mut(),
+                mock_env(),
+                message_info(&other, &[]),
+                update.clone(),
+            )
+            .unwrap_err();
+            assert!(matches!(err, ContractError::Ownership(_)));
+
+            execute(deps.as_mut(), mock_env(), message_info(&owner, &[]), update).unwrap();
+            assert_eq!(
+                CONFIG.load(&deps.storage).unwrap().aggregation,
+                AggregationStrategy::WeightedMedian
+            );
+        }
+    }
+
+    mod parse_prices {
+        use super::*;
+
+        fn config() -> Config {
+            Config {
+                operator_contract: Addr::unchecked("operators"),
+                threshold_percent: Decimal::percent(50),
+                allowed_spread: Decimal::percent(10),
+                slashable_spread: Decimal::percent(20),
+                required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: Some(64),
+                result_schema: None,
+            }
+        }
+
+        #[test]
+        fn valid_prices() {
+            let prices = config()
+                .parse_prices(r#"{"BTC/USD": "67187.34", "ETH/USD": "2650"}"#)
+                .unwrap();
+            assert_eq!(
+                prices,
+                [
+                    (
+                        "BTC/USD".to_string(),
+                        Decimal::from_str("67187.34").unwrap()
+                    ),
+                    ("ETH/USD".to_string(), Decimal::percent(265000)),
+                ]
+                .into()
+            );
+        }
+
+        #[test]
+        fn invalid_prices() {
+            let config = config();
+            let err = |result: &str| config.parse_prices(result).unwrap_err().to_string();
+
+            assert_eq!(
+                err(r#"{"BTC/USD": "-1"}"#),
+                ContractError::NegativePrice("BTC/USD".to_string()).to_string()
+            );
+            assert_eq!(
+                err(r#"{"BTC/USD": "0.0"}"#),
+                ContractError::ZeroPrice("BTC/USD".to_string()).to_string()
+            );
+            assert_eq!(
+                err(r#"{"BTC/USD": "a lot"}"#),
+                ContractError::InvalidPrice("BTC/USD".to_string(), "a lot".to_string()).to_string()
+            );
+            assert_eq!(err("{}"), ContractError::NoPrices.to_string());
+            assert!(matches!(
+                config.parse_prices(r#"{"BTC/USD": 100}"#),
+                Err(ContractError::InvalidPriceResult(_))
+            ));
+            assert!(matches!(
+                config.parse_prices(r#"{"price": "#),
+                Err(ContractError::InvalidResult(ResultError::InvalidJson(_)))
+            ));
+            assert!(matches!(
+                config.parse_prices(&format!(r#"{{"price": "{}"}}"#, "1".repeat(64))),
+                Err(ContractError::InvalidResult(ResultError::TooLarge { .. }))
+            ));
+        }
+
+        #[test]
+        fn prices_match_schema() {
+            let config = Config {
+                result_schema: Some(serde_json::json!({
+                    "type": "object",
+                    "required": ["BTC/USD"]
+                })),
+                ..config()
+            };
+            config.parse_prices(r#"{"BTC/USD": "100"}"#).unwrap();
+            assert!(matches!(
+                config.parse_prices(r#"{"ETH/USD": "100"}"#),
+                Err(ContractError::InvalidResult(ResultError::Schema(_)))
+            ));
+        }
+    }
+
+    mod feed_votes {
+        use super::*;
+
+        #[test]
+        fn only_operators_with_the_feed() {
+            let op1 = Addr::unchecked("operator1");
+            let op2 = Addr::unchecked("operator2");
+
+            let votes = vec![
+                (
+                    op1.clone(),
+                    OperatorPrices {
+                        power: Uint128::new(60),
+                        prices: [
+                            ("BTC/USD".to_string(), Decimal::percent(100)),
+                            ("ETH/USD".to_string(), Decimal::percent(10)),
+                        ]
+                        .into(),
+                        result: r#"{"BTC/USD": "1", "ETH/USD": "0.1"}"#.to_string(),
+                    },
+                ),
+                (
+                    op2.clone(),
+                    OperatorPrices {
+                        power: Uint128::new(40),
+                        prices: [("BTC/USD".to_string(), Decimal::percent(102))].into(),
+                        result: r#"{"BTC/USD": "1.02"}"#.to_string(),
+                    },
+                ),
+            ];
+
+            let btc = feed_votes(&votes, "BTC/USD");
+            assert_eq!(
+                btc,
+                vec![
+                    (
+                        op1.clone(),
+                        OperatorVote {
+                            power: Uint128::new(60),
+                            result: Decimal::percent(100),
+                        },
+                    ),
+                    (
+                        op2,
+                        OperatorVote {
+                            power: Uint128::new(40),
+                            result: Decimal::percent(102),
+                        },
+                    ),
+                ]
+            );
+
+            let eth = feed_votes(&votes, "ETH/USD");
+            assert_eq!(eth.len(), 1);
+            assert_eq!(eth[0].0, op1);
+
+            assert!(feed_votes(&votes, "SOL/USD").is_empty());
+        }
+    }
+
     mod allowed_range {
         use super::*;
 
@@ -1014,6 +2104,34 @@ mod tests {
         }
     }
 
+    mod calculate_spread {
+        use super::*;
+
+        #[test]
+        fn spread_above_median() {
+            let spread = calculate_spread(Decimal::percent(12000), Decimal::percent(10000));
+            assert_eq!(spread, Some(Decimal::percent(20)));
+        }
+
+        #[test]
+        fn spread_below_median() {
+            let spread = calculate_spread(Decimal::percent(7500), Decimal::percent(10000));
+            assert_eq!(spread, Some(Decimal::percent(25)));
+        }
+
+        #[test]
+        fn spread_at_median() {
+            let spread = calculate_spread(Decimal::one(), Decimal::one());
+            assert_eq!(spread, Some(Decimal::zero()));
+        }
+
+        #[test]
+        fn spread_zero_median() {
+            let spread = calculate_spread(Decimal::one(), Decimal::zero());
+            assert_eq!(spread, None);
+        }
+    }
+
     mod process_votes {
         use super::*;
 
@@ -1045,10 +2163,21 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // mocking the power
-            let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
+            let result = process_votes(
+                &votes,
+                Uint128::new(100),
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             let expected_median = Decimal::one();
             let expected_slashable_operators: Vec<Addr> = vec![];
@@ -1087,10 +2216,21 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // mocking the power
-            let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
+            let result = process_votes(
+                &votes,
+                Uint128::new(100),
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             let expected_median = Decimal::percent(200);
             let expected_slashable_operators = vec![op1.clone(), op2.clone()];
@@ -1137,10 +2277,21 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // mocking the power
-            let result = process_votes(&votes, Uint128::new(100), &config).unwrap();
+            let result = process_votes(
+                &votes,
+                Uint128::new(100),
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             let expected_median = Decimal::percent(200);
             let expected_slashable_operators = vec![op1.clone(), op3.clone()];
@@ -1165,6 +2316,10 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // submitted are 100.00 and 102.00
@@ -1185,8 +2340,14 @@ mod tests {
                 ),
             ];
 
-            let (median, slashed_operators, is_threshold_met) =
-                process_votes(&votes, total_power, &config).unwrap();
+            let (median, slashed_operators, is_threshold_met) = process_votes(
+                &votes,
+                total_power,
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             assert!(!is_threshold_met);
             // 101
@@ -1206,8 +2367,14 @@ mod tests {
                 ),
             ];
 
-            let (median, slashed_operators, is_threshold_met) =
-                process_votes(&votes_with_op3, total_power, &config).unwrap();
+            let (median, slashed_operators, is_threshold_met) = process_votes(
+                &votes_with_op3,
+                total_power,
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             assert!(is_threshold_met);
             // 100
@@ -1229,6 +2396,10 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // submited are 1.0 1.3 and 0.7
@@ -1256,8 +2427,14 @@ mod tests {
                 ),
             ];
 
-            let (median, slashed_operators, is_threshold_met) =
-                process_votes(&votes, total_power, &config).unwrap();
+            let (median, slashed_operators, is_threshold_met) = process_votes(
+                &votes,
+                total_power,
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             assert!(!is_threshold_met);
             assert_eq!(median, Decimal::percent(100));
@@ -1278,6 +2455,10 @@ mod tests {
                 allowed_spread: Decimal::percent(10),
                 slashable_spread: Decimal::percent(20),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // submited are 1.0 1.05 and 1.5
@@ -1306,8 +2487,14 @@ mod tests {
                 ),
             ];
 
-            let (median, slashed_operators, is_threshold_met) =
-                process_votes(&votes, total_power, &config).unwrap();
+            let (median, slashed_operators, is_threshold_met) = process_votes(
+                &votes,
+                total_power,
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             assert!(is_threshold_met);
             assert_eq!(median, Decimal::percent(105));
@@ -1328,6 +2515,10 @@ mod tests {
                 allowed_spread: Decimal::percent(50),
                 slashable_spread: Decimal::percent(60),
                 required_percentage: 70,
+                slash_amount: None,
+                aggregation: AggregationStrategy::Median,
+                max_result_bytes: None,
+                result_schema: None,
             };
 
             // submitted are 1.0 1.1 and 1.2
@@ -1355,8 +2546,14 @@ mod tests {
                 ),
             ];
 
-            let (median, slashed_operators, is_threshold_met) =
-                process_votes(&votes, total_power, &config).unwrap();
+            let (median, slashed_operators, is_threshold_met) = process_votes(
+                &votes,
+                total_power,
+                &config,
+                &[],
+                Timestamp::from_seconds(0),
+            )
+            .unwrap();
 
             assert_eq!(median, Decimal::percent(110));
             assert!(is_threshold_met);
@@ -1,6 +1,8 @@
 use cosmwasm_std::{Decimal, StdError};
+use cw_ownable::OwnershipError;
 use cw_utils::PaymentError;
 use lavs_apis::verifier_simple::VerifierError;
+use lavs_helpers::{json_schema::SchemaError, verifier::ResultError};
 use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum ContractError {
@@ -10,6 +12,9 @@ pub enum ContractError {
     #[error("{0}")]
     Payment(#[from] PaymentError),
 
+    #[error("{0}")]
+    Ownership(#[from] OwnershipError),
+
     #[error("{0}")]
     ConversionError(#[from] serde_json::Error),
 
@@ -22,8 +27,17 @@ pub enum ContractError {
     #[error("Thresshold not met")]
     ThresholdNotMet,
 
-    #[error("Zero price submitted")]
-    ZeroPrice,
+    #[error("Zero price submitted for {0}")]
+    ZeroPrice(String),
+
+    #[error("Negative price submitted for {0}")]
+    NegativePrice(String),
+
+    #[error("No prices submitted")]
+    NoPrices,
+
+    #[error("Result must map feed ids to prices as strings: {0}")]
+    InvalidPriceResult(String),
 
     #[error("Operator tried to vote twice: {0}")]
     OperatorAlreadyVoted(String),
@@ -37,9 +51,27 @@ pub enum ContractError {
     #[error("Invalid spread configuration. Slashable: {0}. Allowed: {1}.")]
     InvalidSpread(Decimal, Decimal),
 
+    #[error("Invalid slash amount, must be a non-zero amount or a fraction between 0 and 1")]
+    InvalidSlashAmount,
+
+    #[error("Invalid trimmed mean, must trim less than 50% from each end: {0}")]
+    InvalidTrimPercent(Decimal),
+
+    #[error("Invalid TWAP, must average between 1 and {0} tasks")]
+    InvalidTwapTasks(u32),
+
     #[error("{0}")]
     Verifier(#[from] VerifierError),
 
-    #[error("Invalid price provided")]
-    InvalidPrice,
+    #[error("Invalid price submitted for {0}: {1}")]
+    InvalidPrice(String, String),
+
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
+    #[error("{0}")]
+    InvalidResult(#[from] ResultError),
+
+    #[error("Unknown reply id {id}")]
+    UnknownReplyId { id: u64 },
 }
@@ -1,8 +1,12 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{Decimal, Timestamp};
 use cw_orch::ExecuteFns;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 use lavs_apis::id::TaskId;
 
+pub use crate::state::AggregationStrategy;
+pub use lavs_apis::interfaces::slashing::SlashAmount;
+
 #[cw_serde]
 pub struct InstantiateMsg {
     // The address of the operator contract
@@ -15,8 +19,21 @@ pub struct InstantiateMsg {
     pub slashable_spread: Decimal,
     /// The percentage of voting power needed to agree in order to complete a task
     pub required_percentage: u32,
+    /// How much to slash operators outside the slashable spread.
+    /// If unset, slashes are only recorded. Requires the operator contract to implement
+    /// `lavs_apis::interfaces::slashing` and to allow this contract to slash.
+    pub slash_amount: Option<SlashAmount>,
+    /// How the submitted prices are combined into the task result, the median if unset
+    pub aggregation: Option<AggregationStrategy>,
+    /// Can update the aggregation strategy, defaults to the instantiator
+    pub owner: Option<String>,
+    /// Results above this size are rejected, if set
+    pub max_result_bytes: Option<u32>,
+    /// Schema every result must match, if any, on top of being a map of feed ids to prices
+    pub result_schema: Option<serde_json::Value>,
 }
 
+#[cw_ownable_execute]
 #[cw_serde]
 #[derive(ExecuteFns)]
 #[cw_orch(disable_fields_sorting)]
@@ -27,11 +44,28 @@ pub enum ExecuteMsg {
         /// The ID of the task that was completed
         task_id: TaskId,
         /// The result of the task, (JSON) serialized as a string
-        /// It is serialized to allow for easy comparison and to avoid field sorting issues when verifying signatures
+        /// It is serialized to allow for easy comparison and to avoid field sorting issues when verifying signatures.
+        /// The price of each feed by feed id, e.g. `{"BTC/USD": "67187.34", "ETH/USD": "2650.1"}`.
+        /// Invalid results are rejected: they are not counted as a vote, and the operator's invalid submissions
+        /// count is increased.
         result: String,
     },
+    /// Only the owner can update the aggregation strategy, it applies to the tasks completed afterwards
+    UpdateAggregation { aggregation: AggregationStrategy },
+    /// Counts the completed task as missed by each of the given operators which had voting power on it
+    /// but didn't vote. Other operators, and ones already counted for the task, are skipped.
+    /// Anyone can send this, as missed tasks are not counted when the task is completed.
+    RecordMissed {
+        /// Task queue contract of the completed task
+        task_queue_contract: String,
+        /// The ID of the completed task
+        task_id: TaskId,
+        /// The operators to check
+        operators: Vec<String>,
+    },
 }
 
+#[cw_ownable_query]
 #[cw_serde]
 #[derive(cw_orch::QueryFns)]
 #[cw_orch(disable_fields_sorting)]
@@ -47,6 +81,26 @@ pub enum QueryMsg {
         /// The ID of the task we are interested in
         task_id: TaskId,
     },
+    /// All votes on the task, ordered by operator address
+    #[returns(lavs_apis::verifier_simple::ListTaskVotesResponse)]
+    ListTaskVotes {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// The ID of the task we are interested in
+        task_id: TaskId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The tasks of the task contract which received votes, ordered by id
+    #[returns(lavs_apis::verifier_simple::ListTasksResponse)]
+    ListTasks {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// Only list the tasks with this status, if set
+        status: Option<lavs_apis::tasks::TaskStatus>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
     #[returns(Option<lavs_apis::verifier_simple::OperatorVoteInfoResponse>)]
     OperatorVote {
         /// The task contract we are interested in
@@ -56,6 +110,68 @@ pub enum QueryMsg {
         /// The operator whose vote we are interested in
         operator: String,
     },
-    #[returns(Vec<cosmwasm_std::Addr>)]
-    SlashableOperators {},
+    /// Lists slashed operators along with the evidence, ordered by when they were slashed
+    #[returns(SlashableOperatorsResponse)]
+    SlashableOperators {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// How many rejected results an operator submitted
+    #[returns(lavs_apis::verifier_simple::InvalidSubmissionsResponse)]
+    InvalidSubmissions { operator: String },
+    /// How an operator performed on the tasks of this verifier
+    #[returns(lavs_apis::verifier_simple::OperatorStatsResponse)]
+    OperatorStats { operator: String },
+    /// The stats of every operator which took part in a task, ordered by address
+    #[returns(lavs_apis::verifier_simple::ListOperatorStatsResponse)]
+    ListOperatorStats {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The last aggregated price of the feed, from any task queue
+    #[returns(Option<PriceResponse>)]
+    LatestPrice { feed: String },
+    /// The last aggregated price of every feed, ordered by feed id
+    #[returns(AllPricesResponse)]
+    AllPrices {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct PriceResponse {
+    pub feed: String,
+    pub price: Decimal,
+    pub task_queue: String,
+    /// The task which produced the price
+    pub task_id: TaskId,
+    /// When the task was completed
+    pub updated: Timestamp,
+}
+
+#[cw_serde]
+pub struct AllPricesResponse {
+    pub prices: Vec<PriceResponse>,
+}
+
+#[cw_serde]
+pub struct SlashableOperatorsResponse {
+    pub slashes: Vec<SlashRecordResponse>,
+}
+
+#[cw_serde]
+pub struct SlashRecordResponse {
+    pub id: u64,
+    pub operator: String,
+    pub task_queue: String,
+    pub task_id: TaskId,
+    /// The feed the operator was slashed on
+    pub feed: String,
+    /// The price submitted by the operator
+    pub price: Decimal,
+    /// The aggregate of all submitted prices, the median unless another aggregation is configured
+    pub median: Decimal,
+    /// How far the submitted price is from the aggregate, relative to the aggregate
+    pub spread: Option<Decimal>,
 }
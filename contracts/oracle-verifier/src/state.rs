@@ -1,15 +1,37 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal, StdError, Storage, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdError, Storage, Timestamp, Uint128};
 use cw_storage_plus::{Item, Map};
-use lavs_apis::{id::TaskId, verifier_simple::TaskMetadata};
+use lavs_apis::{
+    id::TaskId,
+    interfaces::{operator_stats::OperatorStatsStorage, slashing::SlashAmount},
+    verifier_simple::TaskMetadata,
+};
+use lavs_helpers::verifier::check_result;
+
+use crate::error::ContractError;
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const VOTES: Map<(&Addr, TaskId, &Addr), OperatorVote> = Map::new("operator_votes");
+pub const VOTES: Map<(&Addr, TaskId, &Addr), OperatorPrices> = Map::new("operator_votes");
 pub const TASKS: Map<(&Addr, TaskId), TaskMetadata> = Map::new("tasks");
 pub const OPTIONS: Map<(&Addr, TaskId, &str), TaskOption> = Map::new("task_options");
-pub const SLASHED_OPERATORS: Map<&Addr, bool> = Map::new("slashed_operators");
+/// Every slash with its evidence, indexed by an incrementing id
+pub const SLASHES: Map<u64, SlashRecord> = Map::new("slashes");
+pub const NEXT_SLASH_ID: Item<u64> = Item::new("next_slash_id");
+/// The median price of each feed in the last completed tasks of each task queue, oldest first,
+/// for TWAP aggregation - indexed by (task_queue, feed)
+pub const PRICE_HISTORY: Map<(&Addr, &str), Vec<PricePoint>> = Map::new("price_history");
+/// The last aggregated price of each feed, from any task queue
+pub const LATEST_PRICES: Map<&str, LatestPrice> = Map::new("latest_prices");
+/// How many rejected results each operator submitted
+pub const INVALID_SUBMISSIONS: Map<&Addr, u64> = Map::new("invalid_submissions");
+/// How each operator performed on the tasks
+pub const OPERATOR_STATS: OperatorStatsStorage =
+    OperatorStatsStorage::new("operator_stats", "operator_missed_tasks");
+
+/// The most completed tasks a TWAP can average over, and so the most kept in the price history
+pub const MAX_TWAP_TASKS: u32 = 100;
 
 #[cw_serde]
 pub struct Config {
@@ -18,8 +40,115 @@ pub struct Config {
     pub allowed_spread: Decimal,
     pub slashable_spread: Decimal,
     pub required_percentage: u32,
+    /// How much to slash operators outside the slashable spread.
+    /// If unset, slashes are only recorded and no message is sent to the operator contract.
+    pub slash_amount: Option<SlashAmount>,
+    /// How the submitted prices are combined into the task result.
+    /// The allowed and slashable spreads are measured from the aggregate.
+    #[serde(default)]
+    pub aggregation: AggregationStrategy,
+    /// Results above this size are rejected, if set
+    #[serde(default)]
+    pub max_result_bytes: Option<u32>,
+    /// Schema every result must match, if any, on top of being a map of feed ids to prices
+    #[serde(default)]
+    pub result_schema: Option<serde_json::Value>,
+}
+
+impl Config {
+    /// Parses an operator's result into the price of each feed,
+    /// checking it against the size limit and schema
+    pub fn parse_prices(&self, result: &str) -> Result<BTreeMap<String, Decimal>, ContractError> {
+        let value = check_result(result, self.max_result_bytes, self.result_schema.as_ref())?;
+        let price_result: PriceResult = serde_json::from_value(value)
+            .map_err(|err| ContractError::InvalidPriceResult(err.to_string()))?;
+        if price_result.is_empty() {
+            return Err(ContractError::NoPrices);
+        }
+
+        price_result
+            .into_iter()
+            .map(|(feed, price)| {
+                if price.trim_start().starts_with('-') {
+                    return Err(ContractError::NegativePrice(feed));
+                }
+                let parsed = Decimal::from_str(&price)
+                    .map_err(|_| ContractError::InvalidPrice(feed.clone(), price))?;
+                if parsed.is_zero() {
+                    return Err(ContractError::ZeroPrice(feed));
+                }
+                Ok((feed, parsed))
+            })
+            .collect()
+    }
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub enum AggregationStrategy {
+    /// The median of the submitted prices, every operator counting the same
+    #[default]
+    Median,
+    /// The price with at most half of the voting power below it and at most half above it
+    WeightedMedian,
+    /// The mean of the submitted prices, after dropping `trim_percent` of them from each end
+    TrimmedMean { trim_percent: Decimal },
+    /// The time-weighted average of the median prices of the feed in the last `tasks` completed tasks
+    /// of the task queue, this one included. Every price is weighted by the time since the previous one.
+    Twap { tasks: u32 },
+}
+
+#[cw_serde]
+pub struct PricePoint {
+    pub price: Decimal,
+    pub time: Timestamp,
+}
+
+/// Appends the price to the history of the feed on the task queue, keeping the last `MAX_TWAP_TASKS` prices
+pub fn record_price(
+    storage: &mut dyn Storage,
+    task_queue: &Addr,
+    feed: &str,
+    point: PricePoint,
+) -> Result<Vec<PricePoint>, StdError> {
+    PRICE_HISTORY.update::<_, StdError>(storage, (task_queue, feed), |history| {
+        let mut history = history.unwrap_or_default();
+        history.push(point);
+        let excess = history.len().saturating_sub(MAX_TWAP_TASKS as usize);
+        history.drain(..excess);
+        Ok(history)
+    })
+}
+
+#[cw_serde]
+pub struct LatestPrice {
+    pub price: Decimal,
+    pub task_queue: Addr,
+    /// The task which produced the price
+    pub task_id: TaskId,
+    pub updated: Timestamp,
 }
 
+/// The prices submitted by an operator on a task, by feed id
+#[cw_serde]
+pub struct OperatorPrices {
+    pub power: Uint128,
+    pub prices: BTreeMap<String, Decimal>,
+    /// The result as submitted, which is the key of its option
+    pub result: String,
+}
+
+impl OperatorPrices {
+    /// The vote on a single feed, if the operator submitted a price for it
+    pub fn feed_vote(&self, feed: &str) -> Option<OperatorVote> {
+        self.prices.get(feed).map(|price| OperatorVote {
+            power: self.power,
+            result: *price,
+        })
+    }
+}
+
+/// An operator's vote on a single feed
 #[cw_serde]
 pub struct OperatorVote {
     pub power: Uint128,
@@ -32,35 +161,51 @@ pub struct TaskOption {
     pub power: Uint128,
 }
 
+/// The evidence for slashing an operator on a task
 #[cw_serde]
-pub struct PriceResult {
-    pub price: String,
+pub struct SlashRecord {
+    pub operator: Addr,
+    pub task_queue: Addr,
+    pub task_id: TaskId,
+    /// The feed the operator was slashed on
+    pub feed: String,
+    /// The price submitted by the operator
+    pub price: Decimal,
+    /// The aggregate of all submitted prices, the median unless another aggregation is configured
+    pub median: Decimal,
+    /// How far the submitted price is from the aggregate, relative to the aggregate.
+    /// Unset if the aggregate is zero.
+    pub spread: Option<Decimal>,
 }
 
-/// This assumes a previous check was made that the operator has not yet voted.
+/// Stores a slash record under the next id, returning the id
+pub fn save_slash(storage: &mut dyn Storage, record: &SlashRecord) -> Result<u64, StdError> {
+    let id = NEXT_SLASH_ID.may_load(storage)?.unwrap_or_default();
+    SLASHES.save(storage, id, record)?;
+    NEXT_SLASH_ID.save(storage, &(id + 1))?;
+    Ok(id)
+}
+
+/// A vote or a task result, the price of each feed by feed id, e.g. `{"BTC/USD": "67187.34"}`.
+/// A single price is the `price` feed: `{"price": "67187.34"}`
+pub type PriceResult = BTreeMap<String, String>;
+
+/// This assumes a previous check was made that the operator has not yet voted,
+/// and that the prices were parsed from the result with `Config::parse_prices`.
 /// Returns the running tally of votes in favor of this result.
 pub fn record_vote(
     storage: &mut dyn Storage,
     task_queue: &Addr,
     task_id: TaskId,
     operator: &Addr,
-    result: &str,
-    power: Uint128,
+    vote: &OperatorPrices,
 ) -> Result<Uint128, StdError> {
-    let price_result: PriceResult =
-        serde_json::from_str(result).expect("Record vote: Invalid result input provided");
-
-    let vote = OperatorVote {
-        power,
-        result: Decimal::from_str(&price_result.price)?,
-    };
-
-    VOTES
-        .save(storage, (task_queue, task_id, operator), &vote)
-        .unwrap();
+    VOTES.save(storage, (task_queue, task_id, operator), vote)?;
+    let power = vote.power;
 
     // Update the option and get the running tally of power in favor of this result
-    let tally = OPTIONS.update::<_, StdError>(storage, (task_queue, task_id, result), |old| {
+    let key = (task_queue, task_id, vote.result.as_str());
+    let tally = OPTIONS.update::<_, StdError>(storage, key, |old| {
         let old_power = old.map_or(Uint128::zero(), |v| v.power);
         Ok(TaskOption {
             power: old_power + power,
@@ -68,3 +213,12 @@ pub fn record_vote(
     })?;
     Ok(tally.power)
 }
+
+/// Counts a rejected result against the operator, returning the new count
+pub fn record_invalid_submission(
+    storage: &mut dyn Storage,
+    operator: &Addr,
+) -> Result<u64, StdError> {
+    INVALID_SUBMISSIONS
+        .update::<_, StdError>(storage, operator, |count| Ok(count.unwrap_or_default() + 1))
+}
@@ -1,10 +1,12 @@
-use cosmwasm_std::Decimal;
+use cosmwasm_std::{coins, Decimal, Uint128};
 use cw_orch::environment::{ChainState, CwEnv};
 use cw_orch::prelude::*;
 
+use lavs_apis::events::invalid_result_event::InvalidResultEvent;
 use lavs_apis::id::TaskId;
-use lavs_apis::tasks::{Requestor, Status, TimeoutInfo};
+use lavs_apis::tasks::{Requestor, Status, TaskStatus, TimeoutInfo};
 use lavs_apis::time::Duration;
+use lavs_apis::verifier_simple::VerifierError;
 use lavs_orch::{Addressable, AltSigner};
 use serde_json::json;
 
@@ -20,11 +22,18 @@ use lavs_mock_operators::interface::Contract as MockOperatorsContract;
 use lavs_mock_operators::msg::{
     InstantiateMsg as MockOperatorsInstantiateMsg, InstantiateOperator,
 };
+use lavs_operators::interface::Contract as OperatorsContract;
+use lavs_operators::msg::{
+    CustomExecuteMsgFns as OperatorsExecuteMsgFns, CustomQueryMsgFns as OperatorsQueryMsgFns,
+    InstantiateMsg as OperatorsInstantiateMsg,
+};
 
+use crate::error::ContractError;
 use crate::interface::Contract;
-use crate::msg::{ExecuteMsgFns, InstantiateMsg, QueryMsgFns};
+use crate::msg::{ExecuteMsgFns, InstantiateMsg, QueryMsgFns, SlashAmount};
 
 pub const BECH_PREFIX: &str = "slay3r";
+pub const DENOM: &str = "uslay";
 
 pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> Contract<Chain> {
     let contract = Contract::new(chain);
@@ -66,6 +75,11 @@ where
         allowed_spread: Decimal::percent(10),
         slashable_spread: Decimal::percent(20),
         required_percentage: 100,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
     };
     let oracle_verifier = setup(chain.clone(), msg);
 
@@ -105,8 +119,8 @@ where
     let task_result = status.result.unwrap();
     assert_eq!(task_result, json!({"price": median_price.to_string()}));
 
-    let slashed_operators: Vec<Addr> = oracle_verifier.slashable_operators().unwrap();
-    assert!(slashed_operators.is_empty());
+    let slashed_operators = oracle_verifier.slashable_operators(None, None).unwrap();
+    assert!(slashed_operators.slashes.is_empty());
 }
 
 pub fn threshold_not_met<C>(chain: C)
@@ -140,6 +154,11 @@ where
         allowed_spread: Decimal::percent(5),
         slashable_spread: Decimal::percent(10),
         required_percentage: 70,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
     };
     let verifier = setup(chain.clone(), msg);
 
@@ -165,6 +184,612 @@ where
     assert_eq!(status.status, Status::Open {});
 }
 
+/// Operators outside the slashable spread are recorded with the evidence,
+/// and slashed in the operator contract
+pub fn slashing_works<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+    let operator3 = chain.alt_signer(5);
+
+    let operators = setup_operators(chain.clone());
+    operators
+        .call_as(&operator1)
+        .bond(&coins(50, DENOM))
+        .unwrap();
+    operators
+        .call_as(&operator2)
+        .bond(&coins(30, DENOM))
+        .unwrap();
+    operators
+        .call_as(&operator3)
+        .bond(&coins(20, DENOM))
+        .unwrap();
+    // power only counts from the next block
+    chain.next_block().unwrap();
+
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(70),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        slash_amount: Some(SlashAmount::Fraction(Decimal::percent(50))),
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+    operators
+        .update_slashers(Some(vec![verifier.addr_str().unwrap()]), None)
+        .unwrap();
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    for (operator, price) in [
+        (&operator1, "100"),
+        (&operator2, "102"),
+        (&operator3, "150"),
+    ] {
+        verifier
+            .call_as(operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                format!(r#"{{"price": "{price}"}}"#),
+            )
+            .unwrap();
+    }
+
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+
+    // operator3 is far outside the slashable spread
+    let slashes = verifier.slashable_operators(None, None).unwrap().slashes;
+    assert_eq!(slashes.len(), 1);
+    let slash = &slashes[0];
+    assert_eq!(slash.operator, operator3.addr().into_string());
+    assert_eq!(slash.task_queue, tasker.addr_str().unwrap());
+    assert_eq!(slash.task_id, task_id);
+    assert_eq!(slash.feed, "price");
+    assert_eq!(slash.price, Decimal::percent(15000));
+    assert_eq!(slash.median, Decimal::percent(10200));
+    assert!(slash.spread.unwrap() > Decimal::percent(20));
+
+    // pagination starts after the last id
+    let slashes = verifier.slashable_operators(Some(slash.id), None).unwrap();
+    assert!(slashes.slashes.is_empty());
+
+    // and half of its stake is gone
+    let info = operators.operator(operator3.addr().into_string()).unwrap();
+    assert_eq!(info.bonded, Uint128::new(10));
+    let info = operators.operator(operator1.addr().into_string()).unwrap();
+    assert_eq!(info.bonded, Uint128::new(50));
+
+    // a failed slash is still recorded, and doesn't revert the vote completing the task
+    operators
+        .update_slashers(None, Some(vec![verifier.addr_str().unwrap()]))
+        .unwrap();
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+    for (operator, price) in [
+        (&operator1, "100"),
+        (&operator2, "102"),
+        (&operator3, "150"),
+    ] {
+        verifier
+            .call_as(operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                format!(r#"{{"price": "{price}"}}"#),
+            )
+            .unwrap();
+    }
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+    let slashes = verifier.slashable_operators(None, None).unwrap().slashes;
+    assert_eq!(slashes.len(), 2);
+    let info = operators.operator(operator3.addr().into_string()).unwrap();
+    assert_eq!(info.bonded, Uint128::new(10));
+}
+
+/// Every feed is aggregated and spread-checked on its own, and the latest prices are stored
+pub fn multiple_feeds<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+    let operator3 = chain.alt_signer(5);
+
+    let operators = vec![
+        InstantiateOperator {
+            addr: operator1.addr().to_string(),
+            voting_power: 50u32,
+        },
+        InstantiateOperator {
+            addr: operator2.addr().to_string(),
+            voting_power: 30u32,
+        },
+        InstantiateOperator {
+            addr: operator3.addr().to_string(),
+            voting_power: 20u32,
+        },
+    ];
+    let mock_operators = setup_mock_operators(chain.clone(), operators);
+
+    let msg = InstantiateMsg {
+        operator_contract: mock_operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(70),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_prices"});
+    let task_id = make_task(&tasker, "Get Prices Task", None, &payload);
+
+    // operator3 only submits a BTC price, far from the others
+    for (operator, result) in [
+        (&operator1, r#"{"BTC/USD": "100", "ETH/USD": "10"}"#),
+        (&operator2, r#"{"BTC/USD": "102", "ETH/USD": "11"}"#),
+        (&operator3, r#"{"BTC/USD": "150"}"#),
+    ] {
+        verifier
+            .call_as(operator)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+
+    let status = tasker.task(task_id).unwrap();
+    let completed = chain.block_info().unwrap().time;
+    assert_eq!(status.status, Status::Completed { completed });
+    assert_eq!(
+        status.result.unwrap(),
+        json!({"BTC/USD": "102", "ETH/USD": "10.5"})
+    );
+
+    // operator3 is only slashed on the feed it was off on
+    let slashes = verifier.slashable_operators(None, None).unwrap().slashes;
+    assert_eq!(slashes.len(), 1);
+    assert_eq!(slashes[0].operator, operator3.addr().into_string());
+    assert_eq!(slashes[0].feed, "BTC/USD");
+
+    let eth = verifier
+        .latest_price("ETH/USD".to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(eth.price, Decimal::percent(1050));
+    assert_eq!(eth.task_queue, tasker.addr_str().unwrap());
+    assert_eq!(eth.task_id, task_id);
+    assert_eq!(eth.updated, completed);
+    assert!(verifier
+        .latest_price("SOL/USD".to_string())
+        .unwrap()
+        .is_none());
+
+    let prices = verifier.all_prices(None, None).unwrap().prices;
+    let feeds: Vec<_> = prices.iter().map(|price| price.feed.as_str()).collect();
+    assert_eq!(feeds, vec!["BTC/USD", "ETH/USD"]);
+    let prices = verifier
+        .all_prices(Some("BTC/USD".to_string()), None)
+        .unwrap()
+        .prices;
+    assert_eq!(prices, vec![eth]);
+}
+
+/// A feed only submitted by a few operators is left out of the result, rather than published
+pub fn unreported_feed<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+    let operator3 = chain.alt_signer(5);
+
+    let operators = vec![
+        InstantiateOperator {
+            addr: operator1.addr().to_string(),
+            voting_power: 50u32,
+        },
+        InstantiateOperator {
+            addr: operator2.addr().to_string(),
+            voting_power: 30u32,
+        },
+        InstantiateOperator {
+            addr: operator3.addr().to_string(),
+            voting_power: 20u32,
+        },
+    ];
+    let mock_operators = setup_mock_operators(chain.clone(), operators);
+
+    let msg = InstantiateMsg {
+        operator_contract: mock_operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(70),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    // operator3 alone adds a made up feed
+    for (operator, result) in [
+        (&operator1, r#"{"price": "100"}"#),
+        (&operator2, r#"{"price": "101"}"#),
+        (&operator3, r#"{"price": "100", "FOO/USD": "1"}"#),
+    ] {
+        verifier
+            .call_as(operator)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+    assert_eq!(status.result.unwrap(), json!({"price": "100"}));
+
+    assert!(verifier
+        .latest_price("FOO/USD".to_string())
+        .unwrap()
+        .is_none());
+    let prices = verifier.all_prices(None, None).unwrap().prices;
+    let feeds: Vec<_> = prices.iter().map(|price| price.feed.as_str()).collect();
+    assert_eq!(feeds, vec!["price"]);
+}
+
+/// Invalid results are not counted as votes, and are counted against the operator instead
+pub fn invalid_results<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+
+    let operators = vec![
+        InstantiateOperator {
+            addr: operator1.addr().to_string(),
+            voting_power: 50u32,
+        },
+        InstantiateOperator {
+            addr: operator2.addr().to_string(),
+            voting_power: 50u32,
+        },
+    ];
+    let mock_operators = setup_mock_operators(chain.clone(), operators);
+
+    let msg = InstantiateMsg {
+        operator_contract: mock_operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(70),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: Some(100),
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    for result in [
+        r#"{"price": "#.to_string(),
+        r#"{"price": "-100"}"#.to_string(),
+        format!(r#"{{"price": "{}"}}"#, "1".repeat(100)),
+    ] {
+        let res = verifier
+            .call_as(&operator1)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result)
+            .unwrap();
+        let event = res
+            .events()
+            .iter()
+            .find_map(|event| InvalidResultEvent::try_from(event).ok())
+            .unwrap();
+        assert_eq!(event.operator, operator1.addr().into_string());
+        assert_eq!(event.task_id, task_id);
+    }
+
+    let invalid = verifier
+        .invalid_submissions(operator1.addr().into_string())
+        .unwrap();
+    assert_eq!(invalid.count, 3);
+    let invalid = verifier
+        .invalid_submissions(operator2.addr().into_string())
+        .unwrap();
+    assert_eq!(invalid.count, 0);
+    let vote = verifier
+        .operator_vote(
+            tasker.addr_str().unwrap(),
+            task_id,
+            operator1.addr().into_string(),
+        )
+        .unwrap();
+    assert!(vote.is_none());
+
+    // the operator can still submit a valid result
+    for operator in [&operator1, &operator2] {
+        verifier
+            .call_as(operator)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                task_id,
+                r#"{"price": "100"}"#.to_string(),
+            )
+            .unwrap();
+    }
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+}
+
+/// The tallies of a task only include its own votes, matched to the options by the submitted result
+pub fn task_votes<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operator1 = chain.alt_signer(3);
+    let operator2 = chain.alt_signer(4);
+
+    let operators = vec![
+        InstantiateOperator {
+            addr: operator1.addr().to_string(),
+            voting_power: 50u32,
+        },
+        InstantiateOperator {
+            addr: operator2.addr().to_string(),
+            voting_power: 50u32,
+        },
+    ];
+    let mock_operators = setup_mock_operators(chain.clone(), operators);
+
+    let msg = InstantiateMsg {
+        operator_contract: mock_operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(70),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        required_percentage: 100,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let first = make_task(&tasker, "Get Price Task", None, &payload);
+    let second = make_task(&tasker, "Get Price Task", None, &payload);
+
+    // both operators complete the first task, only the first one votes on the second
+    let votes = [
+        (&operator1, first, r#"{"price": "100"}"#),
+        (&operator1, second, r#"{"price": "104"}"#),
+        (&operator2, first, r#"{"price":"100.0"}"#),
+    ];
+    for (operator, task_id, result) in votes {
+        verifier
+            .call_as(operator)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+
+    // a second vote is rejected, so it can't be counted twice
+    let err = verifier
+        .call_as(&operator1)
+        .executed_task(
+            tasker.addr_str().unwrap(),
+            second,
+            r#"{"price": "105"}"#.to_string(),
+        )
+        .unwrap_err();
+    let expected = ContractError::OperatorAlreadyVoted(operator1.addr().to_string());
+    assert!(
+        err.root().to_string().contains(&expected.to_string()),
+        "Unexpected error: {}",
+        err.root()
+    );
+
+    // the same prices formatted differently are separate options, each with its own voter
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), first)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, TaskStatus::Completed);
+    assert_eq!(info.tallies.len(), 2);
+    for tally in &info.tallies {
+        assert_eq!(tally.power, Uint128::new(50));
+        assert_eq!(tally.voters.len(), 1);
+        assert_eq!(tally.voters[0].power, tally.power);
+    }
+    let voter = |result: &str| {
+        let tally = info.tallies.iter().find(|t| t.result == result).unwrap();
+        tally.voters[0].operator.clone()
+    };
+    assert_eq!(voter(r#"{"price": "100"}"#), operator1.addr().into_string());
+    assert_eq!(
+        voter(r#"{"price":"100.0"}"#),
+        operator2.addr().into_string()
+    );
+
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), second)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, TaskStatus::Open);
+    assert_eq!(info.tallies.len(), 1);
+    assert_eq!(info.tallies[0].result, r#"{"price": "104"}"#);
+    assert_eq!(info.tallies[0].voters.len(), 1);
+    assert_eq!(
+        info.tallies[0].voters[0].operator,
+        operator1.addr().into_string()
+    );
+
+    let votes = verifier
+        .list_task_votes(tasker.addr_str().unwrap(), second, None, None)
+        .unwrap()
+        .votes;
+    assert_eq!(votes.len(), 1);
+    assert_eq!(votes[0].result, r#"{"price": "104"}"#);
+
+    let open = verifier
+        .list_tasks(
+            tasker.addr_str().unwrap(),
+            Some(TaskStatus::Open),
+            None,
+            None,
+        )
+        .unwrap()
+        .tasks;
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].task_id, second);
+    let all = verifier
+        .list_tasks(tasker.addr_str().unwrap(), None, None, Some(1))
+        .unwrap()
+        .tasks;
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].task_id, first);
+}
+
+/// Votes are matched against the allowed spread of every feed once the task is completed
+pub fn operator_stats<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let operators: Vec<_> = (3..=6).map(|i| chain.alt_signer(i)).collect();
+    let mock_operators = setup_mock_operators(
+        chain.clone(),
+        operators
+            .iter()
+            .map(|operator| InstantiateOperator {
+                addr: operator.addr().to_string(),
+                voting_power: 25u32,
+            })
+            .collect(),
+    );
+
+    let msg = InstantiateMsg {
+        operator_contract: mock_operators.addr_str().unwrap(),
+        threshold_percentage: Decimal::percent(50),
+        allowed_spread: Decimal::percent(10),
+        slashable_spread: Decimal::percent(20),
+        // 3 out of 4 operators complete the task
+        required_percentage: 75,
+        slash_amount: None,
+        aggregation: None,
+        owner: None,
+        max_result_bytes: None,
+        result_schema: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let tasker = setup_task_queue(chain.clone(), &verifier.addr_str().unwrap());
+
+    let payload = json!({"action": "get_price"});
+    let task_id = make_task(&tasker, "Get Price Task", None, &payload);
+
+    // the third price is outside the allowed spread from the median of 102, and the fourth operator misses the task
+    let votes = [
+        (&operators[0], r#"{"price": "100"}"#, 10),
+        (&operators[1], r#"{"price": "102"}"#, 20),
+        (&operators[2], r#"{"price": "150"}"#, 0),
+    ];
+    for (operator, result, wait) in votes {
+        chain.wait_seconds(wait).unwrap();
+        verifier
+            .call_as(operator)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+
+    // Missed tasks are only counted once recorded, which anyone can do, and recording again counts nothing
+    let candidates: Vec<String> = operators.iter().map(|o| o.addr().to_string()).collect();
+    let stats = verifier.operator_stats(candidates[3].clone()).unwrap();
+    assert_eq!(stats.tasks_missed, 0);
+    for _ in 0..2 {
+        verifier
+            .call_as(&chain.alt_signer(7))
+            .record_missed(tasker.addr_str().unwrap(), task_id, candidates.clone())
+            .unwrap();
+    }
+
+    // Nothing is missed on a task that is still open
+    let open_task = make_task(&tasker, "Get Price Task", None, &payload);
+    let err = verifier
+        .record_missed(tasker.addr_str().unwrap(), open_task, candidates)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&VerifierError::TaskNotCompleted.to_string()));
+
+    // (voted, matched, outside spread, missed, average latency)
+    let expected = [
+        (1, 1, 0, 0, Some(10)),
+        (1, 1, 0, 0, Some(30)),
+        (1, 0, 1, 0, Some(30)),
+        (0, 0, 0, 1, None),
+    ];
+    for (operator, expected) in operators.iter().zip(expected) {
+        let stats = verifier
+            .operator_stats(operator.addr().into_string())
+            .unwrap();
+        let stats = (
+            stats.tasks_voted,
+            stats.votes_matched,
+            stats.votes_outside_spread,
+            stats.tasks_missed,
+            stats.average_latency,
+        );
+        assert_eq!(stats, expected);
+    }
+
+    let all = verifier.list_operator_stats(None, None).unwrap().operators;
+    assert_eq!(all.len(), 4);
+    let page = verifier
+        .list_operator_stats(Some(all[0].operator.clone()), Some(2))
+        .unwrap()
+        .operators;
+    assert_eq!(page, all[1..3]);
+}
+
 #[track_caller]
 pub fn make_task<C: ChainState + TxHandler>(
     contract: &TasksContract<C>,
@@ -206,6 +831,9 @@ where
         verifier: verifier_addr.to_string(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let tasker = TasksContract::new(chain);
     tasker.upload().unwrap();
@@ -227,3 +855,20 @@ where
     mock_operators.instantiate(&msg, None, &[]).unwrap();
     mock_operators
 }
+
+pub fn setup_operators<C>(chain: C) -> OperatorsContract<C>
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let msg = OperatorsInstantiateMsg {
+        denom: DENOM.to_string(),
+        min_bond: Uint128::one(),
+        unbonding_period: Duration::new_seconds(600),
+        owner: None,
+    };
+    let operators = OperatorsContract::new(chain);
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+    operators
+}
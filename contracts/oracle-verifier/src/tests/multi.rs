@@ -1,6 +1,8 @@
-use cw_orch::prelude::MockBech32;
+use cosmwasm_std::coins;
+use cw_orch::prelude::*;
+use lavs_orch::AltSigner;
 
-use super::common::BECH_PREFIX;
+use super::common::{BECH_PREFIX, DENOM};
 
 #[test]
 fn happy_path_works() {
@@ -13,3 +15,44 @@ fn threshold_not_met() {
     let chain = MockBech32::new(BECH_PREFIX);
     super::common::threshold_not_met(chain);
 }
+
+#[test]
+fn multiple_feeds() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::multiple_feeds(chain);
+}
+
+#[test]
+fn task_votes_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::task_votes(chain);
+}
+
+#[test]
+fn unreported_feed() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::unreported_feed(chain);
+}
+
+#[test]
+fn invalid_results() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::invalid_results(chain);
+}
+
+#[test]
+fn operator_stats_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::operator_stats(chain);
+}
+
+#[test]
+fn slashing_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    for i in 3..=5 {
+        chain
+            .add_balance(&chain.alt_signer(i), coins(1_000, DENOM))
+            .unwrap();
+    }
+    super::common::slashing_works(chain);
+}
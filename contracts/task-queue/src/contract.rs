@@ -1,19 +1,19 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    from_json, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo, Reply,
-    Response, WasmMsg,
+    from_json, to_json_binary, Binary, CosmosMsg, Deps, DepsMut, Env, Event, MessageInfo, Order,
+    Reply, Response, StdResult, WasmMsg,
 };
-use cw2::set_contract_version;
+use cw2::{ensure_from_older_version, set_contract_version};
 
 use lavs_apis::interfaces::task_hooks::TaskHookPayload;
 use lavs_apis::interfaces::tasks as interface;
-use lavs_apis::tasks::{CustomExecuteMsg, CustomQueryMsg, TaskQueryMsg};
+use lavs_apis::tasks::{CustomQueryMsg, TaskQueryMsg};
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{CustomExecuteMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use crate::msg::{RequestType, ResponseType, Status};
-use crate::state::{Config, Task, CONFIG, TASKS, TASK_HOOKS};
+use crate::state::{reset_requestor_allowlist, Config, Task, CONFIG, TASKS, TASK_HOOKS};
 
 // version info for migration info
 const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
@@ -45,8 +45,10 @@ pub fn instantiate(
         vec![]
     };
 
+    let requestor = msg.requestor.clone();
     let config = Config::validate(deps.as_ref(), msg)?;
     CONFIG.save(deps.storage, &config)?;
+    reset_requestor_allowlist(deps.api, deps.storage, &requestor)?;
     cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -85,6 +87,10 @@ pub fn execute(
                 with_timeout_hooks,
             ),
             CustomExecuteMsg::Timeout { task_id } => execute::timeout(deps, env, info, task_id),
+            CustomExecuteMsg::TimeoutExpired { limit } => {
+                execute::timeout_expired(deps, env, info, limit)
+            }
+            CustomExecuteMsg::Cancel { task_id } => execute::cancel(deps, env, info, task_id),
             CustomExecuteMsg::AddHooks {
                 task_id,
                 hook_type,
@@ -107,6 +113,14 @@ pub fn execute(
             CustomExecuteMsg::UpdateTaskSpecificWhitelist { to_add, to_remove } => {
                 execute::update_task_specific_whitelist(deps, env, info, to_add, to_remove)
             }
+            CustomExecuteMsg::UpdateRequestorAllowlist { to_add, to_remove } => {
+                execute::update_requestor_allowlist(deps, info, to_add, to_remove)
+            }
+            CustomExecuteMsg::UpdateConfig {
+                requestor,
+                timeout,
+                verifier,
+            } => execute::update_config(deps, info, requestor, timeout, verifier),
         },
     }
 }
@@ -130,6 +144,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             CustomQueryMsg::ListOpen { start_after, limit } => Ok(to_json_binary(
                 &query::list_open(deps, env, start_after, limit)?,
             )?),
+            CustomQueryMsg::ListOpenByPriority { start_after, limit } => Ok(to_json_binary(
+                &query::list_open_by_priority(deps, env, start_after, limit)?,
+            )?),
             CustomQueryMsg::ListCompleted { start_after, limit } => Ok(to_json_binary(
                 &query::list_completed(deps, env, start_after, limit)?,
             )?),
@@ -143,18 +160,24 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
             CustomQueryMsg::TaskSpecificWhitelist { start_after, limit } => Ok(to_json_binary(
                 &query::task_specific_whitelist(deps, start_after, limit)?,
             )?),
+            CustomQueryMsg::RequestorAllowlist { start_after, limit } => Ok(to_json_binary(
+                &query::requestor_allowlist(deps, start_after, limit)?,
+            )?),
         },
     }
 }
 
 mod execute {
-    use cosmwasm_std::{ensure, BankMsg, SubMsg, WasmMsg};
+    use std::collections::BTreeMap;
+
+    use cosmwasm_std::{ensure, BankMsg, Coin, Storage, SubMsg, Uint128, WasmMsg};
     use cw_ownable::assert_owner;
+    use cw_storage_plus::Bound;
     use cw_utils::nonpayable;
     use lavs_apis::{
         events::task_queue_events::{
-            HookAddedEvent, HookRemovedEvent, TaskCompletedEvent, TaskCreatedEvent,
-            TaskExpiredEvent,
+            HookAddedEvent, HookRemovedEvent, TaskCancelledEvent, TaskCompletedEvent,
+            TaskCreatedEvent, TaskExpiredEvent, TasksSweptEvent,
         },
         id::TaskId,
         interfaces::task_hooks::{TaskHookExecuteMsg, TaskHookType},
@@ -162,7 +185,11 @@ mod execute {
         time::Duration,
     };
 
-    use crate::state::{check_timeout, RequestorConfig, TaskDeposit, Timing, TASK_DEPOSITS};
+    use crate::msg::{Requestor, TimeoutInfo};
+    use crate::state::{
+        check_timeout, validate_timeout_info, FeeConfig, RequestorConfig, TaskDeposit, Timing,
+        TASK_DEPOSITS,
+    };
 
     use super::*;
 
@@ -179,7 +206,23 @@ mod execute {
     ) -> Result<Response, ContractError> {
         let mut config = CONFIG.load(deps.storage)?;
         let timeout = check_timeout(&config.timeout, timeout)?;
-        config.requestor.check_requestor(&info)?;
+        let deposit = config.requestor.check_requestor(deps.storage, &info)?;
+        config.validate_payload(&payload)?;
+
+        // Tips go to the operators, so only accept them if operators are paid at all
+        let tip = deposit
+            .as_ref()
+            .filter(|d| !d.tip.is_zero())
+            .map(|d| Coin::new(d.tip, &d.coin.denom));
+        if tip.is_some() && !config.fees.as_ref().is_some_and(FeeConfig::pays_operators) {
+            return Err(ContractError::TipNotAccepted);
+        }
+        if let Some(tip) = &tip {
+            let denom = config.requestor.tip_denom().unwrap_or_default();
+            if tip.denom != denom {
+                return Err(ContractError::TipDenomNotAccepted(denom.to_string()));
+            }
+        }
 
         let timing = Timing::new(&env, timeout);
         let status = Status::new();
@@ -190,21 +233,15 @@ mod execute {
             payload,
             result: None,
             creator: info.sender.clone(),
+            tip,
         };
         let task_id = config.next_id;
         TASKS.save(deps.storage, task_id, &task)?;
         config.next_id = TaskId::new(task_id.u64() + 1);
         CONFIG.save(deps.storage, &config)?;
 
-        if let RequestorConfig::OpenPayment(coin) = config.requestor {
-            TASK_DEPOSITS.save(
-                deps.storage,
-                task_id,
-                &TaskDeposit {
-                    addr: info.sender.clone(),
-                    coin,
-                },
-            )?;
+        if let Some(deposit) = deposit {
+            TASK_DEPOSITS.save(deps.storage, task_id, &deposit)?;
         }
 
         // Prepare hooks
@@ -213,13 +250,19 @@ mod execute {
                 Ok(SubMsg::reply_always(
                     WasmMsg::Execute {
                         contract_addr: addr.to_string(),
-                        msg: to_json_binary(&TaskHookExecuteMsg::TaskCreatedHook(TaskResponse {
-                            description: task.description.clone(),
-                            status: task.status.clone(),
-                            id: task_id,
-                            payload: task.payload.clone(),
-                            result: None,
-                        }))?,
+                        msg: to_json_binary(
+                            &TaskHookExecuteMsg::<RequestType, ResponseType>::TaskCreatedHook(
+                                TaskResponse {
+                                    description: task.description.clone(),
+                                    status: task.status.clone(),
+                                    id: task_id,
+                                    payload: task.payload.clone(),
+                                    result: None,
+                                    created_height: task.timing.created_height,
+                                    expires: task.timing.expires_at,
+                                },
+                            ),
+                        )?,
                         funds: vec![],
                     },
                     TASK_HOOK_REPLY_ID,
@@ -284,13 +327,19 @@ mod execute {
         if info.sender != config.verifier {
             return Err(ContractError::Unauthorized {});
         }
+        config.validate_result(&response)?;
 
         // ensures it is open and not expired, then store response
         let mut task = TASKS.load(deps.storage, task_id)?;
         task.complete(&env, response)?;
         TASKS.save(deps.storage, task_id, &task)?;
 
-        if TASK_DEPOSITS.has(deps.storage, task_id) {
+        // Pay out the deposit, if any. Without a fee split, the task queue keeps it.
+        let mut payouts = vec![];
+        if let Some(deposit) = TASK_DEPOSITS.may_load(deps.storage, task_id)? {
+            if let Some(fees) = &config.fees {
+                payouts = fees.payouts(&deposit, &config.verifier, task_id)?;
+            }
             TASK_DEPOSITS.remove(deps.storage, task_id);
         }
 
@@ -307,6 +356,8 @@ mod execute {
                                 id: task_id,
                                 payload: task.payload.clone(),
                                 result: task.result.clone(),
+                                created_height: task.timing.created_height,
+                                expires: task.timing.expires_at,
                             },
                         ))?,
                         funds: vec![],
@@ -319,6 +370,7 @@ mod execute {
 
         let res = Response::new()
             .add_event(task_queue_event)
+            .add_messages(payouts)
             .add_submessages(hooks);
 
         Ok(res)
@@ -332,43 +384,194 @@ mod execute {
     ) -> Result<Response, ContractError> {
         nonpayable(&info)?;
 
-        // ensures it is open and expired
+        let (hooks, deposit) = expire_task(deps.storage, &env, task_id)?;
+
+        let task_queue_event = TaskExpiredEvent { task_id };
+
+        let mut res = Response::new()
+            .add_event(task_queue_event)
+            .add_submessages(hooks);
+
+        if let Some(task_deposit) = deposit {
+            res = res.add_message(BankMsg::Send {
+                to_address: task_deposit.addr.to_string(),
+                amount: vec![task_deposit.coin],
+            });
+        }
+
+        Ok(res)
+    }
+
+    /// Default and maximum number of tasks expired by one `TimeoutExpired`
+    const DEFAULT_SWEEP_LIMIT: u32 = 10;
+    const MAX_SWEEP_LIMIT: u32 = 50;
+
+    pub fn timeout_expired(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        limit: Option<u32>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let config = CONFIG.load(deps.storage)?;
+        let limit = limit.unwrap_or(DEFAULT_SWEEP_LIMIT).min(MAX_SWEEP_LIMIT) as usize;
+
+        // Earliest expiration first, only reading the open tasks which already expired
+        let expired_before = Bound::inclusive((env.block.time.nanos(), TaskId::new(u64::MAX)));
+        let expired = TASKS
+            .idx
+            .expiry
+            .sub_prefix(Status::Open {}.as_str())
+            .keys(deps.storage, None, Some(expired_before), Order::Ascending)
+            .take(limit)
+            .collect::<StdResult<Vec<_>>>()?;
+        if expired.is_empty() {
+            return Err(ContractError::NoExpiredTasks);
+        }
+
+        let mut res = Response::new();
+        // Sorted by denom, as required for bank sends
+        let mut bounty = BTreeMap::<String, Uint128>::new();
+        for &task_id in &expired {
+            let (hooks, deposit) = expire_task(deps.storage, &env, task_id)?;
+            res = res
+                .add_event(TaskExpiredEvent { task_id })
+                .add_submessages(hooks);
+
+            if let Some(task_deposit) = deposit {
+                let (refund, reward) = match &config.fees {
+                    Some(fees) => fees.sweep_split(&task_deposit.coin),
+                    None => (
+                        task_deposit.coin.clone(),
+                        Coin::new(0u128, &task_deposit.coin.denom),
+                    ),
+                };
+                if !refund.amount.is_zero() {
+                    res = res.add_message(BankMsg::Send {
+                        to_address: task_deposit.addr.to_string(),
+                        amount: vec![refund],
+                    });
+                }
+                *bounty.entry(reward.denom).or_default() += reward.amount;
+            }
+        }
+
+        let bounty: Vec<Coin> = bounty
+            .into_iter()
+            .filter(|(_, amount)| !amount.is_zero())
+            .map(|(denom, amount)| Coin { denom, amount })
+            .collect();
+        if !bounty.is_empty() {
+            res = res.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: bounty,
+            });
+        }
+
+        Ok(res.add_event(TasksSweptEvent {
+            count: expired.len() as u32,
+        }))
+    }
+
+    /// Ensures the task is open and past its timeout, and marks it as expired.
+    /// Returns the timeout hooks to fire and the deposit to refund, if any.
+    fn expire_task(
+        storage: &mut dyn Storage,
+        env: &Env,
+        task_id: TaskId,
+    ) -> Result<(Vec<SubMsg>, Option<TaskDeposit>), ContractError> {
+        let mut task = TASKS.load(storage, task_id)?;
+        task.expire(env)?;
+        TASKS.save(storage, task_id, &task)?;
+
+        // Prepare hooks
+        let hooks = TASK_HOOKS.prepare_hooks(storage, task_id, TaskHookType::Timeout, |addr| {
+            Ok(SubMsg::reply_always(
+                WasmMsg::Execute {
+                    contract_addr: addr.to_string(),
+                    msg: to_json_binary(
+                        &TaskHookExecuteMsg::<RequestType, ResponseType>::TaskTimeoutHook(
+                            TaskResponse {
+                                description: task.description.clone(),
+                                status: task.status.clone(),
+                                id: task_id,
+                                payload: task.payload.clone(),
+                                result: None,
+                                created_height: task.timing.created_height,
+                                expires: task.timing.expires_at,
+                            },
+                        ),
+                    )?,
+                    funds: vec![],
+                },
+                TASK_HOOK_REPLY_ID,
+            ))
+        })?;
+
+        let deposit = TASK_DEPOSITS.may_load(storage, task_id)?;
+        TASK_DEPOSITS.remove(storage, task_id);
+
+        Ok((hooks, deposit))
+    }
+
+    pub fn cancel(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        // Only the creator or the owner can cancel
         let mut task = TASKS.load(deps.storage, task_id)?;
-        task.expire(&env)?;
+        ensure!(
+            task.creator == info.sender || assert_owner(deps.storage, &info.sender).is_ok(),
+            ContractError::Unauthorized
+        );
+
+        // ensures it is open and not expired
+        task.cancel(&env)?;
         TASKS.save(deps.storage, task_id, &task)?;
 
         // Prepare hooks
         let hooks =
-            TASK_HOOKS.prepare_hooks(deps.storage, task_id, TaskHookType::Timeout, |addr| {
+            TASK_HOOKS.prepare_hooks(deps.storage, task_id, TaskHookType::Cancelled, |addr| {
                 Ok(SubMsg::reply_always(
                     WasmMsg::Execute {
                         contract_addr: addr.to_string(),
-                        msg: to_json_binary(&TaskHookExecuteMsg::TaskTimeoutHook(TaskResponse {
-                            description: task.description.clone(),
-                            status: task.status.clone(),
-                            id: task_id,
-                            payload: task.payload.clone(),
-                            result: None,
-                        }))?,
+                        msg: to_json_binary(
+                            &TaskHookExecuteMsg::<RequestType, ResponseType>::TaskCancelledHook(
+                                TaskResponse {
+                                    description: task.description.clone(),
+                                    status: task.status.clone(),
+                                    id: task_id,
+                                    payload: task.payload.clone(),
+                                    result: None,
+                                    created_height: task.timing.created_height,
+                                    expires: task.timing.expires_at,
+                                },
+                            ),
+                        )?,
                         funds: vec![],
                     },
                     TASK_HOOK_REPLY_ID,
                 ))
             })?;
 
-        let task_queue_event = TaskExpiredEvent { task_id };
+        let task_queue_event = TaskCancelledEvent { task_id };
 
         let mut res = Response::new()
             .add_event(task_queue_event)
             .add_submessages(hooks);
 
+        // The deposit is refunded in full
         if let Some(task_deposit) = TASK_DEPOSITS.may_load(deps.storage, task_id)? {
+            TASK_DEPOSITS.remove(deps.storage, task_id);
             res = res.add_message(BankMsg::Send {
                 to_address: task_deposit.addr.to_string(),
                 amount: vec![task_deposit.coin],
             });
-
-            TASK_DEPOSITS.remove(deps.storage, task_id);
         }
 
         Ok(res)
@@ -485,6 +688,51 @@ mod execute {
 
         Ok(Response::new().add_attribute("action", "Update_task_specific_whitelist"))
     }
+
+    pub fn update_requestor_allowlist(
+        deps: DepsMut,
+        info: MessageInfo,
+        to_add: Option<Vec<String>>,
+        to_remove: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        assert_owner(deps.storage, &info.sender)?;
+
+        crate::state::update_requestor_allowlist(deps.api, deps.storage, to_add, to_remove)?;
+
+        Ok(Response::new().add_attribute("action", "update_requestor_allowlist"))
+    }
+
+    pub fn update_config(
+        deps: DepsMut,
+        info: MessageInfo,
+        requestor: Option<Requestor>,
+        timeout: Option<TimeoutInfo>,
+        verifier: Option<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        assert_owner(deps.storage, &info.sender)?;
+
+        // Open tasks are not touched: their deposits are stored with the coin actually paid,
+        // and get refunded or paid out to whichever verifier is configured when they close.
+        let mut config = CONFIG.load(deps.storage)?;
+        if let Some(requestor) = requestor {
+            config.requestor = RequestorConfig::validate(deps.as_ref(), requestor.clone())?;
+            reset_requestor_allowlist(deps.api, deps.storage, &requestor)?;
+        }
+        if let Some(timeout) = timeout {
+            config.timeout = validate_timeout_info(timeout)?;
+        }
+        if let Some(verifier) = verifier {
+            config.verifier = deps.api.addr_validate(&verifier)?;
+            if let Some(fees) = &config.fees {
+                fees.check_verifier(deps.as_ref(), &config.verifier)?;
+            }
+        }
+        CONFIG.save(deps.storage, &config)?;
+
+        Ok(Response::new().add_attribute("action", "update_config"))
+    }
 }
 
 mod query {
@@ -493,13 +741,14 @@ mod query {
     use cw_storage_plus::Bound;
     use lavs_apis::{
         id::TaskId,
-        tasks::{ConfigResponse, TaskSpecificWhitelistResponse},
+        tasks::{ConfigResponse, RequestorAllowlistResponse, TaskSpecificWhitelistResponse},
     };
 
     use crate::msg::{
         CompletedTaskOverview, InfoStatus, ListCompletedResponse, ListOpenResponse, ListResponse,
         OpenTaskOverview, TaskInfoResponse, TaskResponse, TaskStatusResponse,
     };
+    use crate::state::REQUESTOR_ALLOWLIST;
 
     use super::*;
 
@@ -513,6 +762,8 @@ mod query {
             status,
             payload: task.payload,
             result: task.result,
+            created_height: task.timing.created_height,
+            expires: task.timing.expires_at,
         };
         Ok(r)
     }
@@ -542,6 +793,9 @@ mod query {
             requestor: config.requestor.into(),
             timeout: config.timeout,
             verifier: config.verifier.into_string(),
+            payload_schema: config.payload_schema,
+            result_schema: config.result_schema,
+            fees: config.fees.map(Into::into),
         };
         Ok(r)
     }
@@ -573,6 +827,7 @@ mod query {
                         Status::Expired {} => InfoStatus::Expired {
                             expired: task.timing.expires_at,
                         },
+                        Status::Cancelled { cancelled } => InfoStatus::Cancelled { cancelled },
                     };
 
                     TaskInfoResponse {
@@ -609,29 +864,73 @@ mod query {
                 start_after.map(Bound::exclusive),
                 cosmwasm_std::Order::Descending,
             )
-            .filter_map(|r| match r {
-                Ok((
-                    id,
-                    Task {
-                        payload,
-                        status: Status::Open {},
-                        timing,
-                        ..
-                    },
-                )) if timing.expires_at > env.block.time => Some(Ok(OpenTaskOverview {
-                    id,
-                    expires: timing.expires_at,
-                    payload,
-                })),
-                Ok(_) => None,
-                Err(e) => Some(Err(e)),
-            })
+            .filter_map(|r| open_overview(&env, r))
             .take(limit)
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(ListOpenResponse { tasks: open })
     }
 
+    pub fn list_open_by_priority(
+        deps: Deps,
+        env: Env,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> Result<ListOpenResponse, ContractError> {
+        let limit = limit.map(|v| v as usize).unwrap_or(usize::MAX);
+
+        // Continue after the given task, among those with the same tip
+        let start_after = match start_after {
+            Some(id) => {
+                let task = TASKS.load(deps.storage, id)?;
+                Some(Bound::exclusive((task.priority(), id)))
+            }
+            None => None,
+        };
+
+        let open = TASKS
+            .idx
+            .priority
+            .sub_prefix(Status::Open {}.as_str())
+            .range(
+                deps.storage,
+                None,
+                start_after,
+                cosmwasm_std::Order::Descending,
+            )
+            .filter_map(|r| open_overview(&env, r))
+            .take(limit)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ListOpenResponse { tasks: open })
+    }
+
+    /// Skips tasks which are past their timeout, but not marked as expired yet
+    fn open_overview(
+        env: &Env,
+        item: StdResult<(TaskId, Task)>,
+    ) -> Option<StdResult<OpenTaskOverview>> {
+        match item {
+            Ok((
+                id,
+                Task {
+                    payload,
+                    status: Status::Open {},
+                    timing,
+                    tip,
+                    ..
+                },
+            )) if timing.expires_at > env.block.time => Some(Ok(OpenTaskOverview {
+                id,
+                expires: timing.expires_at,
+                payload,
+                tip,
+            })),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+
     pub fn list_completed(
         deps: Deps,
         _env: Env,
@@ -704,6 +1003,51 @@ mod query {
 
         Ok(TaskSpecificWhitelistResponse { addrs })
     }
+
+    pub fn requestor_allowlist(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<RequestorAllowlistResponse, ContractError> {
+        let limit = limit.unwrap_or(30);
+        let binding = start_after
+            .map(|x| deps.api.addr_validate(&x))
+            .transpose()?;
+        let start_after = binding.as_ref().map(Bound::exclusive);
+
+        let addrs = REQUESTOR_ALLOWLIST
+            .keys(
+                deps.storage,
+                start_after,
+                None,
+                cosmwasm_std::Order::Ascending,
+            )
+            .take(limit as usize)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(RequestorAllowlistResponse { addrs })
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    // Only upgrades from an older version of this contract are allowed
+    let original = ensure_from_older_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    // Saving the open tasks again adds them to any new index, like the priority and expiry ones
+    let open = TASKS
+        .idx
+        .status
+        .prefix(Status::Open {}.as_str())
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (id, task) in open {
+        TASKS.save(deps.storage, id, &task)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("from_version", original.to_string()))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -2,6 +2,7 @@ use cosmwasm_std::StdError;
 use cw_ownable::OwnershipError;
 use cw_utils::PaymentError;
 use lavs_apis::{id::TaskId, interfaces::task_hooks::TaskHookError, time::Duration};
+use lavs_helpers::json_schema::SchemaError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -18,6 +19,9 @@ pub enum ContractError {
     #[error("{0}")]
     Ownership(#[from] OwnershipError),
 
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
     #[error("Unauthorized")]
     Unauthorized,
 
@@ -33,18 +37,57 @@ pub enum ContractError {
     #[error("You need to pay at least {0} {1} to create a task")]
     InsufficientPayment(u128, String),
 
+    #[error("At least one payment coin is required")]
+    MissingPaymentCoins,
+
+    #[error("Payment denom {0} is listed twice")]
+    DuplicatePaymentDenom(String),
+
+    #[error("Tips are only accepted when operators get a share of the fees")]
+    TipNotAccepted,
+
+    #[error("Tips are only accepted in {0}")]
+    TipDenomNotAccepted(String),
+
     #[error("Task is completed")]
     TaskCompleted,
 
     #[error("Task is expired")]
     TaskExpired,
 
+    #[error("Task is cancelled")]
+    TaskCancelled,
+
     #[error("Task is not yet expired")]
     TaskNotExpired,
 
     #[error("Missing result for completed task {id}")]
     MissingResultCompleted { id: TaskId },
 
+    #[error("Treasury and verifier shares cannot add up to more than 1")]
+    InvalidFeeSplit,
+
+    #[error("A treasury address is required for a non-zero treasury share")]
+    MissingTreasury,
+
+    #[error("The verifier and operator shares require a verifier which supports operator rewards")]
+    VerifierWithoutRewards,
+
+    #[error("The verifier share requires a verifier with a fee recipient to credit it to")]
+    VerifierWithoutFeeRecipient,
+
+    #[error("Sweep bounty cannot be more than 1")]
+    InvalidSweepBounty,
+
+    #[error("No expired tasks to time out")]
+    NoExpiredTasks,
+
+    #[error("Task payload does not match the schema: {0}")]
+    InvalidPayload(SchemaError),
+
+    #[error("Task result does not match the schema: {0}")]
+    InvalidResult(SchemaError),
+
     #[error("Unknown reply id {id}")]
     UnknownReplyId { id: u64 },
 }
@@ -1,7 +1,6 @@
 use cw_orch::{interface, prelude::*};
 
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
-type MigrateMsg = cosmwasm_std::Empty;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 pub const CONTRACT_ID: &str = env!("CARGO_PKG_NAME");
 
@@ -24,7 +23,8 @@ impl<Chain> Uploadable for Contract<Chain> {
                 crate::contract::instantiate,
                 crate::contract::query,
             )
-            .with_reply(crate::contract::reply), // .with_migrate(crate::contract::migrate),
+            .with_reply(crate::contract::reply)
+            .with_migrate(crate::contract::migrate),
         )
     }
 }
@@ -1,2 +1,6 @@
 // TODO: explicitly import the types we want?
 pub use lavs_apis::tasks::*;
+
+// The contract stores any JSON, optionally validated against the schemas in the config
+pub type ExecuteMsg = lavs_apis::tasks::ExecuteMsg<RequestType, ResponseType>;
+pub type CustomExecuteMsg = lavs_apis::tasks::CustomExecuteMsg<RequestType>;
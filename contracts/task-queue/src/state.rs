@@ -1,12 +1,19 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin, Deps, Env, MessageInfo, StdError, Timestamp};
+use cosmwasm_std::{
+    to_json_binary, Addr, Api, BankMsg, Coin, CosmosMsg, Decimal, Deps, Env, MessageInfo,
+    StdResult, Storage, Timestamp, Uint128, WasmMsg,
+};
 use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
-use cw_utils::must_pay;
+use cw_utils::{must_pay, one_coin, PaymentError};
 
 use lavs_apis::id::TaskId;
+use lavs_apis::interfaces::rewards::{
+    FeeRecipientResponse, PendingRewardsResponse, RewardsExecuteMsg, RewardsQueryMsg,
+};
 use lavs_apis::interfaces::task_hooks::TaskHooks;
-use lavs_apis::tasks::{Requestor, Status, TimeoutConfig};
+use lavs_apis::tasks::{FeeSplit, Requestor, Status, TimeoutConfig};
 use lavs_apis::time::Duration;
+use lavs_helpers::json_schema;
 
 use crate::error::ContractError;
 use crate::msg::{self, InstantiateMsg, RequestType, ResponseType};
@@ -18,14 +25,21 @@ pub const TASK_HOOKS: TaskHooks = TaskHooks::new(
     "task_specific_whitelist",
 );
 pub const TASK_DEPOSITS: Map<TaskId, TaskDeposit> = Map::new("task_deposits");
+/// Who can create tasks with the allowlist requestor modes
+pub const REQUESTOR_ALLOWLIST: Map<&Addr, ()> = Map::new("requestor_allowlist");
 
 pub struct TaskIndexes<'a> {
     pub status: MultiIndex<'a, &'a str, Task, TaskId>,
+    /// Tasks by status and tip amount, to list open tasks by priority
+    pub priority: MultiIndex<'a, (&'a str, u128), Task, TaskId>,
+    /// Tasks by status and expiration in nanoseconds, to find the expired open tasks
+    pub expiry: MultiIndex<'a, (&'a str, u64), Task, TaskId>,
 }
 
 impl<'a> IndexList<Task> for TaskIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Task>> + '_> {
-        Box::new(std::iter::once(&self.status as &dyn Index<Task>))
+        let v: Vec<&dyn Index<Task>> = vec![&self.status, &self.priority, &self.expiry];
+        Box::new(v.into_iter())
     }
 }
 
@@ -33,6 +47,16 @@ pub const TASKS: IndexedMap<TaskId, Task, TaskIndexes<'static>> = IndexedMap::ne
     "tasks",
     TaskIndexes {
         status: MultiIndex::new(|_, d: &Task| d.status.as_str(), "tasks", "tasks__status"),
+        priority: MultiIndex::new(
+            |_, d: &Task| (d.status.as_str(), d.priority()),
+            "tasks",
+            "tasks__priority",
+        ),
+        expiry: MultiIndex::new(
+            |_, d: &Task| (d.status.as_str(), d.timing.expires_at.nanos()),
+            "tasks",
+            "tasks__expiry",
+        ),
     },
 );
 
@@ -42,13 +66,23 @@ pub struct Config {
     pub requestor: RequestorConfig,
     pub timeout: TimeoutConfig,
     pub verifier: Addr,
+    /// Schema every task payload must match, if any
+    pub payload_schema: Option<serde_json::Value>,
+    /// Schema every task result must match, if any
+    pub result_schema: Option<serde_json::Value>,
+    /// How deposits are split upon completion, they are kept by the task queue if unset
+    #[serde(default)]
+    pub fees: Option<FeeConfig>,
 }
 
 #[cw_serde]
 pub struct TaskDeposit {
     pub addr: Addr,
-    /// We store a coin here in case we ever want to support updates to the RequestorConfig
+    /// The coin actually paid, so open tasks are unaffected by updates to the RequestorConfig
     pub coin: Coin,
+    /// The part of `coin` paid above the task price
+    #[serde(default)]
+    pub tip: Uint128,
 }
 
 impl Config {
@@ -56,59 +90,357 @@ impl Config {
         let requestor = RequestorConfig::validate(deps, input.requestor)?;
         let timeout = validate_timeout_info(input.timeout)?;
         let verifier = deps.api.addr_validate(&input.verifier)?;
+        for schema in [&input.payload_schema, &input.result_schema]
+            .into_iter()
+            .flatten()
+        {
+            json_schema::check_schema(schema)?;
+        }
+        let fees = input
+            .fees
+            .map(|fees| FeeConfig::validate(deps, fees, &verifier))
+            .transpose()?;
         Ok(Config {
             next_id: TaskId::new(1),
             requestor,
             timeout,
             verifier,
+            payload_schema: input.payload_schema,
+            result_schema: input.result_schema,
+            fees,
         })
     }
+
+    pub fn validate_payload(&self, payload: &RequestType) -> Result<(), ContractError> {
+        match &self.payload_schema {
+            Some(schema) => {
+                json_schema::validate(schema, payload).map_err(ContractError::InvalidPayload)
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn validate_result(&self, result: &ResponseType) -> Result<(), ContractError> {
+        match &self.result_schema {
+            Some(schema) => {
+                json_schema::validate(schema, result).map_err(ContractError::InvalidResult)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct FeeConfig {
+    pub treasury: Option<Addr>,
+    pub treasury_share: Decimal,
+    pub verifier_share: Decimal,
+    #[serde(default)]
+    pub sweep_bounty: Decimal,
+}
+
+impl FeeConfig {
+    pub fn validate(deps: Deps, input: FeeSplit, verifier: &Addr) -> Result<Self, ContractError> {
+        if input.treasury_share + input.verifier_share > Decimal::one() {
+            return Err(ContractError::InvalidFeeSplit);
+        }
+        let treasury = input
+            .treasury
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?;
+        if treasury.is_none() && !input.treasury_share.is_zero() {
+            return Err(ContractError::MissingTreasury);
+        }
+        if input.sweep_bounty > Decimal::one() {
+            return Err(ContractError::InvalidSweepBounty);
+        }
+        let fees = FeeConfig {
+            treasury,
+            treasury_share: input.treasury_share,
+            verifier_share: input.verifier_share,
+            sweep_bounty: input.sweep_bounty,
+        };
+        fees.check_verifier(deps, verifier)?;
+        Ok(fees)
+    }
+
+    /// Only a verifier supporting operator rewards can be paid a share, anything else would be stuck there
+    /// or fail the completion. Its own share is credited to its fee recipient, so that must be set.
+    pub fn check_verifier(&self, deps: Deps, verifier: &Addr) -> Result<(), ContractError> {
+        if self.verifier_share.is_zero() && !self.pays_operators() {
+            return Ok(());
+        }
+        let query = RewardsQueryMsg::PendingRewards {
+            operator: verifier.to_string(),
+        };
+        deps.querier
+            .query_wasm_smart::<PendingRewardsResponse>(verifier, &query)
+            .map_err(|_| ContractError::VerifierWithoutRewards)?;
+        if !self.verifier_share.is_zero() {
+            let res: FeeRecipientResponse = deps
+                .querier
+                .query_wasm_smart(verifier, &RewardsQueryMsg::FeeRecipient {})
+                .map_err(|_| ContractError::VerifierWithoutRewards)?;
+            if res.recipient.is_none() {
+                return Err(ContractError::VerifierWithoutFeeRecipient);
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits the deposit of a swept task into the refund and the sweeper's bounty
+    pub fn sweep_split(&self, deposit: &Coin) -> (Coin, Coin) {
+        let bounty = deposit.amount.mul_floor(self.sweep_bounty);
+        (
+            Coin::new(deposit.amount - bounty, &deposit.denom),
+            Coin::new(bounty, &deposit.denom),
+        )
+    }
+
+    /// Whether operators get a share of deposits, so the verifier is sent `DistributeRewards`
+    pub fn pays_operators(&self) -> bool {
+        self.treasury_share + self.verifier_share < Decimal::one()
+    }
+
+    /// Splits the deposit of a completed task between the treasury, the verifier and the operators.
+    /// Operators get the remainder and the tip, which are sent to the verifier to distribute by vote.
+    /// The verifier share is sent with `CollectFee`, to be claimed by the verifier's fee recipient.
+    pub fn payouts(
+        &self,
+        deposit: &TaskDeposit,
+        verifier: &Addr,
+        task_id: TaskId,
+    ) -> StdResult<Vec<CosmosMsg>> {
+        let denom = &deposit.coin.denom;
+        let price = deposit.coin.amount - deposit.tip;
+        let treasury_amount = price.mul_floor(self.treasury_share);
+        let verifier_amount = price.mul_floor(self.verifier_share);
+        let operators_amount = price - treasury_amount - verifier_amount + deposit.tip;
+
+        let mut msgs = vec![];
+        if let Some(treasury) = &self.treasury {
+            if !treasury_amount.is_zero() {
+                msgs.push(
+                    BankMsg::Send {
+                        to_address: treasury.to_string(),
+                        amount: vec![Coin::new(treasury_amount, denom)],
+                    }
+                    .into(),
+                );
+            }
+        }
+        if !verifier_amount.is_zero() {
+            msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: verifier.to_string(),
+                    msg: to_json_binary(&RewardsExecuteMsg::CollectFee { task_id })?,
+                    funds: vec![Coin::new(verifier_amount, denom)],
+                }
+                .into(),
+            );
+        }
+        if !operators_amount.is_zero() {
+            msgs.push(
+                WasmMsg::Execute {
+                    contract_addr: verifier.to_string(),
+                    msg: to_json_binary(&RewardsExecuteMsg::DistributeRewards { task_id })?,
+                    funds: vec![Coin::new(operators_amount, denom)],
+                }
+                .into(),
+            );
+        }
+        Ok(msgs)
+    }
 }
 
+impl From<FeeConfig> for FeeSplit {
+    fn from(val: FeeConfig) -> Self {
+        FeeSplit {
+            treasury: val.treasury.map(Addr::into_string),
+            treasury_share: val.treasury_share,
+            verifier_share: val.verifier_share,
+            sweep_bounty: val.sweep_bounty,
+        }
+    }
+}
+
+/// The allowlist itself is stored in `REQUESTOR_ALLOWLIST`
 #[cw_serde]
 pub enum RequestorConfig {
     Fixed(Addr),
     OpenPayment(Coin),
+    Allowlist,
+    AllowlistWithPayment(Vec<Coin>),
+    OpenPaymentAnyOf(Vec<Coin>),
 }
 
 impl RequestorConfig {
-    pub fn validate(deps: Deps, input: msg::Requestor) -> Result<Self, StdError> {
+    pub fn validate(deps: Deps, input: msg::Requestor) -> Result<Self, ContractError> {
         match input {
             msg::Requestor::Fixed(addr) => {
                 Ok(RequestorConfig::Fixed(deps.api.addr_validate(&addr)?))
             }
             msg::Requestor::OpenPayment(coin) => Ok(RequestorConfig::OpenPayment(coin)),
+            msg::Requestor::Allowlist(addrs) => {
+                validate_addrs(deps.api, &addrs)?;
+                Ok(RequestorConfig::Allowlist)
+            }
+            msg::Requestor::AllowlistWithPayment { addrs, coins } => {
+                validate_addrs(deps.api, &addrs)?;
+                validate_coins(&coins)?;
+                Ok(RequestorConfig::AllowlistWithPayment(coins))
+            }
+            msg::Requestor::OpenPaymentAnyOf(coins) => {
+                validate_coins(&coins)?;
+                Ok(RequestorConfig::OpenPaymentAnyOf(coins))
+            }
         }
     }
 
-    pub fn check_requestor(&self, info: &MessageInfo) -> Result<(), ContractError> {
+    /// Ensures the sender may create a task, returning the deposit to hold for it (if any)
+    pub fn check_requestor(
+        &self,
+        storage: &dyn Storage,
+        info: &MessageInfo,
+    ) -> Result<Option<TaskDeposit>, ContractError> {
         match self {
             RequestorConfig::Fixed(addr) => {
                 if info.sender != addr {
                     return Err(ContractError::Unauthorized);
                 }
+                Ok(None)
             }
             RequestorConfig::OpenPayment(needed) => {
                 let paid = must_pay(info, &needed.denom)?;
-                if paid < needed.amount {
-                    return Err(ContractError::InsufficientPayment(
-                        needed.amount.u128(),
-                        needed.denom.clone(),
-                    ));
-                }
+                check_payment(info, Coin::new(paid, &needed.denom), needed).map(Some)
             }
+            RequestorConfig::Allowlist => {
+                check_allowlist(storage, &info.sender)?;
+                Ok(None)
+            }
+            RequestorConfig::AllowlistWithPayment(coins) => {
+                check_allowlist(storage, &info.sender)?;
+                check_payment_any_of(info, coins).map(Some)
+            }
+            RequestorConfig::OpenPaymentAnyOf(coins) => check_payment_any_of(info, coins).map(Some),
+        }
+    }
+
+    /// Tips are only accepted in the first payment denom, so task priorities can be compared
+    pub fn tip_denom(&self) -> Option<&str> {
+        match self {
+            RequestorConfig::Fixed(_) | RequestorConfig::Allowlist => None,
+            RequestorConfig::OpenPayment(coin) => Some(&coin.denom),
+            RequestorConfig::AllowlistWithPayment(coins)
+            | RequestorConfig::OpenPaymentAnyOf(coins) => coins.first().map(|c| c.denom.as_str()),
         }
-        Ok(())
     }
 }
 
+/// Addresses are stored separately, so they are left empty here
 impl From<RequestorConfig> for Requestor {
     fn from(val: RequestorConfig) -> Self {
         match val {
             RequestorConfig::Fixed(addr) => Requestor::Fixed(addr.into_string()),
             RequestorConfig::OpenPayment(coin) => Requestor::OpenPayment(coin),
+            RequestorConfig::Allowlist => Requestor::Allowlist(vec![]),
+            RequestorConfig::AllowlistWithPayment(coins) => Requestor::AllowlistWithPayment {
+                addrs: vec![],
+                coins,
+            },
+            RequestorConfig::OpenPaymentAnyOf(coins) => Requestor::OpenPaymentAnyOf(coins),
+        }
+    }
+}
+
+fn validate_addrs(api: &dyn Api, addrs: &[String]) -> Result<(), ContractError> {
+    for addr in addrs {
+        api.addr_validate(addr)?;
+    }
+    Ok(())
+}
+
+fn validate_coins(coins: &[Coin]) -> Result<(), ContractError> {
+    if coins.is_empty() {
+        return Err(ContractError::MissingPaymentCoins);
+    }
+    for (i, coin) in coins.iter().enumerate() {
+        if coins[..i].iter().any(|c| c.denom == coin.denom) {
+            return Err(ContractError::DuplicatePaymentDenom(coin.denom.clone()));
         }
     }
+    Ok(())
+}
+
+fn check_allowlist(storage: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    if !REQUESTOR_ALLOWLIST.has(storage, sender) {
+        return Err(ContractError::Unauthorized);
+    }
+    Ok(())
+}
+
+/// Exactly one coin must be sent, matching one of the accepted denoms
+fn check_payment_any_of(
+    info: &MessageInfo,
+    accepted: &[Coin],
+) -> Result<TaskDeposit, ContractError> {
+    let paid = one_coin(info)?;
+    let needed = accepted
+        .iter()
+        .find(|c| c.denom == paid.denom)
+        .ok_or_else(|| PaymentError::ExtraDenom(paid.denom.clone()))?;
+    check_payment(info, paid, needed)
+}
+
+/// Anything paid above the price is a tip
+fn check_payment(
+    info: &MessageInfo,
+    paid: Coin,
+    needed: &Coin,
+) -> Result<TaskDeposit, ContractError> {
+    if paid.amount < needed.amount {
+        return Err(ContractError::InsufficientPayment(
+            needed.amount.u128(),
+            needed.denom.clone(),
+        ));
+    }
+    Ok(TaskDeposit {
+        addr: info.sender.clone(),
+        tip: paid.amount - needed.amount,
+        coin: paid,
+    })
+}
+
+/// Replaces the allowlist with the addresses of the requestor config (if any)
+pub fn reset_requestor_allowlist(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    requestor: &msg::Requestor,
+) -> Result<(), ContractError> {
+    REQUESTOR_ALLOWLIST.clear(storage);
+    let addrs = match requestor {
+        msg::Requestor::Allowlist(addrs) => addrs,
+        msg::Requestor::AllowlistWithPayment { addrs, .. } => addrs,
+        _ => return Ok(()),
+    };
+    update_requestor_allowlist(api, storage, Some(addrs.clone()), None)
+}
+
+pub fn update_requestor_allowlist(
+    api: &dyn Api,
+    storage: &mut dyn Storage,
+    to_add: Option<Vec<String>>,
+    to_remove: Option<Vec<String>>,
+) -> Result<(), ContractError> {
+    for addr in to_add.unwrap_or_default() {
+        let addr = api.addr_validate(&addr)?;
+        REQUESTOR_ALLOWLIST.save(storage, &addr, &())?;
+    }
+    for addr in to_remove.unwrap_or_default() {
+        let addr = api.addr_validate(&addr)?;
+        REQUESTOR_ALLOWLIST.remove(storage, &addr);
+    }
+    Ok(())
 }
 
 pub fn validate_timeout_info(input: msg::TimeoutInfo) -> Result<TimeoutConfig, ContractError> {
@@ -145,14 +477,23 @@ pub struct Task {
     pub timing: Timing,
     pub payload: RequestType,
     pub result: Option<ResponseType>,
+    /// Paid on top of the task price, which sets its priority
+    #[serde(default)]
+    pub tip: Option<Coin>,
 }
 
 impl Task {
+    /// Tasks with higher tips should be picked first. All tips share the denom given by
+    /// `RequestorConfig::tip_denom`, so the amounts can be compared directly.
+    pub fn priority(&self) -> u128 {
+        self.tip.as_ref().map_or(0, |c| c.amount.u128())
+    }
+
     pub fn validate_status(&self, env: &Env) -> Status {
         match self.status {
             Status::Open {} if !self.timing.is_expired(env) => self.status.clone(),
             Status::Expired {} | Status::Open {} => Status::Expired {},
-            Status::Completed { .. } => self.status.clone(),
+            Status::Completed { .. } | Status::Cancelled { .. } => self.status.clone(),
         }
     }
 }
@@ -187,6 +528,7 @@ impl Task {
             Status::Open {} if !self.timing.is_expired(env) => {}
             Status::Open {} | Status::Expired {} => return Err(ContractError::TaskExpired),
             Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled { .. } => return Err(ContractError::TaskCancelled),
         };
         self.status = Status::completed(env);
         self.result = Some(result);
@@ -199,8 +541,20 @@ impl Task {
             Status::Open {} => return Err(ContractError::TaskNotExpired),
             Status::Expired {} => return Err(ContractError::TaskExpired),
             Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled { .. } => return Err(ContractError::TaskCancelled),
         };
         self.status = Status::Expired {};
         Ok(())
     }
+
+    pub fn cancel(&mut self, env: &Env) -> Result<(), ContractError> {
+        match self.status {
+            Status::Open {} if !self.timing.is_expired(env) => {}
+            Status::Open {} | Status::Expired {} => return Err(ContractError::TaskExpired),
+            Status::Completed { .. } => return Err(ContractError::TaskCompleted),
+            Status::Cancelled { .. } => return Err(ContractError::TaskCancelled),
+        };
+        self.status = Status::cancelled(env);
+        Ok(())
+    }
 }
@@ -1,10 +1,11 @@
-use cosmwasm_std::{Timestamp, Uint128};
+use cosmwasm_std::{coin, coins, Decimal, Timestamp, Uint128};
 use cw_orch::environment::{ChainState, CwEnv, Environment, IndexResponse, QueryHandler};
 use cw_orch::prelude::*;
 use lavs_apis::id::TaskId;
 use lavs_apis::interfaces::task_hooks::TaskHookType;
-use lavs_apis::tasks::{InfoStatus, TaskInfoResponse, TaskStatus};
+use lavs_apis::tasks::{FeeSplit, InfoStatus, TaskInfoResponse, TaskStatus};
 use lavs_apis::time::Duration;
+use lavs_helpers::json_schema::SchemaError;
 use mock_hook_consumer::msg::{ExecuteMsgFns, QueryMsgFns as _};
 use serde_json::json;
 
@@ -12,9 +13,11 @@ use crate::error::ContractError;
 use crate::interface::Contract as TaskContract;
 use crate::msg::{
     CompletedTaskOverview, InstantiateMsg, ListCompletedResponse, ListOpenResponse, ListResponse,
-    OpenTaskOverview, Requestor, Status, TimeoutInfo,
+    MigrateMsg, OpenTaskOverview, Requestor, Status, TimeoutInfo,
 };
 use crate::tests::multi::DENOM;
+use lavs_verifier_simple::interface::Contract as VerifierContract;
+use lavs_verifier_simple::msg::InstantiateMsg as VerifierInstantiateMsg;
 use mock_hook_consumer::interface::Contract as MockHookConsumerContract;
 
 // FIXME: any way to get these as one import, rather than import all sub traits?
@@ -51,6 +54,9 @@ where
         verifier: verifier.addr().into(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
 
     let contract = setup(chain.clone(), msg);
@@ -247,6 +253,7 @@ where
             id: three,
             expires: task_three_expiration, // we waited two blocks to create
             payload: payload_three,
+            tip: None,
         }
     );
 
@@ -257,6 +264,7 @@ where
             id: two,
             expires: task_two_expiration, // we waited one block to create
             payload: payload_two,
+            tip: None,
         }
     );
 
@@ -267,6 +275,7 @@ where
             id: one,
             expires: task_one_expiration,
             payload: payload_one,
+            tip: None,
         }
     );
 
@@ -606,6 +615,9 @@ where
         verifier: verifier.addr().into(),
         owner: None, // defaults to sender
         task_specific_whitelist: Some(vec![mock_consumer.addr_str().unwrap()]),
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let task_contract = setup(chain.clone(), msg);
 
@@ -795,6 +807,9 @@ where
         verifier: verifier.addr().into(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let task_contract = setup(chain.clone(), msg);
 
@@ -850,7 +865,724 @@ where
     );
 }
 
+pub fn schema_validation_works<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let mut msg = InstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: Some(json!({ "oneOf": [] })),
+        result_schema: None,
+        fees: None,
+    };
+
+    // Unsupported schemas are rejected up front
+    let contract = TaskContract::new(chain.clone());
+    contract.upload().unwrap();
+    let err = contract.instantiate(&msg, None, &[]).unwrap_err();
+    let expected = ContractError::Schema(SchemaError::InvalidSchema {
+        path: "$".to_string(),
+        reason: "unsupported keyword oneOf".to_string(),
+    });
+    assert!(
+        err.root().to_string().contains(&expected.to_string()),
+        "unexpected error: {}",
+        err.root(),
+    );
+
+    msg.payload_schema = Some(json!({
+        "type": "object",
+        "properties": {
+            "pair": { "type": "array", "items": { "type": "string" }, "minItems": 2, "maxItems": 2 }
+        },
+        "required": ["pair"]
+    }));
+    msg.result_schema = Some(json!({
+        "type": "object",
+        "properties": { "price": { "type": "string" } },
+        "required": ["price"]
+    }));
+    contract.instantiate(&msg, None, &[]).unwrap();
+
+    let config = contract.config().unwrap();
+    assert_eq!(config.payload_schema, msg.payload_schema);
+    assert_eq!(config.result_schema, msg.result_schema);
+
+    // Malformed payloads are rejected
+    let err = contract
+        .create("Bad", None, json!({ "pair": ["eth"] }), None, None, &[])
+        .unwrap_err();
+    let expected = ContractError::InvalidPayload(SchemaError::Mismatch {
+        path: "$.pair".to_string(),
+        reason: "length 1 below minItems 2".to_string(),
+    });
+    assert!(
+        err.root().to_string().contains(&expected.to_string()),
+        "unexpected error: {}",
+        err.root(),
+    );
+
+    let task_id = make_task(&contract, "Good", None, &json!({ "pair": ["eth", "usd"] }));
+
+    // Malformed results are rejected, so the task stays open
+    let err = contract
+        .call_as(&verifier)
+        .complete(task_id, json!({ "price": 1234 }))
+        .unwrap_err();
+    let expected = ContractError::InvalidResult(SchemaError::Mismatch {
+        path: "$.price".to_string(),
+        reason: "expected type \"string\"".to_string(),
+    });
+    assert!(
+        err.root().to_string().contains(&expected.to_string()),
+        "unexpected error: {}",
+        err.root(),
+    );
+    let task = contract.task(task_id).unwrap();
+    assert_eq!(task.status, Status::Open {});
+
+    contract
+        .call_as(&verifier)
+        .complete(task_id, json!({ "price": "1234.56" }))
+        .unwrap();
+    let task = contract.task(task_id).unwrap();
+    assert_eq!(task.result, Some(json!({ "price": "1234.56" })));
+}
+
 #[track_caller]
+/// The owner can change requestor, timeout and verifier, while open tasks keep their deposits
+pub fn update_config_works<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let old_verifier = chain.alt_signer(VERIFIER_INDEX);
+    let new_verifier = chain.alt_signer(VERIFIER_INDEX + 1);
+    let msg = InstantiateMsg {
+        requestor: Requestor::OpenPayment(coin(100, DENOM)),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: old_verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let contract = setup(chain.clone(), msg);
+    let payload = json!({"x": 17});
+    let balance = |addr: &Addr| {
+        chain
+            .balance(addr, Some(DENOM.to_string()))
+            .unwrap()
+            .first()
+            .map(|c| c.amount.u128())
+            .unwrap_or_default()
+    };
+
+    // Paid under the old config
+    let first = make_task_with_funds(&contract, "First", None, &payload, &coins(100, DENOM));
+    let second = make_task_with_funds(&contract, "Second", None, &payload, &coins(100, DENOM));
+
+    // Only the owner can update the config
+    let err = contract
+        .call_as(&old_verifier)
+        .update_config(None, None, Some(new_verifier.addr().to_string()))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&cw_ownable::OwnershipError::NotOwner.to_string()));
+
+    // Raise the price, extend the timeouts and swap the verifier
+    let timeout = mock_timeout(Duration::new_seconds(1000));
+    contract
+        .update_config(
+            Some(Requestor::OpenPayment(coin(250, DENOM))),
+            Some(timeout.clone()),
+            Some(new_verifier.addr().to_string()),
+        )
+        .unwrap();
+    let config = contract.config().unwrap();
+    assert_eq!(config.requestor, Requestor::OpenPayment(coin(250, DENOM)));
+    assert_eq!(config.timeout.maximum, timeout.maximum.unwrap());
+    assert_eq!(config.verifier, new_verifier.addr().to_string());
+
+    // Invalid timeouts are rejected
+    let err = contract
+        .update_config(
+            None,
+            Some(TimeoutInfo {
+                default: Duration::new_seconds(10),
+                minimum: Some(Duration::new_seconds(20)),
+                maximum: None,
+            }),
+            None,
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InvalidTimeoutInfo.to_string()));
+
+    // New tasks use the new price and timeouts
+    let err = contract
+        .create(
+            "Cheap".to_string(),
+            None,
+            payload.clone(),
+            None,
+            None,
+            &coins(100, DENOM),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientPayment(250, DENOM.to_string()).to_string()));
+    make_task_with_funds(
+        &contract,
+        "Long",
+        Duration::new_seconds(1500),
+        &payload,
+        &coins(250, DENOM),
+    );
+
+    // Only the new verifier can complete open tasks, and without a fee split the deposit is kept
+    let result = json!({"y": 289});
+    contract
+        .call_as(&old_verifier)
+        .complete(first, result.clone())
+        .unwrap_err();
+    let held = balance(&contract.address().unwrap());
+    contract
+        .call_as(&new_verifier)
+        .complete(first, result)
+        .unwrap();
+    assert_eq!(balance(&new_verifier.addr()), 0);
+    assert_eq!(balance(&contract.address().unwrap()), held);
+
+    // Switching to a fixed requestor still refunds the original deposit on timeout
+    contract
+        .update_config(
+            Some(Requestor::Fixed(chain.sender_addr().into())),
+            None,
+            None,
+        )
+        .unwrap();
+    chain.wait_seconds(300).unwrap();
+    let before = balance(&chain.sender_addr());
+    contract.timeout(second).unwrap();
+    assert_eq!(balance(&chain.sender_addr()), before + 100);
+}
+
+/// Only allowlisted addresses can create tasks, paying in any of the accepted denoms
+pub fn requestor_allowlist_works<C>(chain: C, other_denom: &str)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let alice = chain.alt_signer(1);
+    let bob = chain.alt_signer(2);
+    let accepted = vec![coin(100, DENOM), coin(50, other_denom)];
+    let mut msg = InstantiateMsg {
+        requestor: Requestor::AllowlistWithPayment {
+            addrs: vec![alice.addr().to_string()],
+            coins: vec![coin(100, DENOM), coin(200, DENOM)],
+        },
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+
+    // Each denom can only be listed once
+    let contract = TaskContract::new(chain.clone());
+    contract.upload().unwrap();
+    let err = contract.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::DuplicatePaymentDenom(DENOM.to_string()).to_string()));
+
+    msg.requestor = Requestor::AllowlistWithPayment {
+        addrs: vec![alice.addr().to_string()],
+        coins: accepted.clone(),
+    };
+    contract.instantiate(&msg, None, &[]).unwrap();
+    let config = contract.config().unwrap();
+    assert_eq!(
+        config.requestor,
+        Requestor::AllowlistWithPayment {
+            addrs: vec![],
+            coins: accepted.clone(),
+        }
+    );
+
+    let create = |sender: &C::Sender, funds: &[Coin]| {
+        contract.call_as(sender).create(
+            "Allowlisted".to_string(),
+            None,
+            json!({"x": 17}),
+            None,
+            None,
+            funds,
+        )
+    };
+
+    // Alice can pay in either denom, Bob is not allowed
+    create(&alice, &coins(100, DENOM)).unwrap();
+    create(&alice, &coins(50, other_denom)).unwrap();
+    let err = create(&bob, &coins(100, DENOM)).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+
+    // Not enough, or the wrong denom
+    let err = create(&alice, &coins(20, other_denom)).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InsufficientPayment(50, other_denom.to_string()).to_string()));
+    create(&alice, &[]).unwrap_err();
+
+    // Only the owner can change the allowlist
+    contract
+        .call_as(&alice)
+        .update_requestor_allowlist(Some(vec![bob.addr().to_string()]), None)
+        .unwrap_err();
+    contract
+        .update_requestor_allowlist(
+            Some(vec![bob.addr().to_string()]),
+            Some(vec![alice.addr().to_string()]),
+        )
+        .unwrap();
+    create(&bob, &coins(100, DENOM)).unwrap();
+    create(&alice, &coins(100, DENOM)).unwrap_err();
+
+    // Paginate through the allowlist
+    contract
+        .update_requestor_allowlist(Some(vec![alice.addr().to_string()]), None)
+        .unwrap();
+    let mut expected = [alice.addr(), bob.addr()];
+    expected.sort();
+    let first = contract.requestor_allowlist(None, Some(1)).unwrap();
+    assert_eq!(first.addrs, expected[..1]);
+    let rest = contract
+        .requestor_allowlist(Some(first.addrs[0].to_string()), None)
+        .unwrap();
+    assert_eq!(rest.addrs, expected[1..]);
+
+    // Switching to open payment clears the allowlist and lets anyone pay
+    contract
+        .update_config(Some(Requestor::OpenPaymentAnyOf(accepted)), None, None)
+        .unwrap();
+    let list = contract.requestor_allowlist(None, None).unwrap();
+    assert!(list.addrs.is_empty());
+    create(&verifier, &coins(50, other_denom)).unwrap();
+}
+
+/// Anyone can expire all tasks past their timeout at once, for a share of the deposits
+pub fn timeout_expired_works<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let sweeper = chain.alt_signer(1);
+    let mut fees = FeeSplit {
+        treasury: Some(chain.alt_signer(VERIFIER_INDEX + 1).addr().into()),
+        treasury_share: Decimal::one(),
+        verifier_share: Decimal::zero(),
+        sweep_bounty: Decimal::percent(150),
+    };
+    let mut msg = InstantiateMsg {
+        requestor: Requestor::OpenPayment(coin(100, DENOM)),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: Some(fees.clone()),
+    };
+
+    let contract = TaskContract::new(chain.clone());
+    contract.upload().unwrap();
+    let err = contract.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::InvalidSweepBounty.to_string()));
+
+    fees.sweep_bounty = Decimal::percent(10);
+    msg.fees = Some(fees);
+    contract.instantiate(&msg, None, &[]).unwrap();
+    let balance = |addr: &Addr| {
+        chain
+            .balance(addr, Some(DENOM.to_string()))
+            .unwrap()
+            .first()
+            .map(|c| c.amount.u128())
+            .unwrap_or_default()
+    };
+
+    // One long task, created before two short ones
+    let payload = json!({"x": 17});
+    let funds = coins(100, DENOM);
+    let long = make_task_with_funds(
+        &contract,
+        "Long",
+        Duration::new_seconds(400),
+        &payload,
+        &funds,
+    );
+    let short = Duration::new_seconds(100);
+    let first = make_task_with_funds(&contract, "First", short, &payload, &funds);
+    let second = make_task_with_funds(&contract, "Second", short, &payload, &funds);
+
+    // Nothing to do yet
+    let err = contract
+        .call_as(&sweeper)
+        .timeout_expired(None)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NoExpiredTasks.to_string()));
+
+    // Sweep one at a time, earliest expiration first
+    chain.wait_seconds(150).unwrap();
+    let before = balance(&chain.sender_addr());
+    contract.call_as(&sweeper).timeout_expired(Some(1)).unwrap();
+    assert_eq!(
+        contract.task_status(first).unwrap().status,
+        TaskStatus::Expired
+    );
+    // The second task reports as expired too, but only the first one was refunded
+    assert_eq!(balance(&sweeper.addr()), 10);
+    assert_eq!(balance(&chain.sender_addr()), before + 90);
+
+    // Sweep the rest, the long task is still open
+    contract.call_as(&sweeper).timeout_expired(None).unwrap();
+    assert_eq!(
+        contract.task_status(second).unwrap().status,
+        TaskStatus::Expired
+    );
+    assert_eq!(contract.task_status(long).unwrap().status, TaskStatus::Open);
+    assert_eq!(balance(&sweeper.addr()), 20);
+    assert_eq!(balance(&chain.sender_addr()), before + 180);
+
+    contract
+        .call_as(&sweeper)
+        .timeout_expired(None)
+        .unwrap_err();
+    contract.timeout(first).unwrap_err();
+}
+
+pub fn cancel_works<C>(chain: C, mock_consumer: MockHookConsumerContract<C>)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let creator = chain.alt_signer(1);
+    let other = chain.alt_signer(2);
+    let msg = InstantiateMsg {
+        requestor: Requestor::OpenPayment(coin(100, DENOM)),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None, // defaults to sender
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let contract = setup(chain.clone(), msg);
+    for hook_type in [TaskHookType::Created, TaskHookType::Cancelled] {
+        contract
+            .add_hooks(None, hook_type, vec![mock_consumer.addr_str().unwrap()])
+            .unwrap();
+    }
+    let balance = |addr: &Addr| {
+        chain
+            .balance(addr, Some(DENOM.to_string()))
+            .unwrap()
+            .first()
+            .map(|c| c.amount.u128())
+            .unwrap_or_default()
+    };
+
+    let payload = json!({"x": 17});
+    let funds = coins(100, DENOM);
+    let initial = balance(&creator.addr());
+    let task_id = make_task_with_funds(
+        &contract.call_as(&creator),
+        "Cancel me",
+        None,
+        &payload,
+        &funds,
+    );
+    assert_eq!(balance(&creator.addr()), initial - 100);
+    assert_eq!(mock_consumer.created_count().unwrap(), 1);
+
+    // Only the creator or the owner can cancel
+    let err = contract.call_as(&other).cancel(task_id).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::Unauthorized.to_string()));
+
+    // The creator gets the deposit back and hooks are fired
+    contract.call_as(&creator).cancel(task_id).unwrap();
+    assert_eq!(balance(&creator.addr()), initial);
+    assert_eq!(mock_consumer.created_count().unwrap(), 0);
+    assert_eq!(
+        contract.task_status(task_id).unwrap().status,
+        TaskStatus::Cancelled
+    );
+    let now = get_time(&chain);
+    let list = contract.list(None, None).unwrap();
+    assert_eq!(
+        list.tasks[0].status,
+        InfoStatus::Cancelled { cancelled: now }
+    );
+    assert_eq!(contract.list_open(None, None).unwrap().tasks.len(), 0);
+
+    // A cancelled task can't be completed, cancelled or timed out
+    let err = contract
+        .call_as(&verifier)
+        .complete(task_id, json!({"y": 289}))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskCancelled.to_string()));
+    contract.call_as(&creator).cancel(task_id).unwrap_err();
+    chain.wait_seconds(500).unwrap();
+    contract.timeout(task_id).unwrap_err();
+
+    // The owner can cancel any task, but not an expired one
+    let task_id = make_task_with_funds(
+        &contract.call_as(&creator),
+        "Owner cancels",
+        None,
+        &payload,
+        &funds,
+    );
+    contract.cancel(task_id).unwrap();
+    assert_eq!(balance(&creator.addr()), initial);
+
+    let task_id = make_task_with_funds(
+        &contract.call_as(&creator),
+        "Expires",
+        None,
+        &payload,
+        &funds,
+    );
+    chain.wait_seconds(500).unwrap();
+    let err = contract.call_as(&creator).cancel(task_id).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskExpired.to_string()));
+}
+
+pub fn priority_works<C>(chain: C, other_denom: &str)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let mut msg = InstantiateMsg {
+        requestor: Requestor::OpenPayment(coin(100, DENOM)),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let contract = TaskContract::new(chain.clone());
+    contract.upload().unwrap();
+    contract.instantiate(&msg, None, &[]).unwrap();
+    let balance = |addr: &Addr| {
+        chain
+            .balance(addr, Some(DENOM.to_string()))
+            .unwrap()
+            .first()
+            .map(|c| c.amount.u128())
+            .unwrap_or_default()
+    };
+    let payload = json!({"x": 17});
+
+    // Tips are rejected if the operators don't get paid
+    let err = contract
+        .create(
+            "Tipped".to_string(),
+            None,
+            payload.clone(),
+            None,
+            None,
+            &coins(150, DENOM),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TipNotAccepted.to_string()));
+
+    // Operators can only be paid through a verifier supporting rewards
+    msg.fees = Some(FeeSplit {
+        treasury: None,
+        treasury_share: Decimal::zero(),
+        verifier_share: Decimal::percent(50),
+        sweep_bounty: Decimal::zero(),
+    });
+    let err = contract.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::VerifierWithoutRewards.to_string()));
+    // and its own share needs someone to credit it to
+    msg.verifier = setup_rewards_verifier(chain.clone(), None).into_string();
+    let err = contract.instantiate(&msg, None, &[]).unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::VerifierWithoutFeeRecipient.to_string()));
+    let fee_recipient = chain.alt_signer(VERIFIER_INDEX + 1).addr();
+    msg.verifier = setup_rewards_verifier(chain.clone(), Some(&fee_recipient)).into_string();
+    contract.instantiate(&msg, None, &[]).unwrap();
+    let err = contract
+        .update_config(None, None, Some(verifier.addr().to_string()))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::VerifierWithoutRewards.to_string()));
+
+    let initial = balance(&chain.sender_addr());
+    let no_tip = make_task_with_funds(&contract, "No tip", None, &payload, &coins(100, DENOM));
+    let first = make_task_with_funds(&contract, "First", None, &payload, &coins(150, DENOM));
+    let small = make_task_with_funds(&contract, "Small", None, &payload, &coins(130, DENOM));
+    let second = make_task_with_funds(&contract, "Second", None, &payload, &coins(150, DENOM));
+    assert_eq!(balance(&chain.sender_addr()), initial - 530);
+
+    // Highest tips first, then the most recent
+    let ids = |tasks: Vec<OpenTaskOverview>| tasks.into_iter().map(|t| t.id).collect::<Vec<_>>();
+    let page = contract.list_open_by_priority(None, Some(2)).unwrap();
+    assert_eq!(ids(page.tasks), vec![second, first]);
+    let page = contract.list_open_by_priority(Some(first), None).unwrap();
+    assert_eq!(ids(page.tasks), vec![small, no_tip]);
+
+    let open = contract.list_open(None, None).unwrap();
+    assert_eq!(open.tasks[0].tip, Some(coin(50, DENOM)));
+    assert_eq!(open.tasks[3].tip, None);
+
+    // The tip is refunded along with the deposit
+    contract.cancel(second).unwrap();
+    assert_eq!(balance(&chain.sender_addr()), initial - 380);
+    let page = contract.list_open_by_priority(None, None).unwrap();
+    assert_eq!(ids(page.tasks), vec![first, small, no_tip]);
+
+    chain.wait_seconds(250).unwrap();
+    assert!(contract
+        .list_open_by_priority(None, None)
+        .unwrap()
+        .tasks
+        .is_empty());
+    contract.timeout(first).unwrap();
+    assert_eq!(balance(&chain.sender_addr()), initial - 230);
+
+    // With several payment coins, tips are only accepted in the first one
+    let accepted = vec![coin(100, DENOM), coin(50, other_denom)];
+    contract
+        .update_config(Some(Requestor::OpenPaymentAnyOf(accepted)), None, None)
+        .unwrap();
+    let err = contract
+        .create(
+            "Tipped".to_string(),
+            None,
+            payload.clone(),
+            None,
+            None,
+            &coins(80, other_denom),
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TipDenomNotAccepted(DENOM.to_string()).to_string()));
+    make_task_with_funds(&contract, "Other", None, &payload, &coins(50, other_denom));
+    let tipped = make_task_with_funds(&contract, "Tipped", None, &payload, &coins(120, DENOM));
+    let page = contract.list_open_by_priority(None, Some(1)).unwrap();
+    assert_eq!(ids(page.tasks), vec![tipped]);
+}
+
+/// A verifier supporting operator rewards, as required by the verifier and operator fee shares
+fn setup_rewards_verifier<C: CwEnv>(chain: C, fee_recipient: Option<&Addr>) -> Addr {
+    let msg = VerifierInstantiateMsg {
+        // not queried on instantiation
+        operator_contract: chain.sender_addr().to_string(),
+        required_percentage: 50,
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: fee_recipient.map(Addr::to_string),
+    };
+    let verifier = VerifierContract::new(chain);
+    verifier.upload().unwrap();
+    verifier.instantiate(&msg, None, &[]).unwrap();
+    verifier.address().unwrap()
+}
+
+/// Migrating to the same or a newer version keeps the state
+pub fn migrate_works<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let verifier = chain.alt_signer(VERIFIER_INDEX);
+    let msg = InstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: mock_timeout(Duration::new_seconds(200)),
+        verifier: verifier.addr().into(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let contract = TaskContract::new(chain.clone());
+    contract.upload().unwrap();
+    contract
+        .instantiate(&msg, Some(&chain.sender_addr()), &[])
+        .unwrap();
+    let task_id = make_task(&contract, "Before", None, &json!({"x": 17}));
+    let config = contract.config().unwrap();
+
+    // Upload the code again and migrate to it
+    contract.upload().unwrap();
+    contract
+        .migrate(&MigrateMsg {}, contract.code_id().unwrap())
+        .unwrap();
+
+    assert_eq!(contract.config().unwrap(), config);
+    let task = contract.task(task_id).unwrap();
+    assert_eq!(task.description, "Before");
+}
+
 pub fn get_time(chain: &impl QueryHandler) -> Timestamp {
     chain.block_info().unwrap().time
 }
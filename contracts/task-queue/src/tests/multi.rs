@@ -1,4 +1,4 @@
-use cosmwasm_std::{coins, Uint128};
+use cosmwasm_std::{coin, coins, Uint128};
 use cw_orch::prelude::*;
 use lavs_apis::time::Duration;
 use lavs_orch::AltSigner;
@@ -50,6 +50,12 @@ fn task_pagination() {
     super::common::task_pagination_works(chain);
 }
 
+#[test]
+fn schema_validation() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::schema_validation_works(chain);
+}
+
 #[test]
 fn task_hooks() {
     let chain = MockBech32::new(BECH_PREFIX);
@@ -94,6 +100,68 @@ fn task_refunds() {
     super::common::timeout_refund_test(chain, DENOM.to_string());
 }
 
+#[test]
+fn update_config() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    chain
+        .add_balance(&chain.sender_addr(), coins(10_000, DENOM))
+        .unwrap();
+    super::common::update_config_works(chain);
+}
+
+#[test]
+fn requestor_allowlist() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let other_denom = "ustable";
+    for i in 1..=3 {
+        chain
+            .add_balance(
+                &chain.alt_signer(i),
+                vec![coin(10_000, DENOM), coin(10_000, other_denom)],
+            )
+            .unwrap();
+    }
+    super::common::requestor_allowlist_works(chain, other_denom);
+}
+
+#[test]
+fn timeout_expired() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    chain
+        .add_balance(&chain.sender_addr(), coins(10_000, DENOM))
+        .unwrap();
+    super::common::timeout_expired_works(chain);
+}
+
+#[test]
+fn priority() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let other_denom = "ustable";
+    chain
+        .add_balance(
+            &chain.sender_addr(),
+            vec![coin(10_000, DENOM), coin(10_000, other_denom)],
+        )
+        .unwrap();
+    super::common::priority_works(chain, other_denom);
+}
+
+#[test]
+fn cancel() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    let mock_hook_consumer = super::common::setup_mock_hooks_consumer(chain.clone());
+    chain
+        .add_balance(&chain.alt_signer(1), coins(10_000, DENOM))
+        .unwrap();
+    super::common::cancel_works(chain, mock_hook_consumer);
+}
+
+#[test]
+fn migrate() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::migrate_works(chain);
+}
+
 /// This is the simplest, most explicit test to bootstrap, before importing from common
 #[test]
 fn sanity_check() {
@@ -114,6 +182,9 @@ fn sanity_check() {
         verifier: verifier.to_string(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let init_res = tasker.instantiate(&msg, None, &[]).unwrap();
     let contract_addr = init_res.instantiated_contract_address().unwrap();
@@ -0,0 +1,364 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw2::set_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{ExecuteMsg, InstantiateMsg, KeyType, QueryMsg};
+
+use crate::state::{Config, CONFIG};
+
+// version info for migration info
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    // validate the input data
+    let operators = deps.api.addr_validate(&msg.operator_contract)?;
+    let required_percentage = msg.required_percentage;
+    if required_percentage > 100 || required_percentage == 0 {
+        return Err(ContractError::InvalidPercentage);
+    }
+
+    // save config and cw2 metadata
+    let config = Config {
+        operators,
+        required_percentage,
+    };
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::RegisterPubkey {
+            key_type,
+            pubkey,
+            signature,
+        } => execute::register_pubkey(deps, env, info, key_type, pubkey, signature),
+        ExecuteMsg::SubmitAggregated {
+            task_queue_contract,
+            task_id,
+            result,
+            signatures,
+        } => execute::submit_aggregated(
+            deps,
+            env,
+            info,
+            task_queue_contract,
+            task_id,
+            result,
+            signatures,
+        ),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&query::config(deps)?)?),
+        QueryMsg::OperatorPubkey { operator } => {
+            Ok(to_json_binary(&query::operator_pubkey(deps, operator)?)?)
+        }
+        QueryMsg::TaskInfo {
+            task_contract,
+            task_id,
+        } => Ok(to_json_binary(&query::task_info(
+            deps,
+            env,
+            task_contract,
+            task_id,
+        )?)?),
+    }
+}
+
+/// Verifies the signature over the digest with the given key
+fn verify_signature(
+    deps: Deps,
+    key_type: KeyType,
+    digest: &[u8],
+    signature: &[u8],
+    pubkey: &[u8],
+) -> Result<bool, ContractError> {
+    let valid = match key_type {
+        KeyType::Secp256k1 => deps.api.secp256k1_verify(digest, signature, pubkey)?,
+        KeyType::Ed25519 => deps.api.ed25519_verify(digest, signature, pubkey)?,
+    };
+    Ok(valid)
+}
+
+mod execute {
+    use super::*;
+
+    use cosmwasm_std::{from_json, Addr, Uint128, WasmMsg};
+
+    use cw_utils::nonpayable;
+    use lavs_apis::id::TaskId;
+    use lavs_apis::interfaces::tasks::{ResponseType, TaskExecuteMsg, TaskStatus, TasksStorage};
+    use lavs_apis::interfaces::voting::{QueryMsg as OperatorQueryMsg, VotingPowerResponse};
+
+    use crate::msg::{RegistrationPayload, SignedResult};
+    use crate::state::{
+        OperatorKey, Submission, OPERATOR_KEYS, PUBKEY_OPERATORS, SUBMISSIONS, TASKS,
+    };
+
+    pub fn register_pubkey(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        key_type: KeyType,
+        pubkey: Binary,
+        signature: Binary,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        // a key can only belong to one operator
+        match PUBKEY_OPERATORS.may_load(deps.storage, pubkey.as_slice())? {
+            Some(owner) if owner != info.sender => {
+                return Err(ContractError::PubkeyAlreadyRegistered)
+            }
+            _ => {}
+        }
+
+        // prove the sender owns the key
+        let digest = RegistrationPayload {
+            chain_id: env.block.chain_id,
+            verifier: env.contract.address,
+            operator: info.sender.clone(),
+        }
+        .digest()?;
+        if !verify_signature(deps.as_ref(), key_type, &digest, &signature, &pubkey)? {
+            return Err(ContractError::InvalidSignature(info.sender.into_string()));
+        }
+
+        // rotating keys releases the old one
+        if let Some(old) = OPERATOR_KEYS.may_load(deps.storage, &info.sender)? {
+            PUBKEY_OPERATORS.remove(deps.storage, old.pubkey.as_slice());
+        }
+        PUBKEY_OPERATORS.save(deps.storage, pubkey.as_slice(), &info.sender)?;
+        OPERATOR_KEYS.save(
+            deps.storage,
+            &info.sender,
+            &OperatorKey { key_type, pubkey },
+        )?;
+
+        Ok(Response::new()
+            .add_attribute("action", "register_pubkey")
+            .add_attribute("operator", info.sender))
+    }
+
+    pub fn submit_aggregated(
+        mut deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+        result: String,
+        signatures: Vec<(Binary, Binary)>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+        // the required power is zero if the operators had no power when the task was created,
+        // so this must not be left to the power check
+        if signatures.is_empty() {
+            return Err(ContractError::NoSignatures);
+        }
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let config = CONFIG.load(deps.storage)?;
+
+        // verify the result type upon submissions (parse it into expected ResponseType)
+        let response: ResponseType = from_json(&result)?;
+
+        // Ensure the task is still open, and get the power needed at the creation height.
+        // Unlike with individual votes, we error here, as the submitter pays for the whole quorum.
+        let mut task_data = TasksStorage::handle_metadata(
+            deps.branch(),
+            &env,
+            &config.operators,
+            &task_queue,
+            task_id,
+            config.required_percentage,
+        )?;
+
+        let digest = SignedResult {
+            chain_id: env.block.chain_id.clone(),
+            verifier: env.contract.address.clone(),
+            task_queue: task_queue.clone(),
+            task_id,
+            result: result.clone(),
+        }
+        .digest()?;
+
+        let mut signers = Vec::with_capacity(signatures.len());
+        let mut power = Uint128::zero();
+        for (pubkey, signature) in signatures {
+            let operator = PUBKEY_OPERATORS
+                .may_load(deps.storage, pubkey.as_slice())?
+                .ok_or_else(|| ContractError::UnknownPubkey(pubkey.to_base64()))?;
+            if signers.contains(&operator) {
+                return Err(ContractError::DuplicateSigner(operator.into_string()));
+            }
+
+            let key = OPERATOR_KEYS.load(deps.storage, &operator)?;
+            if !verify_signature(
+                deps.as_ref(),
+                key.key_type,
+                &digest,
+                &signature,
+                &key.pubkey,
+            )? {
+                return Err(ContractError::InvalidSignature(operator.into_string()));
+            }
+
+            // Get the operators voting power at time of task creation
+            let operator_power: VotingPowerResponse = deps.querier.query_wasm_smart(
+                config.operators.to_string(),
+                &OperatorQueryMsg::VotingPowerAtHeight {
+                    address: operator.to_string(),
+                    height: Some(task_data.created_height),
+                },
+            )?;
+            if operator_power.power.is_zero() {
+                return Err(ContractError::NoVotingPower(operator.into_string()));
+            }
+            power += operator_power.power;
+            signers.push(operator);
+        }
+
+        if power < task_data.power_required {
+            return Err(ContractError::InsufficientPower {
+                power,
+                required: task_data.power_required,
+            });
+        }
+
+        task_data.status = TaskStatus::Completed;
+        TASKS.save(deps.storage, (&task_queue, task_id), &task_data)?;
+        SUBMISSIONS.save(
+            deps.storage,
+            (&task_queue, task_id),
+            &Submission {
+                result,
+                power,
+                signers: signers.clone(),
+            },
+        )?;
+
+        let signers = signers
+            .into_iter()
+            .map(Addr::into_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(Response::new()
+            .add_message(WasmMsg::Execute {
+                contract_addr: task_queue_contract.clone(),
+                msg: to_json_binary(&TaskExecuteMsg::Complete { task_id, response })?,
+                funds: vec![],
+            })
+            .add_attribute("action", "submit_aggregated")
+            .add_attribute("task_queue", task_queue_contract)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("power", power)
+            .add_attribute("signers", signers))
+    }
+}
+
+mod query {
+    use lavs_apis::id::TaskId;
+    use lavs_apis::interfaces::voting::{QueryMsg as OperatorQueryMsg, VotingPowerResponse};
+    use lavs_apis::verifier_simple::TaskVoter;
+
+    use super::*;
+
+    use crate::msg::{ConfigResponse, PubkeyResponse, TaskInfoResponse, TaskTally};
+    use crate::state::{OPERATOR_KEYS, SUBMISSIONS, TASKS};
+
+    pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
+        let cfg = CONFIG.load(deps.storage)?;
+        Ok(ConfigResponse {
+            operator_contract: cfg.operators.to_string(),
+            required_percentage: cfg.required_percentage,
+        })
+    }
+
+    pub fn operator_pubkey(deps: Deps, operator: String) -> StdResult<Option<PubkeyResponse>> {
+        let operator = deps.api.addr_validate(&operator)?;
+        let key = OPERATOR_KEYS
+            .may_load(deps.storage, &operator)?
+            .map(|key| PubkeyResponse {
+                key_type: key.key_type,
+                pubkey: key.pubkey,
+            });
+        Ok(key)
+    }
+
+    pub fn task_info(
+        deps: Deps,
+        env: Env,
+        task_contract: String,
+        task_id: TaskId,
+    ) -> StdResult<Option<TaskInfoResponse>> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let info = TASKS.may_load(deps.storage, (&task_contract, task_id))?;
+        if let Some(i) = info {
+            // Check current time and update the status if it expired
+            let status = i.current_status(&env);
+            // Only the accepted result is stored, there are no partial tallies
+            let tallies = match SUBMISSIONS.may_load(deps.storage, (&task_contract, task_id))? {
+                Some(s) => {
+                    // the power of each signer is not stored, only their total
+                    let operators = CONFIG.load(deps.storage)?.operators;
+                    let mut voters = s
+                        .signers
+                        .into_iter()
+                        .map(|signer| -> StdResult<_> {
+                            let power: VotingPowerResponse = deps.querier.query_wasm_smart(
+                                operators.to_string(),
+                                &OperatorQueryMsg::VotingPowerAtHeight {
+                                    address: signer.to_string(),
+                                    height: Some(i.created_height),
+                                },
+                            )?;
+                            Ok(TaskVoter {
+                                operator: signer.into_string(),
+                                power: power.power,
+                            })
+                        })
+                        .collect::<StdResult<Vec<_>>>()?;
+                    voters.sort_by(|a, b| a.operator.cmp(&b.operator));
+                    vec![TaskTally {
+                        result: s.result,
+                        power: s.power,
+                        voters,
+                    }]
+                }
+                None => vec![],
+            };
+            let res = TaskInfoResponse {
+                status,
+                power_needed: i.power_required,
+                tallies,
+            };
+            Ok(Some(res))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {}
@@ -0,0 +1,46 @@
+use cosmwasm_std::{StdError, Uint128, VerificationError};
+use cw_utils::PaymentError;
+use lavs_apis::verifier_simple::VerifierError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Payment(#[from] PaymentError),
+
+    #[error("{0}")]
+    Verifier(#[from] VerifierError),
+
+    #[error("{0}")]
+    Verification(#[from] VerificationError),
+
+    #[error("Invalid percentage, must be between 1 and 100")]
+    InvalidPercentage,
+
+    #[error("Public key is already registered by another operator")]
+    PubkeyAlreadyRegistered,
+
+    #[error("Public key is not registered: {0}")]
+    UnknownPubkey(String),
+
+    #[error("Invalid signature from operator {0}")]
+    InvalidSignature(String),
+
+    #[error("Operator signed twice: {0}")]
+    DuplicateSigner(String),
+
+    #[error("No signatures were submitted")]
+    NoSignatures,
+
+    #[error("Operator {0} has no voting power on this task")]
+    NoVotingPower(String),
+
+    #[error("Signers have {power} voting power, but {required} is required")]
+    InsufficientPower { power: Uint128, required: Uint128 },
+
+    #[error("Unauthorized")]
+    Unauthorized,
+}
@@ -0,0 +1,118 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{to_json_vec, Addr, Binary, StdResult};
+use cw_orch::{ExecuteFns, QueryFns};
+use lavs_apis::id::TaskId;
+use sha2::{Digest, Sha256};
+
+pub use lavs_apis::verifier_simple::{TaskInfoResponse, TaskStatus, TaskTally};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// The contract storing the operator weights
+    pub operator_contract: String,
+    /// The percentage of voting power needed to agree in order to complete a task
+    pub required_percentage: u32,
+}
+
+#[cw_serde]
+#[derive(ExecuteFns)]
+#[cw_orch(disable_fields_sorting)]
+pub enum ExecuteMsg {
+    /// Register (or rotate) the public key the sender signs results with.
+    /// The signature must be over the digest of `RegistrationPayload`, to prove ownership of the key.
+    RegisterPubkey {
+        key_type: KeyType,
+        pubkey: Binary,
+        signature: Binary,
+    },
+    /// Submit a result signed off-chain by enough operators to complete the task in one transaction.
+    /// Anyone can submit this, the signatures decide if it is accepted.
+    SubmitAggregated {
+        /// Task queue contract for which we completed the task
+        task_queue_contract: String,
+        /// The ID of the task that was completed
+        task_id: TaskId,
+        /// The result of the task, (JSON) serialized as a string
+        result: String,
+        /// Pairs of (pubkey, signature) over the digest of `SignedResult`
+        signatures: Vec<(Binary, Binary)>,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryFns)]
+#[cw_orch(disable_fields_sorting)]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// The contract configuration
+    #[returns(ConfigResponse)]
+    Config {},
+    /// The public key registered by an operator
+    #[returns(Option<PubkeyResponse>)]
+    OperatorPubkey { operator: String },
+    /// The status of a task that this verifier has seen
+    #[returns(Option<TaskInfoResponse>)]
+    TaskInfo {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// The ID of the task we are interested in
+        task_id: TaskId,
+    },
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum KeyType {
+    /// Compressed or uncompressed secp256k1 public key, with 64 byte (r, s) signatures
+    Secp256k1,
+    /// 32 byte ed25519 public key, with 64 byte signatures
+    Ed25519,
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    /// The contract storing the operator weights
+    pub operator_contract: String,
+    /// The percentage of voting power needed to agree in order to complete a task
+    pub required_percentage: u32,
+}
+
+#[cw_serde]
+pub struct PubkeyResponse {
+    pub key_type: KeyType,
+    pub pubkey: Binary,
+}
+
+/// What an operator signs to prove it owns a key.
+/// Binding the chain, verifier and operator prevents reusing the proof elsewhere.
+#[cw_serde]
+pub struct RegistrationPayload {
+    pub chain_id: String,
+    pub verifier: Addr,
+    pub operator: Addr,
+}
+
+impl RegistrationPayload {
+    /// The sha256 digest of the JSON serialized payload, which is what gets signed
+    pub fn digest(&self) -> StdResult<[u8; 32]> {
+        Ok(Sha256::digest(to_json_vec(self)?).into())
+    }
+}
+
+/// What operators sign off-chain to vote for a result.
+/// Binding the chain and verifier prevents replaying the signatures on another deployment.
+#[cw_serde]
+pub struct SignedResult {
+    pub chain_id: String,
+    pub verifier: Addr,
+    pub task_queue: Addr,
+    pub task_id: TaskId,
+    pub result: String,
+}
+
+impl SignedResult {
+    /// The sha256 digest of the JSON serialized payload, which is what gets signed
+    pub fn digest(&self) -> StdResult<[u8; 32]> {
+        Ok(Sha256::digest(to_json_vec(self)?).into())
+    }
+}
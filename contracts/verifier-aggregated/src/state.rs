@@ -0,0 +1,38 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Uint128};
+use cw_storage_plus::{Item, Map};
+use lavs_apis::{id::TaskId, verifier_simple::TaskMetadata};
+
+use crate::msg::KeyType;
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// key is (task_queue_address, task_id)
+// must match the key used by `TasksStorage::handle_metadata`
+pub const TASKS: Map<(&Addr, TaskId), TaskMetadata> = Map::new("tasks");
+/// key is (task_queue_address, task_id), only set once the task is completed
+pub const SUBMISSIONS: Map<(&Addr, TaskId), Submission> = Map::new("submissions");
+/// key is the operator address
+pub const OPERATOR_KEYS: Map<&Addr, OperatorKey> = Map::new("operator_keys");
+/// key is the raw public key, pointing back to the operator who registered it
+pub const PUBKEY_OPERATORS: Map<&[u8], Addr> = Map::new("pubkey_operators");
+
+#[cw_serde]
+pub struct Config {
+    pub operators: Addr,
+    pub required_percentage: u32,
+}
+
+#[cw_serde]
+pub struct OperatorKey {
+    pub key_type: KeyType,
+    pub pubkey: Binary,
+}
+
+/// The accepted result for a task, along with who signed it
+#[cw_serde]
+pub struct Submission {
+    pub result: String,
+    pub power: Uint128,
+    pub signers: Vec<Addr>,
+}
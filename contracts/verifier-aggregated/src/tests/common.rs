@@ -0,0 +1,422 @@
+use cosmwasm_std::Binary;
+use cw_orch::environment::{ChainState, CwEnv};
+use cw_orch::prelude::*;
+use ed25519_zebra::{SigningKey as EdSigningKey, VerificationKey as EdVerificationKey};
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::ecdsa::{Signature, SigningKey};
+use lavs_apis::id::TaskId;
+use lavs_apis::time::Duration;
+use serde_json::json;
+
+use lavs_apis::tasks::{Requestor, Status, TaskStatus, TimeoutInfo};
+use lavs_orch::{Addressable, AltSigner};
+
+use lavs_mock_operators::interface::Contract as MockOperatorsContract;
+use lavs_mock_operators::msg::{
+    InstantiateMsg as MockOperatorsInstantiateMsg, InstantiateOperator,
+};
+use lavs_task_queue::interface::Contract as TasksContract;
+use lavs_task_queue::msg::{
+    CustomExecuteMsgFns as TasksExecuteMsgFns, CustomQueryMsgFns as TasksQueryMsgFns,
+    InstantiateMsg as TasksInstantiateMsg,
+};
+
+use crate::interface::Contract;
+use crate::msg::{
+    ExecuteMsgFns, InstantiateMsg, KeyType, QueryMsgFns, RegistrationPayload, SignedResult,
+};
+use crate::ContractError;
+
+pub const BECH_PREFIX: &str = "layer";
+
+/// An off-chain signing key of one operator
+enum OperatorKey {
+    Secp256k1(SigningKey),
+    Ed25519(EdSigningKey),
+}
+
+impl OperatorKey {
+    fn secp256k1(seed: u8) -> Self {
+        Self::Secp256k1(SigningKey::from_bytes(&[seed; 32].into()).unwrap())
+    }
+
+    fn ed25519(seed: u8) -> Self {
+        Self::Ed25519(EdSigningKey::from([seed; 32]))
+    }
+
+    fn key_type(&self) -> KeyType {
+        match self {
+            Self::Secp256k1(_) => KeyType::Secp256k1,
+            Self::Ed25519(_) => KeyType::Ed25519,
+        }
+    }
+
+    fn pubkey(&self) -> Binary {
+        match self {
+            Self::Secp256k1(sk) => sk.verifying_key().to_encoded_point(true).as_bytes().into(),
+            Self::Ed25519(sk) => <[u8; 32]>::from(EdVerificationKey::from(sk))
+                .to_vec()
+                .into(),
+        }
+    }
+
+    fn sign(&self, digest: &[u8; 32]) -> Binary {
+        match self {
+            Self::Secp256k1(sk) => {
+                let sig: Signature = sk.sign_prehash(digest).unwrap();
+                sig.to_bytes().to_vec().into()
+            }
+            Self::Ed25519(sk) => sk.sign(digest).to_bytes().to_vec().into(),
+        }
+    }
+}
+
+struct Setup<Chain: CwEnv> {
+    verifier: Contract<Chain>,
+    tasker: TasksContract<Chain>,
+    keys: Vec<OperatorKey>,
+}
+
+/// Three operators with 100, 200 and 300 power, who all registered a key.
+/// 60% is required, so the two largest operators must sign together.
+fn setup<C>(chain: &C) -> Setup<C>
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_nodes = [
+        chain.alt_signer(3),
+        chain.alt_signer(4),
+        chain.alt_signer(5),
+    ];
+
+    // Upload and instantiate operator contract
+    let operators = op_nodes
+        .iter()
+        .zip([100, 200, 300])
+        .map(|(n, power)| InstantiateOperator::new(n.addr().to_string(), power))
+        .collect();
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    // Upload and instantiate verifier, connecting to the operator
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 60,
+    };
+    let verifier = Contract::new(chain.clone());
+    verifier.upload().unwrap();
+    verifier.instantiate(&msg, None, &[]).unwrap();
+
+    // Upload and instantiate task queue, acknowledging the verifier
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    // Every operator proves ownership of its key
+    let keys = vec![
+        OperatorKey::secp256k1(1),
+        OperatorKey::secp256k1(2),
+        OperatorKey::ed25519(3),
+    ];
+    let chain_id = chain.block_info().unwrap().chain_id;
+    for (node, key) in op_nodes.iter().zip(&keys) {
+        let digest = RegistrationPayload {
+            chain_id: chain_id.clone(),
+            verifier: verifier.address().unwrap(),
+            operator: node.addr(),
+        }
+        .digest()
+        .unwrap();
+        verifier
+            .call_as(node)
+            .register_pubkey(key.key_type(), key.pubkey(), key.sign(&digest))
+            .unwrap();
+    }
+
+    Setup {
+        verifier,
+        tasker,
+        keys,
+    }
+}
+
+fn result_digest<C: CwEnv>(setup: &Setup<C>, chain: &C, task_id: TaskId, result: &str) -> [u8; 32] {
+    SignedResult {
+        chain_id: chain.block_info().unwrap().chain_id,
+        verifier: setup.verifier.address().unwrap(),
+        task_queue: setup.tasker.address().unwrap(),
+        task_id,
+        result: result.to_string(),
+    }
+    .digest()
+    .unwrap()
+}
+
+/// Two operators sign off-chain, anyone submits it in one transaction
+pub fn happy_path<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let setup = setup(&chain);
+    let Setup {
+        verifier,
+        tasker,
+        keys,
+    } = &setup;
+
+    // Keys are registered
+    let registered = verifier
+        .operator_pubkey(chain.alt_signer(5).addr().to_string())
+        .unwrap()
+        .unwrap();
+    assert_eq!(registered.key_type, KeyType::Ed25519);
+    assert_eq!(registered.pubkey, keys[2].pubkey());
+
+    let task_id = make_task(tasker, "Test Task", None, &json!({"x": 17}));
+
+    let result = r#"{"y": 289}"#;
+    let digest = result_digest(&setup, &chain, task_id, result);
+    let signatures = keys[1..]
+        .iter()
+        .map(|k| (k.pubkey(), k.sign(&digest)))
+        .collect::<Vec<_>>();
+    verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            signatures,
+        )
+        .unwrap();
+    let completed = chain.block_info().unwrap().time;
+
+    // Check it is marked as completed (both in verifier and task queue)
+    let status = tasker.task(task_id).unwrap();
+    assert_eq!(status.status, Status::Completed { completed });
+    assert_eq!(status.result, Some(json!({"y": 289})));
+
+    let v_status = verifier
+        .task_info(tasker.addr_str().unwrap(), task_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(v_status.status, TaskStatus::Completed);
+    assert_eq!(v_status.power_needed.u128(), 360);
+    assert_eq!(v_status.tallies.len(), 1);
+    assert_eq!(v_status.tallies[0].power.u128(), 500);
+    let voters = &v_status.tallies[0].voters;
+    assert_eq!(voters.len(), 2);
+    let voted_power: u128 = voters.iter().map(|voter| voter.power.u128()).sum();
+    assert_eq!(voted_power, 500);
+}
+
+/// Signatures below the required power, or over a different result, are rejected
+pub fn rejects_invalid_submissions<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let setup = setup(&chain);
+    let Setup {
+        verifier,
+        tasker,
+        keys,
+    } = &setup;
+
+    let task_id = make_task(tasker, "Test Task", None, &json!({"x": 17}));
+    let result = r#"{"y": 289}"#;
+    let digest = result_digest(&setup, &chain, task_id, result);
+
+    // The largest operator alone is not enough
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![(keys[2].pubkey(), keys[2].sign(&digest))],
+        )
+        .unwrap_err();
+    let expected = ContractError::InsufficientPower {
+        power: 300u128.into(),
+        required: 360u128.into(),
+    };
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // Signing twice doesn't count twice
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![
+                (keys[2].pubkey(), keys[2].sign(&digest)),
+                (keys[2].pubkey(), keys[2].sign(&digest)),
+            ],
+        )
+        .unwrap_err();
+    let expected = ContractError::DuplicateSigner(chain.alt_signer(5).addr().to_string());
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // A signature over another result is invalid
+    let other = result_digest(&setup, &chain, task_id, r#"{"y": 290}"#);
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![
+                (keys[1].pubkey(), keys[1].sign(&other)),
+                (keys[2].pubkey(), keys[2].sign(&digest)),
+            ],
+        )
+        .unwrap_err();
+    let expected = ContractError::InvalidSignature(chain.alt_signer(4).addr().to_string());
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // Unregistered keys are rejected
+    let stranger = OperatorKey::secp256k1(9);
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![(stranger.pubkey(), stranger.sign(&digest))],
+        )
+        .unwrap_err();
+    let expected = ContractError::UnknownPubkey(stranger.pubkey().to_base64());
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // Nothing to complete the task with
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![],
+        )
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::NoSignatures.to_string()));
+
+    // Anyone can register a key, but only operators with power can sign
+    let outsider = chain.alt_signer(6);
+    let outsider_key = OperatorKey::secp256k1(8);
+    let registration = RegistrationPayload {
+        chain_id: chain.block_info().unwrap().chain_id,
+        verifier: verifier.address().unwrap(),
+        operator: outsider.addr(),
+    }
+    .digest()
+    .unwrap();
+    verifier
+        .call_as(&outsider)
+        .register_pubkey(
+            outsider_key.key_type(),
+            outsider_key.pubkey(),
+            outsider_key.sign(&registration),
+        )
+        .unwrap();
+    let err = verifier
+        .submit_aggregated(
+            tasker.addr_str().unwrap(),
+            task_id,
+            result.to_string(),
+            vec![(outsider_key.pubkey(), outsider_key.sign(&digest))],
+        )
+        .unwrap_err();
+    let expected = ContractError::NoVotingPower(outsider.addr().to_string());
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // Still open
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Open { .. }));
+}
+
+/// Keys can't be registered without proving ownership, nor taken from another operator
+pub fn register_requires_key_ownership<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let setup = setup(&chain);
+    let verifier = &setup.verifier;
+    let chain_id = chain.block_info().unwrap().chain_id;
+    let thief = chain.alt_signer(6);
+
+    // Reusing the proof of another operator fails
+    let victim_digest = RegistrationPayload {
+        chain_id: chain_id.clone(),
+        verifier: verifier.address().unwrap(),
+        operator: chain.alt_signer(3).addr(),
+    }
+    .digest()
+    .unwrap();
+    let key = OperatorKey::secp256k1(7);
+    let err = verifier
+        .call_as(&thief)
+        .register_pubkey(key.key_type(), key.pubkey(), key.sign(&victim_digest))
+        .unwrap_err();
+    let expected = ContractError::InvalidSignature(thief.addr().to_string());
+    assert!(err.root().to_string().contains(&expected.to_string()));
+
+    // A key registered by someone else cannot be claimed
+    let digest = RegistrationPayload {
+        chain_id,
+        verifier: verifier.address().unwrap(),
+        operator: thief.addr(),
+    }
+    .digest()
+    .unwrap();
+    let taken = &setup.keys[0];
+    let err = verifier
+        .call_as(&thief)
+        .register_pubkey(taken.key_type(), taken.pubkey(), taken.sign(&digest))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::PubkeyAlreadyRegistered.to_string()));
+}
+
+#[track_caller]
+pub fn make_task<C: ChainState + TxHandler>(
+    contract: &TasksContract<C>,
+    name: &str,
+    timeout: impl Into<Option<Duration>>,
+    payload: &serde_json::Value,
+) -> TaskId {
+    let res = contract
+        .create(
+            name.to_string(),
+            timeout.into(),
+            payload.clone(),
+            None,
+            None,
+            &[],
+        )
+        .unwrap();
+    get_task_id(&res)
+}
+
+#[track_caller]
+pub fn get_task_id(res: &impl IndexResponse) -> TaskId {
+    res.event_attr_value("wasm-task_created_event", "task-id")
+        .unwrap()
+        .parse()
+        .unwrap()
+}
@@ -0,0 +1,21 @@
+use cw_orch::prelude::MockBech32;
+
+use super::common::BECH_PREFIX;
+
+#[test]
+fn happy_path_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::happy_path(chain);
+}
+
+#[test]
+fn rejects_invalid_submissions() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::rejects_invalid_submissions(chain);
+}
+
+#[test]
+fn register_requires_key_ownership() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::register_requires_key_ownership(chain);
+}
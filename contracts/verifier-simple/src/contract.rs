@@ -6,6 +6,8 @@ use cw2::set_contract_version;
 use crate::error::ContractError;
 use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 
+use lavs_helpers::json_schema;
+
 use crate::state::{Config, CONFIG};
 
 // version info for migration info
@@ -25,11 +27,21 @@ pub fn instantiate(
     if required_percentage > 100 || required_percentage == 0 {
         return Err(ContractError::InvalidPercentage);
     }
+    if let Some(schema) = &msg.result_schema {
+        json_schema::check_schema(schema)?;
+    }
+    let fee_recipient = msg
+        .fee_recipient
+        .map(|addr| deps.api.addr_validate(&addr))
+        .transpose()?;
 
     // save config and cw2 metadata
     let config = Config {
         operators,
         required_percentage,
+        max_result_bytes: msg.max_result_bytes,
+        result_schema: msg.result_schema,
+        fee_recipient,
     };
     CONFIG.save(deps.storage, &config)?;
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -50,6 +62,16 @@ pub fn execute(
             task_id,
             result,
         } => execute::executed_task(deps, env, info, task_queue_contract, task_id, result),
+        ExecuteMsg::DistributeRewards { task_id } => {
+            execute::distribute_rewards(deps, info, task_id)
+        }
+        ExecuteMsg::CollectFee { task_id } => execute::collect_fee(deps, info, task_id),
+        ExecuteMsg::ClaimRewards {} => execute::claim_rewards(deps, info),
+        ExecuteMsg::RecordMissed {
+            task_queue_contract,
+            task_id,
+            operators,
+        } => execute::record_missed(deps, info, task_queue_contract, task_id, operators),
     }
 }
 
@@ -66,6 +88,31 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             task_contract,
             task_id,
         )?)?),
+        QueryMsg::ListTaskVotes {
+            task_contract,
+            task_id,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::list_task_votes(
+            deps,
+            task_contract,
+            task_id,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::ListTasks {
+            task_contract,
+            status,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query::list_tasks(
+            deps,
+            env,
+            task_contract,
+            status,
+            start_after,
+            limit,
+        )?)?),
         QueryMsg::OperatorVote {
             task_contract,
             task_id,
@@ -76,21 +123,37 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             task_id,
             operator,
         )?)?),
+        QueryMsg::PendingRewards { operator } => {
+            Ok(to_json_binary(&query::pending_rewards(deps, operator)?)?)
+        }
+        QueryMsg::FeeRecipient {} => Ok(to_json_binary(&query::fee_recipient(deps)?)?),
+        QueryMsg::InvalidSubmissions { operator } => Ok(to_json_binary(
+            &query::invalid_submissions(deps, operator)?,
+        )?),
+        QueryMsg::OperatorStats { operator } => {
+            Ok(to_json_binary(&query::operator_stats(deps, operator)?)?)
+        }
+        QueryMsg::ListOperatorStats { start_after, limit } => Ok(to_json_binary(
+            &query::list_operator_stats(deps, start_after, limit)?,
+        )?),
     }
 }
 
 mod execute {
     use super::*;
 
-    use cosmwasm_std::{from_json, WasmMsg};
+    use cosmwasm_std::{Addr, Order, StdError, WasmMsg};
 
     use cw_utils::nonpayable;
+    use lavs_apis::events::invalid_result_event::InvalidResultEvent;
     use lavs_apis::events::task_executed_event::TaskExecutedEvent;
     use lavs_apis::id::TaskId;
-    use lavs_apis::interfaces::tasks::{ResponseType, TaskExecuteMsg, TaskStatus};
-    use lavs_helpers::verifier::ensure_valid_vote;
+    use lavs_apis::interfaces::tasks::{TaskExecuteMsg, TaskStatus};
+    use lavs_helpers::verifier::{ensure_valid_vote, had_voting_power};
 
-    use crate::state::{record_vote, TASKS, VOTES};
+    use crate::state::{
+        record_invalid_submission, record_vote, OPERATOR_STATS, OPTIONS, REWARDS, TASKS, VOTES,
+    };
 
     pub fn executed_task(
         mut deps: DepsMut,
@@ -106,9 +169,6 @@ mod execute {
         let task_queue = deps.api.addr_validate(&task_queue_contract)?;
         let operator = info.sender;
 
-        // verify the result type upon submissions (parse it into expected ResponseType)
-        let _: ResponseType = from_json(&result)?;
-
         let vote = VOTES.may_load(deps.storage, (&task_queue, task_id, &operator))?;
         let config = CONFIG.load(deps.storage)?;
 
@@ -131,6 +191,12 @@ mod execute {
             None => return Ok(Response::default()),
         };
 
+        // verify the result upon submission (parse it into expected ResponseType)
+        let response = match config.check_result(&result) {
+            Ok(response) => response,
+            Err(err) => return reject_result(deps, &operator, task_queue_contract, task_id, err),
+        };
+
         // Update the vote and check the total power on this result, also recording the operators vote
         let tally = record_vote(
             deps.storage,
@@ -140,6 +206,7 @@ mod execute {
             &result,
             power,
         )?;
+        OPERATOR_STATS.record_vote(deps.storage, &operator, task_data.latency(&env))?;
 
         let mut task_event = TaskExecutedEvent {
             task_id,
@@ -156,9 +223,9 @@ mod execute {
             // We need to update the status as completed
             task_data.status = TaskStatus::Completed;
             TASKS.save(deps.storage, (&task_queue, task_id), &task_data)?;
+            record_completed_stats(deps.branch(), &task_queue, task_id, &result)?;
 
-            // And submit the result to the task queue (after parsing it into relevant type)
-            let response: ResponseType = from_json(&result)?;
+            // And submit the result to the task queue
             res = res.add_message(WasmMsg::Execute {
                 contract_addr: task_queue_contract,
                 msg: to_json_binary(&TaskExecuteMsg::Complete { task_id, response })?,
@@ -171,22 +238,182 @@ mod execute {
 
         Ok(res)
     }
+
+    /// Counts the votes on the completed task against the accepted result.
+    /// The operators who didn't vote are counted separately, with `RecordMissed`.
+    fn record_completed_stats(
+        deps: DepsMut,
+        task_queue: &Addr,
+        task_id: TaskId,
+        accepted: &str,
+    ) -> Result<(), ContractError> {
+        let votes = VOTES
+            .prefix((task_queue, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (operator, vote) in &votes {
+            OPERATOR_STATS.record_result(deps.storage, operator, vote.result == accepted)?;
+        }
+        Ok(())
+    }
+
+    pub fn record_missed(
+        deps: DepsMut,
+        info: MessageInfo,
+        task_queue_contract: String,
+        task_id: TaskId,
+        operators: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let task_queue = deps.api.addr_validate(&task_queue_contract)?;
+        let task_data = TASKS
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::TaskNotCompleted)?;
+        if task_data.status != TaskStatus::Completed {
+            return Err(ContractError::TaskNotCompleted);
+        }
+
+        let config = CONFIG.load(deps.storage)?;
+        let mut recorded = 0u32;
+        for operator in operators {
+            let operator = deps.api.addr_validate(&operator)?;
+            if VOTES.has(deps.storage, (&task_queue, task_id, &operator))
+                || !had_voting_power(deps.as_ref(), &config.operators, &task_data, &operator)?
+            {
+                continue;
+            }
+            if OPERATOR_STATS.record_missed(deps.storage, &task_queue, task_id, &operator)? {
+                recorded += 1;
+            }
+        }
+
+        Ok(Response::new()
+            .add_attribute("action", "record_missed")
+            .add_attribute("task_queue", task_queue_contract)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("recorded", recorded.to_string()))
+    }
+
+    /// Counts the rejected result against the operator. This doesn't fail, as that would revert the count.
+    fn reject_result(
+        deps: DepsMut,
+        operator: &Addr,
+        task_queue: String,
+        task_id: TaskId,
+        err: ContractError,
+    ) -> Result<Response, ContractError> {
+        let invalid_submissions = record_invalid_submission(deps.storage, operator)?;
+        let event = InvalidResultEvent {
+            task_id,
+            task_queue,
+            operator: operator.to_string(),
+            reason: err.to_string(),
+            invalid_submissions,
+        };
+        Ok(Response::new().add_event(event))
+    }
+
+    pub fn distribute_rewards(
+        deps: DepsMut,
+        info: MessageInfo,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        // Rewards are sent by the task queue itself
+        let task_queue = info.sender;
+        let task_data = TASKS
+            .may_load(deps.storage, (&task_queue, task_id))?
+            .ok_or(ContractError::TaskNotCompleted)?;
+        if task_data.status != TaskStatus::Completed {
+            return Err(ContractError::TaskNotCompleted);
+        }
+
+        // Only one result can reach the required power, and that is the one that was accepted
+        let accepted = OPTIONS
+            .prefix((&task_queue, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .find(|r| {
+                r.as_ref()
+                    .map_or(true, |(_, option)| option.power >= task_data.power_required)
+            })
+            .transpose()?
+            .map(|(result, _)| result)
+            .ok_or_else(|| StdError::generic_err("Completed task without accepted result"))?;
+
+        let voters = VOTES
+            .prefix((&task_queue, task_id))
+            .range(deps.storage, None, None, Order::Ascending)
+            .filter(|r| r.as_ref().map_or(true, |(_, vote)| vote.result == accepted))
+            .map(|r| r.map(|(operator, vote)| (operator, vote.power)))
+            .collect::<StdResult<Vec<_>>>()?;
+        REWARDS.distribute(deps.storage, &info.funds, &voters)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "distribute_rewards")
+            .add_attribute("task_queue", task_queue)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("operators", voters.len().to_string()))
+    }
+
+    pub fn collect_fee(
+        deps: DepsMut,
+        info: MessageInfo,
+        task_id: TaskId,
+    ) -> Result<Response, ContractError> {
+        let recipient = CONFIG
+            .load(deps.storage)?
+            .fee_recipient
+            .ok_or(ContractError::NoFeeRecipient)?;
+        REWARDS.credit(deps.storage, &recipient, &info.funds)?;
+
+        Ok(Response::new()
+            .add_attribute("action", "collect_fee")
+            .add_attribute("task_queue", info.sender)
+            .add_attribute("task_id", task_id.to_string())
+            .add_attribute("recipient", recipient))
+    }
+
+    pub fn claim_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+        nonpayable(&info)?;
+
+        let msgs = REWARDS.claim(deps.storage, &info.sender)?;
+        if msgs.is_empty() {
+            return Err(ContractError::NoRewards);
+        }
+
+        Ok(Response::new()
+            .add_messages(msgs)
+            .add_attribute("action", "claim_rewards")
+            .add_attribute("operator", info.sender))
+    }
 }
 
 mod query {
+    use cosmwasm_std::{Addr, Order};
+    use cw_storage_plus::Bound;
     use lavs_apis::id::TaskId;
-    use lavs_apis::verifier_simple::{TaskStatus, TaskTally};
+    use lavs_apis::verifier_simple::{
+        ListTaskVotesResponse, ListTasksResponse, TaskStatus, TaskTally, TaskVoteResponse,
+        TaskVoter,
+    };
 
     use super::*;
 
-    use crate::msg::{ConfigResponse, OperatorVoteInfoResponse, TaskInfoResponse};
-    use crate::state::{OPTIONS, TASKS, VOTES};
+    use crate::msg::{
+        ConfigResponse, FeeRecipientResponse, InvalidSubmissionsResponse,
+        ListOperatorStatsResponse, OperatorStatsResponse, OperatorVoteInfoResponse,
+        PendingRewardsResponse, TaskInfoResponse,
+    };
+    use crate::state::{INVALID_SUBMISSIONS, OPERATOR_STATS, OPTIONS, REWARDS, TASKS, VOTES};
 
     pub fn config(deps: Deps) -> StdResult<ConfigResponse> {
         let cfg = CONFIG.load(deps.storage)?;
         Ok(ConfigResponse {
             operator_contract: cfg.operators.to_string(),
             required_percentage: cfg.required_percentage,
+            max_result_bytes: cfg.max_result_bytes,
+            result_schema: cfg.result_schema,
+            fee_recipient: cfg.fee_recipient.map(Addr::into_string),
         })
     }
 
@@ -200,17 +427,30 @@ mod query {
         let info = TASKS.may_load(deps.storage, (&task_contract, task_id))?;
         if let Some(i) = info {
             // Check current time and update the status if it expired
-            let status = match i.status {
-                TaskStatus::Open if i.is_expired(&env) => TaskStatus::Expired,
-                x => x,
-            };
-            // Collect the running tallies on the options
+            let status = i.current_status(&env);
+            let votes = VOTES
+                .prefix((&task_contract, task_id))
+                .range(deps.storage, None, None, Order::Ascending)
+                .collect::<StdResult<Vec<_>>>()?;
+            // Collect the running tallies on the options of this task, along with who voted for them
             let tallies: Result<Vec<_>, _> = OPTIONS
-                .range(deps.storage, None, None, cosmwasm_std::Order::Ascending)
+                .prefix((&task_contract, task_id))
+                .range(deps.storage, None, None, Order::Ascending)
                 .map(|r| {
-                    r.map(|((_, _, result), v)| TaskTally {
-                        result,
-                        power: v.power,
+                    r.map(|(result, v)| {
+                        let voters = votes
+                            .iter()
+                            .filter(|(_, vote)| vote.result == result)
+                            .map(|(operator, vote)| TaskVoter {
+                                operator: operator.to_string(),
+                                power: vote.power,
+                            })
+                            .collect();
+                        TaskTally {
+                            result,
+                            power: v.power,
+                            voters,
+                        }
                     })
                 })
                 .collect();
@@ -225,6 +465,66 @@ mod query {
         }
     }
 
+    pub fn list_task_votes(
+        deps: Deps,
+        task_contract: String,
+        task_id: TaskId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTaskVotesResponse> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let start_after = start_after
+            .map(|operator| deps.api.addr_validate(&operator))
+            .transpose()?;
+        let limit = limit.unwrap_or(30);
+        let votes = VOTES
+            .prefix((&task_contract, task_id))
+            .range(
+                deps.storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|r| {
+                r.map(|(operator, vote)| TaskVoteResponse {
+                    operator: operator.into_string(),
+                    power: vote.power,
+                    result: vote.result,
+                })
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(ListTaskVotesResponse { votes })
+    }
+
+    pub fn list_tasks(
+        deps: Deps,
+        env: Env,
+        task_contract: String,
+        status: Option<TaskStatus>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> StdResult<ListTasksResponse> {
+        let task_contract = deps.api.addr_validate(&task_contract)?;
+        let limit = limit.unwrap_or(30);
+        let tasks = TASKS
+            .prefix(&task_contract)
+            .range(
+                deps.storage,
+                start_after.map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .map(|r| r.map(|(task_id, task)| task.summary(&env, task_id)))
+            .filter(|r| match (r, &status) {
+                (Ok(task), Some(status)) => task.status == *status,
+                _ => true,
+            })
+            .take(limit as usize)
+            .collect::<StdResult<_>>()?;
+        Ok(ListTasksResponse { tasks })
+    }
+
     pub fn operator_vote(
         deps: Deps,
         task_contract: String,
@@ -241,6 +541,45 @@ mod query {
             });
         Ok(vote)
     }
+
+    pub fn pending_rewards(deps: Deps, operator: String) -> StdResult<PendingRewardsResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        REWARDS.query_pending(deps.storage, &operator)
+    }
+
+    pub fn fee_recipient(deps: Deps) -> StdResult<FeeRecipientResponse> {
+        let recipient = CONFIG.load(deps.storage)?.fee_recipient;
+        Ok(FeeRecipientResponse {
+            recipient: recipient.map(Addr::into_string),
+        })
+    }
+
+    pub fn invalid_submissions(
+        deps: Deps,
+        operator: String,
+    ) -> StdResult<InvalidSubmissionsResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        let count = INVALID_SUBMISSIONS
+            .may_load(deps.storage, &operator)?
+            .unwrap_or_default();
+        Ok(InvalidSubmissionsResponse { count })
+    }
+
+    pub fn operator_stats(deps: Deps, operator: String) -> StdResult<OperatorStatsResponse> {
+        let operator = deps.api.addr_validate(&operator)?;
+        OPERATOR_STATS.query(deps.storage, operator)
+    }
+
+    pub fn list_operator_stats(
+        deps: Deps,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<ListOperatorStatsResponse> {
+        let start_after = start_after
+            .map(|operator| deps.api.addr_validate(&operator))
+            .transpose()?;
+        OPERATOR_STATS.query_list(deps.storage, start_after, limit)
+    }
 }
 
 #[cfg(test)]
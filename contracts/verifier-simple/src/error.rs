@@ -1,6 +1,7 @@
 use cosmwasm_std::StdError;
 use cw_utils::PaymentError;
 use lavs_apis::verifier_simple::VerifierError;
+use lavs_helpers::{json_schema::SchemaError, verifier::ResultError};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -14,6 +15,12 @@ pub enum ContractError {
     #[error("{0}")]
     Verifier(#[from] VerifierError),
 
+    #[error("{0}")]
+    Schema(#[from] SchemaError),
+
+    #[error("{0}")]
+    InvalidResult(#[from] ResultError),
+
     #[error("Invalid percentage, must be between 1 and 100")]
     InvalidPercentage,
 
@@ -28,6 +35,15 @@ pub enum ContractError {
 
     #[error("Unauthorized")]
     Unauthorized,
+
+    #[error("Task is not completed")]
+    TaskNotCompleted,
+
+    #[error("No rewards to claim")]
+    NoRewards,
+
+    #[error("No fee recipient is configured")]
+    NoFeeRecipient,
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -1,7 +1,14 @@
 use cosmwasm_schema::cw_serde;
 use cosmwasm_std::{Addr, StdError, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
-use lavs_apis::{id::TaskId, verifier_simple::TaskMetadata};
+use lavs_apis::{
+    id::TaskId,
+    interfaces::{operator_stats::OperatorStatsStorage, rewards::OperatorRewards},
+    verifier_simple::{ResponseType, TaskMetadata},
+};
+use lavs_helpers::verifier::check_result;
+
+use crate::error::ContractError;
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
@@ -11,11 +18,38 @@ pub const TASKS: Map<(&Addr, TaskId), TaskMetadata> = Map::new("tasks");
 pub const OPTIONS: Map<(&Addr, TaskId, &str), TaskOption> = Map::new("task_options");
 /// key is (task_queue_address, task_id, operator)
 pub const VOTES: Map<(&Addr, TaskId, &Addr), OperatorVote> = Map::new("operator_votes");
+/// Rewards accrued by operators for voting on accepted results
+pub const REWARDS: OperatorRewards = OperatorRewards::new("rewards");
+/// How many rejected results each operator submitted
+pub const INVALID_SUBMISSIONS: Map<&Addr, u64> = Map::new("invalid_submissions");
+/// How each operator performed on the tasks
+pub const OPERATOR_STATS: OperatorStatsStorage =
+    OperatorStatsStorage::new("operator_stats", "operator_missed_tasks");
 
 #[cw_serde]
 pub struct Config {
     pub operators: Addr,
     pub required_percentage: u32,
+    /// Results above this size are rejected, if set
+    #[serde(default)]
+    pub max_result_bytes: Option<u32>,
+    /// Schema every result must match, if any
+    #[serde(default)]
+    pub result_schema: Option<serde_json::Value>,
+    /// Credited with the verifier's share of task fees, if set
+    #[serde(default)]
+    pub fee_recipient: Option<Addr>,
+}
+
+impl Config {
+    /// Parses an operator's result, checking it against the size limit and schema
+    pub fn check_result(&self, result: &str) -> Result<ResponseType, ContractError> {
+        Ok(check_result(
+            result,
+            self.max_result_bytes,
+            self.result_schema.as_ref(),
+        )?)
+    }
 }
 
 /// Metadata for a task option with some votes - indexed by (task_queue, task_id, result)
@@ -44,9 +78,7 @@ pub fn record_vote(
         power,
         result: result.to_string(),
     };
-    VOTES
-        .save(storage, (task_queue, task_id, operator), &vote)
-        .unwrap();
+    VOTES.save(storage, (task_queue, task_id, operator), &vote)?;
 
     // Update the option and get the running tally of power in favor of this result
     let tally = OPTIONS.update::<_, StdError>(storage, (task_queue, task_id, result), |old| {
@@ -57,3 +89,12 @@ pub fn record_vote(
     })?;
     Ok(tally.power)
 }
+
+/// Counts a rejected result against the operator, returning the new count
+pub fn record_invalid_submission(
+    storage: &mut dyn Storage,
+    operator: &Addr,
+) -> Result<u64, StdError> {
+    INVALID_SUBMISSIONS
+        .update::<_, StdError>(storage, operator, |count| Ok(count.unwrap_or_default() + 1))
+}
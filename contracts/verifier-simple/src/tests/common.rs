@@ -1,12 +1,14 @@
-use cosmwasm_std::Timestamp;
+use cosmwasm_std::{coin, coins, Decimal, Timestamp, Uint128};
 use cw_orch::environment::{ChainState, CwEnv};
 use cw_orch::prelude::*;
+use lavs_apis::events::invalid_result_event::InvalidResultEvent;
 use lavs_apis::events::task_executed_event::TaskExecutedEvent;
 use lavs_apis::id::TaskId;
 use lavs_apis::time::Duration;
+use lavs_apis::verifier_simple::{TaskTally, TaskVoter};
 use serde_json::json;
 
-use lavs_apis::tasks::{Requestor, Status, TaskStatus, TimeoutInfo};
+use lavs_apis::tasks::{FeeSplit, Requestor, Status, TaskStatus, TimeoutInfo};
 use lavs_orch::{Addressable, AltSigner};
 
 use lavs_mock_operators::interface::Contract as MockOperatorsContract;
@@ -20,10 +22,12 @@ use lavs_task_queue::msg::{
     InstantiateMsg as TasksInstantiateMsg,
 };
 
+use crate::error::ContractError;
 use crate::interface::Contract;
 use crate::msg::{ExecuteMsgFns, InstantiateMsg, QueryMsgFns};
 
 pub const BECH_PREFIX: &str = "layer";
+pub const DENOM: &str = "uslay";
 
 pub fn setup<Chain: CwEnv>(chain: Chain, msg: InstantiateMsg) -> Contract<Chain> {
     let contract = Contract::new(chain);
@@ -54,6 +58,9 @@ where
     let msg = InstantiateMsg {
         operator_contract: operators.addr_str().unwrap(),
         required_percentage: 70,
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: None,
     };
     let verifier = setup(chain.clone(), msg);
 
@@ -64,6 +71,9 @@ where
         verifier: verifier.addr_str().unwrap(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let tasker = TasksContract::new(chain.clone());
     tasker.upload().unwrap();
@@ -138,6 +148,9 @@ where
     let msg = InstantiateMsg {
         operator_contract: operators.addr_str().unwrap(),
         required_percentage: 65, // 65% of 3 means 2 needed
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: None,
     };
     let verifier = setup(chain.clone(), msg);
 
@@ -148,6 +161,9 @@ where
         verifier: verifier.addr_str().unwrap(),
         owner: None,
         task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
     };
     let tasker = TasksContract::new(chain.clone());
     tasker.upload().unwrap();
@@ -215,7 +231,591 @@ where
     assert_eq!(v_status.unwrap().status, TaskStatus::Completed);
 }
 
-// TODO: add a
+/// Votes are listed per task, and the tallies only include the votes on the task
+pub fn task_votes<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_nodes = [
+        chain.alt_signer(3),
+        chain.alt_signer(4),
+        chain.alt_signer(5),
+    ];
+
+    let operators = op_nodes
+        .iter()
+        .map(|n| InstantiateOperator::new(n.addr().to_string(), 1))
+        .collect();
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 65, // 65% of 3 means 2 needed
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    // Operator 2 votes for a losing result on the first task, and is the only one to vote on the second
+    let first = make_task(&tasker, "Test Task", None, &json!({"x": 17}));
+    let second = make_task(&tasker, "Test Task", None, &json!({"x": 18}));
+    let votes = [
+        (&op_nodes[0], first, r#"{"y": 289}"#),
+        (&op_nodes[1], first, r#"{"y": 291}"#),
+        (&op_nodes[1], second, r#"{"y": 324}"#),
+        (&op_nodes[2], first, r#"{"y": 289}"#),
+    ];
+    for (op, task_id, result) in votes {
+        verifier
+            .call_as(op)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+
+    let voter = |op: &C::Sender| TaskVoter {
+        operator: op.addr().into_string(),
+        power: Uint128::one(),
+    };
+    let mut accepted_voters = vec![voter(&op_nodes[0]), voter(&op_nodes[2])];
+    accepted_voters.sort_by(|a, b| a.operator.cmp(&b.operator));
+
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), first)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, TaskStatus::Completed);
+    assert_eq!(
+        info.tallies,
+        vec![
+            TaskTally {
+                result: r#"{"y": 289}"#.to_string(),
+                power: Uint128::new(2),
+                voters: accepted_voters,
+            },
+            TaskTally {
+                result: r#"{"y": 291}"#.to_string(),
+                power: Uint128::one(),
+                voters: vec![voter(&op_nodes[1])],
+            },
+        ]
+    );
+
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), second)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, TaskStatus::Open);
+    assert_eq!(
+        info.tallies,
+        vec![TaskTally {
+            result: r#"{"y": 324}"#.to_string(),
+            power: Uint128::one(),
+            voters: vec![voter(&op_nodes[1])],
+        }]
+    );
+
+    // Votes are paginated by operator
+    let votes = verifier
+        .list_task_votes(tasker.addr_str().unwrap(), first, None, None)
+        .unwrap()
+        .votes;
+    assert_eq!(votes.len(), 3);
+    assert!(votes.windows(2).all(|w| w[0].operator < w[1].operator));
+    let page = verifier
+        .list_task_votes(
+            tasker.addr_str().unwrap(),
+            first,
+            Some(votes[0].operator.clone()),
+            Some(1),
+        )
+        .unwrap()
+        .votes;
+    assert_eq!(page, votes[1..2]);
+
+    // Tasks can be filtered by status
+    let tasks = verifier
+        .list_tasks(tasker.addr_str().unwrap(), None, None, None)
+        .unwrap()
+        .tasks;
+    let ids: Vec<_> = tasks.iter().map(|task| task.task_id).collect();
+    assert_eq!(ids, vec![first, second]);
+    assert_eq!(tasks[0].status, TaskStatus::Completed);
+    assert_eq!(tasks[0].power_needed, Uint128::new(2));
+
+    let open = verifier
+        .list_tasks(
+            tasker.addr_str().unwrap(),
+            Some(TaskStatus::Open),
+            None,
+            None,
+        )
+        .unwrap()
+        .tasks;
+    assert_eq!(open, tasks[1..]);
+    let after_first = verifier
+        .list_tasks(tasker.addr_str().unwrap(), None, Some(first), None)
+        .unwrap()
+        .tasks;
+    assert_eq!(after_first, tasks[1..]);
+}
+
+/// Votes are no longer accepted once the task is cancelled in the task queue
+pub fn cancelled_task<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_nodes = [chain.alt_signer(3), chain.alt_signer(4)];
+
+    let operators = op_nodes
+        .iter()
+        .map(|n| InstantiateOperator::new(n.addr().to_string(), 1))
+        .collect();
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 100,
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    // The first vote stores the task in the verifier
+    let task_id = make_task(&tasker, "Test Task", None, &json!({"x": 17}));
+    let result = r#"{"y": 289}"#.to_string();
+    verifier
+        .call_as(&op_nodes[0])
+        .executed_task(tasker.addr_str().unwrap(), task_id, result.clone())
+        .unwrap();
+
+    tasker.cancel(task_id).unwrap();
+
+    // The next vote is ignored rather than failing to complete the task
+    verifier
+        .call_as(&op_nodes[1])
+        .executed_task(tasker.addr_str().unwrap(), task_id, result)
+        .unwrap();
+    let vote = verifier
+        .operator_vote(
+            tasker.addr_str().unwrap(),
+            task_id,
+            op_nodes[1].addr().into_string(),
+        )
+        .unwrap();
+    assert!(vote.is_none());
+
+    let info = verifier
+        .task_info(tasker.addr_str().unwrap(), task_id)
+        .unwrap()
+        .unwrap();
+    assert_eq!(info.status, TaskStatus::Cancelled);
+}
+
+/// Task fees are split between treasury, the verifier and the operators who voted for the accepted result
+pub fn operator_rewards<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_nodes = [
+        chain.alt_signer(3),
+        chain.alt_signer(4),
+        chain.alt_signer(5),
+    ];
+    let treasury = chain.alt_signer(6);
+    let fee_recipient = chain.alt_signer(7);
+
+    // Operator 3 has double power
+    let operators = op_nodes
+        .iter()
+        .zip([1, 1, 2])
+        .map(|(n, power)| InstantiateOperator::new(n.addr().to_string(), power))
+        .collect();
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 60,
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: Some(fee_recipient.addr().to_string()),
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    // 10% to the treasury, 30% to the verifier, the rest to the operators
+    let fee = coin(1000, DENOM);
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::OpenPayment(fee.clone()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: Some(FeeSplit {
+            treasury: Some(treasury.addr().to_string()),
+            treasury_share: Decimal::percent(10),
+            verifier_share: Decimal::percent(30),
+            sweep_bounty: Decimal::zero(),
+        }),
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    // Paying 300 on top of the fee as a tip, which all goes to the operators
+    let res = tasker
+        .create(
+            "Paid Task".to_string(),
+            None,
+            json!({"x": 17}),
+            None,
+            None,
+            &coins(fee.amount.u128() + 300, DENOM),
+        )
+        .unwrap();
+    let task_id = get_task_id(&res);
+
+    // Rewards cannot be sent for a task which is not completed
+    let err = verifier
+        .distribute_rewards(task_id, &coins(100, DENOM))
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskNotCompleted.to_string()));
+
+    // Operator 2 votes for a losing result, 1 and 3 complete the task
+    let votes = [r#"{"y": 289}"#, r#"{"y": 291}"#, r#"{"y": 289}"#];
+    for (op, result) in op_nodes.iter().zip(votes) {
+        verifier
+            .call_as(op)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+    }
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+
+    // Treasury is paid directly, operators by power
+    let treasury_balance = chain.balance(&treasury.addr(), Some(DENOM.to_string()));
+    assert_eq!(treasury_balance.unwrap(), coins(100, DENOM));
+    let expected = [coins(300, DENOM), vec![], coins(600, DENOM)];
+    for (op, rewards) in op_nodes.iter().zip(expected) {
+        let pending = verifier.pending_rewards(op.addr().to_string()).unwrap();
+        assert_eq!(pending.rewards, rewards);
+    }
+
+    // Operators claim their rewards once
+    verifier.call_as(&op_nodes[2]).claim_rewards().unwrap();
+    let balance = chain.balance(&op_nodes[2].addr(), Some(DENOM.to_string()));
+    assert_eq!(balance.unwrap(), coins(600, DENOM));
+    let pending = verifier
+        .pending_rewards(op_nodes[2].addr().to_string())
+        .unwrap();
+    assert!(pending.rewards.is_empty());
+
+    // The verifier share is claimed by the fee recipient
+    let recipient = verifier.fee_recipient().unwrap().recipient;
+    assert_eq!(recipient, Some(fee_recipient.addr().to_string()));
+    verifier.call_as(&fee_recipient).claim_rewards().unwrap();
+    let balance = chain.balance(&fee_recipient.addr(), Some(DENOM.to_string()));
+    assert_eq!(balance.unwrap(), coins(300, DENOM));
+
+    for op in [&op_nodes[1], &op_nodes[2], &fee_recipient] {
+        let err = verifier.call_as(op).claim_rewards().unwrap_err();
+        assert!(err
+            .root()
+            .to_string()
+            .contains(&ContractError::NoRewards.to_string()));
+    }
+}
+
+/// Results which don't match the limits are not counted as votes, and are counted against the operator instead
+pub fn invalid_results<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_node = chain.alt_signer(3);
+
+    let operators = vec![InstantiateOperator::new(op_node.addr().to_string(), 20)];
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    // An invalid schema is rejected upfront
+    let verifier = Contract::new(chain.clone());
+    verifier.upload().unwrap();
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 70,
+        max_result_bytes: Some(20),
+        result_schema: Some(json!({"type": "object", "required": "y"})),
+        fee_recipient: None,
+    };
+    verifier.instantiate(&msg, None, &[]).unwrap_err();
+
+    let msg = InstantiateMsg {
+        result_schema: Some(json!({"type": "object", "required": ["y"]})),
+        ..msg
+    };
+    verifier.instantiate(&msg, None, &[]).unwrap();
+
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    let payload = json!({"x": 17});
+    let task_id = make_task(&tasker, "Test Task", None, &payload);
+
+    for (result, reason) in [
+        (r#"{"y": "#, "Result is not valid JSON"),
+        (r#"{"z": 289}"#, "Result does not match the schema"),
+        (
+            r#"{"y": 289, "padding": true}"#,
+            "Result is 27 bytes, above the limit of 20 bytes",
+        ),
+    ] {
+        let res = verifier
+            .call_as(&op_node)
+            .executed_task(tasker.addr_str().unwrap(), task_id, result.to_string())
+            .unwrap();
+        let event = res
+            .events()
+            .iter()
+            .find_map(|event| InvalidResultEvent::try_from(event).ok())
+            .unwrap();
+        assert!(event.reason.starts_with(reason), "{}", event.reason);
+    }
+
+    let invalid = verifier
+        .invalid_submissions(op_node.addr().into_string())
+        .unwrap();
+    assert_eq!(invalid.count, 3);
+    let status = tasker.task(task_id).unwrap();
+    assert!(matches!(status.status, Status::Open { .. }));
+
+    // The rejected results were not votes, so the operator can still vote
+    verifier
+        .call_as(&op_node)
+        .executed_task(
+            tasker.addr_str().unwrap(),
+            task_id,
+            r#"{"y": 289}"#.to_string(),
+        )
+        .unwrap();
+    let status = tasker.task(task_id).unwrap();
+    assert_eq!(status.result, Some(json!({"y": 289})));
+
+    let config = verifier.config().unwrap();
+    assert_eq!(config.max_result_bytes, Some(20));
+}
+
+/// Operators are tracked on every vote and completed task
+pub fn operator_stats<C>(chain: C)
+where
+    C: CwEnv + AltSigner,
+    C::Sender: Addressable,
+{
+    let op_nodes = [
+        chain.alt_signer(3),
+        chain.alt_signer(4),
+        chain.alt_signer(5),
+        chain.alt_signer(6),
+    ];
+
+    let operators = op_nodes
+        .iter()
+        .map(|n| InstantiateOperator::new(n.addr().to_string(), 1))
+        .collect();
+    let msg = MockOperatorsInstantiateMsg { operators };
+    let operators = MockOperatorsContract::new(chain.clone());
+    operators.upload().unwrap();
+    operators.instantiate(&msg, None, &[]).unwrap();
+
+    // 50% of 4 means 2 needed
+    let msg = InstantiateMsg {
+        operator_contract: operators.addr_str().unwrap(),
+        required_percentage: 50,
+        max_result_bytes: None,
+        result_schema: None,
+        fee_recipient: None,
+    };
+    let verifier = setup(chain.clone(), msg);
+
+    let msg = TasksInstantiateMsg {
+        requestor: Requestor::Fixed(chain.sender_addr().into()),
+        timeout: TimeoutInfo::new(Duration::new_seconds(600)),
+        verifier: verifier.addr_str().unwrap(),
+        owner: None,
+        task_specific_whitelist: None,
+        payload_schema: None,
+        result_schema: None,
+        fees: None,
+    };
+    let tasker = TasksContract::new(chain.clone());
+    tasker.upload().unwrap();
+    tasker.instantiate(&msg, None, &[]).unwrap();
+
+    // Nothing is tracked before any vote
+    let stats = verifier
+        .operator_stats(op_nodes[0].addr().into_string())
+        .unwrap();
+    assert_eq!(stats.tasks_voted, 0);
+    assert_eq!(stats.average_latency, None);
+
+    // Operator 2 votes for a losing result, 1 and 3 complete the task, and 4 misses it
+    let first_task = make_task(&tasker, "Test Task", None, &json!({"x": 17}));
+    let votes = [
+        (&op_nodes[0], r#"{"y": 289}"#, 10),
+        (&op_nodes[1], r#"{"y": 291}"#, 20),
+        (&op_nodes[2], r#"{"y": 289}"#, 0),
+    ];
+    for (op, result, wait) in votes {
+        chain.wait_seconds(wait).unwrap();
+        verifier
+            .call_as(op)
+            .executed_task(tasker.addr_str().unwrap(), first_task, result.to_string())
+            .unwrap();
+    }
+    let status = tasker.task(first_task).unwrap();
+    assert!(matches!(status.status, Status::Completed { .. }));
+
+    // Operators 4 and 1 complete the next task right away, 2 and 3 miss it
+    let second_task = make_task(&tasker, "Test Task", None, &json!({"x": 18}));
+    for op in [&op_nodes[3], &op_nodes[0]] {
+        verifier
+            .call_as(op)
+            .executed_task(
+                tasker.addr_str().unwrap(),
+                second_task,
+                r#"{"y": 324}"#.to_string(),
+            )
+            .unwrap();
+    }
+
+    // A vote on a task that is still open is not matched yet
+    let open_task = make_task(&tasker, "Test Task", None, &json!({"x": 19}));
+    verifier
+        .call_as(&op_nodes[1])
+        .executed_task(
+            tasker.addr_str().unwrap(),
+            open_task,
+            r#"{"y": 361}"#.to_string(),
+        )
+        .unwrap();
+
+    // Missed tasks are only counted once recorded
+    let stats = verifier
+        .operator_stats(op_nodes[3].addr().into_string())
+        .unwrap();
+    assert_eq!(stats.tasks_missed, 0);
+
+    // Anyone can record them, voters and addresses without voting power are skipped
+    let mut candidates: Vec<String> = op_nodes.iter().map(|n| n.addr().to_string()).collect();
+    candidates.push(chain.alt_signer(7).addr().to_string());
+    for task_id in [first_task, second_task] {
+        verifier
+            .call_as(&chain.alt_signer(7))
+            .record_missed(tasker.addr_str().unwrap(), task_id, candidates.clone())
+            .unwrap();
+    }
+    // Recording a task again doesn't count it twice
+    verifier
+        .record_missed(tasker.addr_str().unwrap(), first_task, candidates.clone())
+        .unwrap();
+
+    // Nothing is missed on a task that is still open
+    let err = verifier
+        .record_missed(tasker.addr_str().unwrap(), open_task, candidates)
+        .unwrap_err();
+    assert!(err
+        .root()
+        .to_string()
+        .contains(&ContractError::TaskNotCompleted.to_string()));
+
+    // (voted, matched, outside spread, missed, average latency)
+    let expected = [
+        (2, 2, 0, 0, Some(5)),
+        (2, 0, 1, 1, Some(15)),
+        (1, 1, 0, 1, Some(30)),
+        (1, 1, 0, 1, Some(0)),
+    ];
+    for (op, expected) in op_nodes.iter().zip(expected) {
+        let stats = verifier.operator_stats(op.addr().into_string()).unwrap();
+        assert_eq!(stats.operator, op.addr().as_str());
+        let stats = (
+            stats.tasks_voted,
+            stats.votes_matched,
+            stats.votes_outside_spread,
+            stats.tasks_missed,
+            stats.average_latency,
+        );
+        assert_eq!(stats, expected);
+    }
+
+    // All operators are listed, ordered by address
+    let all = verifier.list_operator_stats(None, None).unwrap().operators;
+    assert_eq!(all.len(), 4);
+    assert!(all.windows(2).all(|w| w[0].operator < w[1].operator));
+    let page = verifier
+        .list_operator_stats(Some(all[1].operator.clone()), Some(1))
+        .unwrap()
+        .operators;
+    assert_eq!(page, vec![all[2].clone()]);
+}
 
 #[track_caller]
 pub fn make_task<C: ChainState + TxHandler>(
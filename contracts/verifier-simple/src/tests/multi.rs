@@ -1,6 +1,7 @@
-use cw_orch::prelude::MockBech32;
+use cosmwasm_std::coins;
+use cw_orch::prelude::*;
 
-use super::common::BECH_PREFIX;
+use super::common::{BECH_PREFIX, DENOM};
 
 #[test]
 fn happy_path_works() {
@@ -13,3 +14,36 @@ fn require_quorum_works() {
     let chain = MockBech32::new(BECH_PREFIX);
     super::common::require_quorum(chain);
 }
+
+#[test]
+fn task_votes_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::task_votes(chain);
+}
+
+#[test]
+fn cancelled_task_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::cancelled_task(chain);
+}
+
+#[test]
+fn invalid_results() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::invalid_results(chain);
+}
+
+#[test]
+fn operator_stats_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    super::common::operator_stats(chain);
+}
+
+#[test]
+fn operator_rewards_works() {
+    let chain = MockBech32::new(BECH_PREFIX);
+    chain
+        .add_balance(&chain.sender_addr(), coins(10_000, DENOM))
+        .unwrap();
+    super::common::operator_rewards(chain);
+}
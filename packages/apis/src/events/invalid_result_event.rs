@@ -0,0 +1,142 @@
+use crate::id::TaskId;
+use cosmwasm_std::{Attribute, Event, StdError};
+
+use super::traits::TypedEvent;
+
+/// Emitted by verifiers when an operator's result is rejected, instead of failing the transaction
+/// so the rejection is counted against the operator
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidResultEvent {
+    pub task_id: TaskId,
+    pub task_queue: String,
+    pub operator: String,
+    /// Why the result was rejected
+    pub reason: String,
+    /// How many results of this operator were rejected so far, this one included
+    pub invalid_submissions: u64,
+}
+
+impl TypedEvent for InvalidResultEvent {
+    const NAME: &'static str = "invalid_result";
+}
+
+impl TryFrom<&Event> for InvalidResultEvent {
+    type Error = StdError;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        if !Self::is_type(&event.ty) {
+            return Err(StdError::generic_err(format!(
+                "expected type was {}, but got {}",
+                Self::NAME,
+                event.ty
+            )));
+        }
+
+        let mut task_id: Option<TaskId> = None;
+        let mut task_queue: Option<String> = None;
+        let mut operator: Option<String> = None;
+        let mut reason: Option<String> = None;
+        let mut invalid_submissions: Option<u64> = None;
+
+        for Attribute { key, value } in event.attributes.iter() {
+            match key.as_str() {
+                "task-id" => {
+                    if let Ok(value) = value.parse() {
+                        task_id = Some(value);
+                    }
+                }
+                "task-queue" => {
+                    task_queue = Some(value.to_string());
+                }
+                "operator" => {
+                    operator = Some(value.to_string());
+                }
+                "reason" => {
+                    reason = Some(value.to_string());
+                }
+                "invalid-submissions" => {
+                    if let Ok(value) = value.parse() {
+                        invalid_submissions = Some(value);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match (task_id, task_queue, operator, reason, invalid_submissions) {
+            (
+                Some(task_id),
+                Some(task_queue),
+                Some(operator),
+                Some(reason),
+                Some(invalid_submissions),
+            ) => Ok(Self {
+                task_id,
+                task_queue,
+                operator,
+                reason,
+                invalid_submissions,
+            }),
+            _ => Err(StdError::generic_err(format!(
+                "Could not parse fields for {}",
+                Self::NAME,
+            ))),
+        }
+    }
+}
+
+impl TryFrom<Event> for InvalidResultEvent {
+    type Error = StdError;
+
+    fn try_from(event: Event) -> Result<Self, Self::Error> {
+        InvalidResultEvent::try_from(&event)
+    }
+}
+
+impl From<InvalidResultEvent> for Event {
+    fn from(value: InvalidResultEvent) -> Self {
+        Self::new(InvalidResultEvent::NAME).add_attributes([
+            ("task-id", value.task_id.to_string()),
+            ("task-queue", value.task_queue),
+            ("operator", value.operator),
+            ("reason", value.reason),
+            ("invalid-submissions", value.invalid_submissions.to_string()),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_result_event_parsing() {
+        let og_event = InvalidResultEvent {
+            task_id: TaskId::new(3),
+            task_queue: "queue_address".to_string(),
+            operator: "operator_address".to_string(),
+            reason: "Result is not valid JSON: EOF while parsing".to_string(),
+            invalid_submissions: 2,
+        };
+
+        let cosm_event: Event = og_event.clone().into();
+
+        let parsed_event =
+            InvalidResultEvent::try_from(&cosm_event).expect("failed to parse event");
+
+        assert_eq!(og_event, parsed_event);
+    }
+
+    #[test]
+    fn invalid_result_event_with_missing_attribute() {
+        let cosm_event = Event::new(InvalidResultEvent::NAME).add_attributes([
+            ("task-id", "3"),
+            ("task-queue", "queue_address"),
+            ("operator", "operator_address"),
+            // we skip the reason attribute
+            ("invalid-submissions", "1"),
+        ]);
+
+        assert!(InvalidResultEvent::try_from(&cosm_event).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+pub mod invalid_result_event;
 pub mod oracle_executed_event;
 pub mod task_executed_event;
 pub mod task_queue_events;
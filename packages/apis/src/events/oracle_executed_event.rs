@@ -40,6 +40,8 @@ impl FromStr for OracleExecutionStatus {
 pub struct OracleExecutedEvent {
     pub task_id: TaskId,
     pub status: OracleExecutionStatus,
+    /// The feed of the new price, emitted once per feed when the threshold is met
+    pub feed: Option<String>,
     pub new_price: Option<Decimal>,
     pub task_queue_contract: String,
 }
@@ -62,6 +64,7 @@ impl TryFrom<&Event> for OracleExecutedEvent {
 
         let mut task_id: Option<TaskId> = None;
         let mut status: Option<OracleExecutionStatus> = None;
+        let mut feed: Option<String> = None;
         let mut new_price: Option<Decimal> = None;
         let mut task_queue_contract: Option<String> = None;
 
@@ -77,6 +80,9 @@ impl TryFrom<&Event> for OracleExecutedEvent {
                         status = Some(value);
                     }
                 }
+                "feed" => {
+                    feed = Some(value.clone());
+                }
                 "new-price" => {
                     if let Ok(value) = value.parse() {
                         new_price = Some(value);
@@ -93,6 +99,7 @@ impl TryFrom<&Event> for OracleExecutedEvent {
             (Some(task_id), Some(status), Some(task_queue_contract)) => Ok(Self {
                 task_id,
                 status,
+                feed,
                 new_price,
                 task_queue_contract,
             }),
@@ -131,6 +138,13 @@ impl From<OracleExecutedEvent> for Event {
             },
         ];
 
+        if let Some(feed) = value.feed {
+            attributes.push(Attribute {
+                key: "feed".to_string(),
+                value: feed,
+            });
+        }
+
         if let Some(new_price) = value.new_price {
             attributes.push(Attribute {
                 key: "new-price".to_string(),
@@ -155,6 +169,7 @@ mod tests {
         let original_event = OracleExecutedEvent {
             task_id: TaskId::new(1),
             status: OracleExecutionStatus::ThresholdMet,
+            feed: Some("BTC/USD".to_string()),
             // 1.5
             new_price: Some(Decimal::percent(150)),
             task_queue_contract: "task_queue_contract_addr".to_string(),
@@ -173,6 +188,7 @@ mod tests {
         let original_event = OracleExecutedEvent {
             task_id: TaskId::new(2),
             status: OracleExecutionStatus::VoteStored,
+            feed: None,
             new_price: None,
             task_queue_contract: "task_queue_contract_addr".to_string(),
         };
@@ -7,6 +7,7 @@ use super::traits::TypedEvent;
 define_task_queue_event!(TaskCreatedEvent, "task_created_event");
 define_task_queue_event!(TaskCompletedEvent, "task_completed_event");
 define_task_queue_event!(TaskExpiredEvent, "task_expired_event");
+define_task_queue_event!(TaskCancelledEvent, "task_cancelled_event");
 define_hook_event!(HookAddedEvent, "hook_added_event");
 define_hook_event!(HookRemovedEvent, "hook_removed_event");
 
@@ -144,3 +145,57 @@ macro_rules! define_hook_event {
         }
     };
 }
+
+/// Emitted once by `TimeoutExpired`, along with a `TaskExpiredEvent` per task
+#[derive(Debug, Clone, PartialEq)]
+pub struct TasksSweptEvent {
+    pub count: u32,
+}
+
+impl TypedEvent for TasksSweptEvent {
+    const NAME: &'static str = "tasks_swept_event";
+}
+
+impl TryFrom<&Event> for TasksSweptEvent {
+    type Error = StdError;
+
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        if !Self::is_type(&event.ty) {
+            return Err(StdError::generic_err(format!(
+                "Expected event type '{}', but got '{}'",
+                Self::NAME,
+                event.ty
+            )));
+        }
+
+        let count = event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "count")
+            .ok_or_else(|| {
+                StdError::generic_err(format!(
+                    "Could not parse 'count' field for '{}'",
+                    Self::NAME
+                ))
+            })?
+            .value
+            .parse()
+            .map_err(|e| StdError::generic_err(format!("Failed to parse 'count': {}", e)))?;
+
+        Ok(Self { count })
+    }
+}
+
+impl TryFrom<Event> for TasksSweptEvent {
+    type Error = StdError;
+
+    fn try_from(event: Event) -> Result<Self, Self::Error> {
+        TasksSweptEvent::try_from(&event)
+    }
+}
+
+impl From<TasksSweptEvent> for Event {
+    fn from(value: TasksSweptEvent) -> Self {
+        Event::new(TasksSweptEvent::NAME).add_attribute("count", value.count.to_string())
+    }
+}
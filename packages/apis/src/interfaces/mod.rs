@@ -1,3 +1,6 @@
+pub mod operator_stats;
+pub mod rewards;
+pub mod slashing;
 pub mod task_hooks;
 pub mod tasks;
 pub mod voting;
@@ -0,0 +1,174 @@
+/// The shared storage verifiers use to track how operators perform on their tasks,
+/// along with the query responses to expose it.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Map};
+
+use crate::id::TaskId;
+
+#[cw_serde]
+#[derive(Default)]
+pub struct OperatorStats {
+    /// Tasks the operator submitted a valid result for
+    pub tasks_voted: u64,
+    /// Votes on completed tasks which agreed with the accepted result
+    pub votes_matched: u64,
+    /// Votes on completed tasks outside the allowed spread of the accepted result.
+    /// Verifiers requiring an exact match count every other result here.
+    pub votes_outside_spread: u64,
+    /// Tasks completed without a vote from the operator, while it had voting power on them.
+    /// These are not found when the task is completed, but recorded afterwards by anyone.
+    pub tasks_missed: u64,
+    /// Sum of the seconds from task creation to vote
+    pub total_latency: u64,
+    /// Votes included in `total_latency`, tasks stored before the creation time was tracked are left out
+    pub latency_samples: u64,
+}
+
+impl OperatorStats {
+    /// Average seconds from task creation to vote, if any vote was timed
+    pub fn average_latency(&self) -> Option<u64> {
+        self.total_latency.checked_div(self.latency_samples)
+    }
+}
+
+#[cw_serde]
+pub struct OperatorStatsResponse {
+    pub operator: String,
+    pub tasks_voted: u64,
+    pub votes_matched: u64,
+    pub votes_outside_spread: u64,
+    pub tasks_missed: u64,
+    /// Average seconds from task creation to vote, unset if the operator never voted
+    pub average_latency: Option<u64>,
+}
+
+impl OperatorStatsResponse {
+    fn new(operator: String, stats: OperatorStats) -> Self {
+        Self {
+            operator,
+            tasks_voted: stats.tasks_voted,
+            votes_matched: stats.votes_matched,
+            votes_outside_spread: stats.votes_outside_spread,
+            tasks_missed: stats.tasks_missed,
+            average_latency: stats.average_latency(),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ListOperatorStatsResponse {
+    pub operators: Vec<OperatorStatsResponse>,
+}
+
+pub struct OperatorStatsStorage<'a> {
+    /// operator -> stats
+    pub stats: Map<&'a Addr, OperatorStats>,
+    /// The missed tasks already counted, so they are counted once
+    /// (task_queue, task_id, operator) -> ()
+    pub missed: Map<(&'a Addr, TaskId, &'a Addr), Empty>,
+}
+
+impl<'a> OperatorStatsStorage<'a> {
+    pub const fn new(stats: &'static str, missed: &'static str) -> Self {
+        Self {
+            stats: Map::new(stats),
+            missed: Map::new(missed),
+        }
+    }
+
+    /// Counts a vote, with the seconds since the task was created if known
+    pub fn record_vote(
+        &self,
+        storage: &mut dyn Storage,
+        operator: &Addr,
+        latency: Option<u64>,
+    ) -> StdResult<()> {
+        self.update(storage, operator, |stats| {
+            stats.tasks_voted += 1;
+            if let Some(latency) = latency {
+                stats.total_latency += latency;
+                stats.latency_samples += 1;
+            }
+        })
+    }
+
+    /// Counts a vote on a completed task as matching the accepted result, or outside the allowed spread
+    pub fn record_result(
+        &self,
+        storage: &mut dyn Storage,
+        operator: &Addr,
+        matched: bool,
+    ) -> StdResult<()> {
+        self.update(storage, operator, |stats| {
+            if matched {
+                stats.votes_matched += 1;
+            } else {
+                stats.votes_outside_spread += 1;
+            }
+        })
+    }
+
+    /// Counts a task completed without a vote from the operator.
+    /// Returns false, without counting it, if it was already counted.
+    pub fn record_missed(
+        &self,
+        storage: &mut dyn Storage,
+        task_queue: &Addr,
+        task_id: TaskId,
+        operator: &Addr,
+    ) -> StdResult<bool> {
+        let key = (task_queue, task_id, operator);
+        if self.missed.has(storage, key) {
+            return Ok(false);
+        }
+        self.missed.save(storage, key, &Empty {})?;
+        self.update(storage, operator, |stats| stats.tasks_missed += 1)?;
+        Ok(true)
+    }
+
+    fn update(
+        &self,
+        storage: &mut dyn Storage,
+        operator: &Addr,
+        action: impl FnOnce(&mut OperatorStats),
+    ) -> StdResult<()> {
+        self.stats
+            .update(storage, operator, |stats| -> StdResult<_> {
+                let mut stats = stats.unwrap_or_default();
+                action(&mut stats);
+                Ok(stats)
+            })?;
+        Ok(())
+    }
+
+    /// The stats of the operator, all zero if it never took part in a task
+    pub fn query(&self, storage: &dyn Storage, operator: Addr) -> StdResult<OperatorStatsResponse> {
+        let stats = self.stats.may_load(storage, &operator)?.unwrap_or_default();
+        Ok(OperatorStatsResponse::new(operator.into_string(), stats))
+    }
+
+    /// The stats of every operator which took part in a task, ordered by address
+    pub fn query_list(
+        &self,
+        storage: &dyn Storage,
+        start_after: Option<Addr>,
+        limit: Option<u32>,
+    ) -> StdResult<ListOperatorStatsResponse> {
+        let limit = limit.unwrap_or(30);
+        let operators = self
+            .stats
+            .range(
+                storage,
+                start_after.as_ref().map(Bound::exclusive),
+                None,
+                Order::Ascending,
+            )
+            .take(limit as usize)
+            .map(|r| {
+                r.map(|(operator, stats)| OperatorStatsResponse::new(operator.into_string(), stats))
+            })
+            .collect::<StdResult<_>>()?;
+        Ok(ListOperatorStatsResponse { operators })
+    }
+}
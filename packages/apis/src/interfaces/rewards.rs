@@ -0,0 +1,154 @@
+/// These are messages a task queue sends to verifiers which support operator rewards,
+/// along with the shared storage to track rewards until the operators claim them.
+/// This must be a subset of any of the implementation.
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, BankMsg, Coin, Order, StdResult, Storage, Uint128};
+use cw_orch::{ExecuteFns, QueryFns};
+use cw_storage_plus::Map;
+
+use crate::id::TaskId;
+
+#[cw_serde]
+#[derive(ExecuteFns)]
+#[cw_orch(disable_fields_sorting)]
+pub enum RewardsExecuteMsg {
+    /// Sent by a task queue upon completion, with the operators' share of the task fee as funds.
+    /// The funds are split between the operators who voted for the accepted result, by power.
+    DistributeRewards {
+        /// The completed task the fee was paid for
+        task_id: TaskId,
+    },
+    /// Sent by a task queue upon completion, with the verifier's share of the task fee as funds.
+    /// It is credited to the fee recipient of the verifier, who claims it like operator rewards.
+    CollectFee {
+        /// The completed task the fee was paid for
+        task_id: TaskId,
+    },
+}
+
+#[cw_serde]
+#[derive(QueryFns)]
+#[cw_orch(disable_fields_sorting)]
+#[derive(QueryResponses)]
+pub enum RewardsQueryMsg {
+    /// The rewards an operator can claim.
+    /// Task queues also send it to check that the verifier supports operator rewards.
+    #[returns(PendingRewardsResponse)]
+    PendingRewards { operator: String },
+    /// Who the verifier's share of task fees is credited to.
+    /// Task queues check that it is set before paying the verifier a share.
+    #[returns(FeeRecipientResponse)]
+    FeeRecipient {},
+}
+
+#[cw_serde]
+pub struct PendingRewardsResponse {
+    /// All rewards the operator can claim, one entry per denom
+    pub rewards: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct FeeRecipientResponse {
+    /// Fees sent with `CollectFee` are rejected if unset
+    pub recipient: Option<String>,
+}
+
+pub struct OperatorRewards<'a> {
+    /// Unclaimed rewards
+    /// (operator, denom) -> amount
+    pub pending: Map<(&'a Addr, &'a str), Uint128>,
+}
+
+impl<'a> OperatorRewards<'a> {
+    pub const fn new(pending: &'static str) -> Self {
+        Self {
+            pending: Map::new(pending),
+        }
+    }
+
+    /// Splits the funds between the voters proportional to their power.
+    /// Rounding leftovers go to the first voter, so no funds are stuck in the contract.
+    pub fn distribute(
+        &self,
+        storage: &mut dyn Storage,
+        funds: &[Coin],
+        voters: &[(Addr, Uint128)],
+    ) -> StdResult<()> {
+        let total_power: Uint128 = voters.iter().map(|(_, power)| power).sum();
+        if total_power.is_zero() {
+            return Ok(());
+        }
+
+        for coin in funds {
+            let mut remaining = coin.amount;
+            for (voter, power) in voters {
+                let share = coin.amount.multiply_ratio(*power, total_power);
+                remaining -= share;
+                self.add(storage, voter, &coin.denom, share)?;
+            }
+            // voters cannot be empty with non-zero power
+            self.add(storage, &voters[0].0, &coin.denom, remaining)?;
+        }
+        Ok(())
+    }
+
+    /// Credits all the funds to a single address, such as the verifier's fee recipient
+    pub fn credit(
+        &self,
+        storage: &mut dyn Storage,
+        recipient: &Addr,
+        funds: &[Coin],
+    ) -> StdResult<()> {
+        for coin in funds {
+            self.add(storage, recipient, &coin.denom, coin.amount)?;
+        }
+        Ok(())
+    }
+
+    fn add(
+        &self,
+        storage: &mut dyn Storage,
+        operator: &Addr,
+        denom: &str,
+        amount: Uint128,
+    ) -> StdResult<()> {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        self.pending
+            .update(storage, (operator, denom), |old| -> StdResult<_> {
+                Ok(old.unwrap_or_default() + amount)
+            })?;
+        Ok(())
+    }
+
+    /// Lists all unclaimed rewards of the operator
+    pub fn query_pending(
+        &self,
+        storage: &dyn Storage,
+        operator: &Addr,
+    ) -> StdResult<PendingRewardsResponse> {
+        let rewards = self
+            .pending
+            .prefix(operator)
+            .range(storage, None, None, Order::Ascending)
+            .map(|r| r.map(|(denom, amount)| Coin { denom, amount }))
+            .collect::<StdResult<_>>()?;
+        Ok(PendingRewardsResponse { rewards })
+    }
+
+    /// Clears all unclaimed rewards of the operator, returning the messages to pay them out.
+    /// Returns no messages if there is nothing to claim.
+    pub fn claim(&self, storage: &mut dyn Storage, operator: &Addr) -> StdResult<Vec<BankMsg>> {
+        let rewards = self.query_pending(storage, operator)?.rewards;
+        let mut msgs = Vec::with_capacity(rewards.len());
+        for coin in rewards {
+            self.pending.remove(storage, (operator, &coin.denom));
+            msgs.push(BankMsg::Send {
+                to_address: operator.to_string(),
+                amount: vec![coin],
+            });
+        }
+        Ok(msgs)
+    }
+}
@@ -0,0 +1,45 @@
+/// These are messages that can be sent to any operator group implementation
+/// which supports slashing. Verifiers send them when an operator misbehaves.
+/// This must be a subset of any of the implementation.
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Decimal, Uint128};
+use cw_orch::ExecuteFns;
+
+use crate::id::TaskId;
+
+#[cw_serde]
+#[derive(ExecuteFns)]
+#[cw_orch(disable_fields_sorting)]
+pub enum SlashExecuteMsg {
+    /// Slash an operator for misbehaving on a task.
+    /// Only authorized slashers (usually verifier contracts) may call this.
+    Slash {
+        /// The operator to slash
+        operator: String,
+        /// The task queue the misbehaviour happened on
+        task_queue: String,
+        /// The task the misbehaviour happened on
+        task_id: TaskId,
+        /// How much of the operator's stake to slash
+        amount: SlashAmount,
+    },
+}
+
+#[cw_serde]
+#[derive(Copy)]
+pub enum SlashAmount {
+    /// A fixed amount of tokens, capped at the operator's stake
+    Fixed(Uint128),
+    /// A fraction of the operator's stake, between 0 and 1
+    Fraction(Decimal),
+}
+
+impl SlashAmount {
+    /// Returns the amount to slash from the given stake
+    pub fn apply(&self, stake: Uint128) -> Uint128 {
+        match self {
+            SlashAmount::Fixed(amount) => (*amount).min(stake),
+            SlashAmount::Fraction(fraction) => stake.mul_floor((*fraction).min(Decimal::one())),
+        }
+    }
+}
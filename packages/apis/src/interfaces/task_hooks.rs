@@ -5,7 +5,10 @@ use cosmwasm_std::{
 use cw_storage_plus::Map;
 use thiserror::Error;
 
-use crate::{id::TaskId, tasks::TaskResponse};
+use crate::{
+    id::TaskId,
+    tasks::{RequestType, ResponseType, TaskResponse},
+};
 
 #[cw_serde]
 /// Maintains cw-controllers interface here
@@ -238,6 +241,7 @@ pub enum TaskHookType {
     Created,
     Completed,
     Timeout,
+    Cancelled,
 }
 
 impl TaskHookType {
@@ -246,6 +250,7 @@ impl TaskHookType {
             TaskHookType::Created => "created",
             TaskHookType::Completed => "completed",
             TaskHookType::Timeout => "timeout",
+            TaskHookType::Cancelled => "cancelled",
         }
     }
 }
@@ -265,9 +270,12 @@ pub enum TaskHookError {
     TaskAlreadyCreated {},
 }
 
+/// Sent to hook receivers. Receivers can use their own request and response types
+/// to have the task deserialized for them, which defaults to any JSON value.
 #[cw_serde]
-pub enum TaskHookExecuteMsg {
-    TaskCompletedHook(TaskResponse),
-    TaskTimeoutHook(TaskResponse),
-    TaskCreatedHook(TaskResponse),
+pub enum TaskHookExecuteMsg<Req = RequestType, Res = ResponseType> {
+    TaskCompletedHook(TaskResponse<Req, Res>),
+    TaskTimeoutHook(TaskResponse<Req, Res>),
+    TaskCreatedHook(TaskResponse<Req, Res>),
+    TaskCancelledHook(TaskResponse<Req, Res>),
 }
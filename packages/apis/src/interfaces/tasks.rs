@@ -16,7 +16,11 @@ use crate::interfaces::voting::QueryMsg as OperatorQueryMsg;
 
 use super::voting::TotalPowerResponse;
 
-// FIXME: hot to make these generic
+/// The default request type: any valid JSON.
+/// Messages and responses are generic over the request and response types, so an AVS
+/// can use its own (de)serializable types instead, which share the same JSON encoding.
+pub type RequestType = serde_json::Value;
+/// The default response type: any valid JSON. See `RequestType`.
 pub type ResponseType = serde_json::Value;
 
 pub struct TasksStorage<'a>(Map<(&'a Addr, TaskId), TaskMetadata>);
@@ -60,12 +64,33 @@ impl<'a> TasksStorage<'a> {
 
         match metadata {
             Some(meta) => {
-                // Ensure this is not yet expired (or completed)
+                // Ensure this is not yet expired (or completed or cancelled)
                 match meta.status {
                     TaskStatus::Completed => Err(VerifierError::TaskAlreadyCompleted),
-                    TaskStatus::Expired => Err(VerifierError::TaskExpired),
+                    TaskStatus::Expired | TaskStatus::Cancelled => Err(VerifierError::TaskExpired),
                     TaskStatus::Open if meta.is_expired(env) => Err(VerifierError::TaskExpired),
-                    _ => Ok(meta.clone()),
+                    TaskStatus::Open => {
+                        // The task queue doesn't notify verifiers when a task is cancelled,
+                        // so check it is still open there before accepting another vote
+                        let task_status: TaskStatusResponse = deps.querier.query_wasm_smart(
+                            task_queue.to_string(),
+                            &TaskQueryMsg::TaskStatus { id: task_id },
+                        )?;
+                        match task_status.status {
+                            TaskStatus::Open => Ok(meta),
+                            TaskStatus::Completed => Err(VerifierError::TaskAlreadyCompleted),
+                            status @ (TaskStatus::Expired | TaskStatus::Cancelled) => {
+                                // remember it, so it is neither queried again nor listed as open
+                                let meta = TaskMetadata { status, ..meta };
+                                tasks_storage.save_tasks(
+                                    deps.storage,
+                                    (task_queue, task_id),
+                                    meta,
+                                )?;
+                                Err(VerifierError::TaskExpired)
+                            }
+                        }
+                    }
                 }
             }
             None => {
@@ -77,7 +102,8 @@ impl<'a> TasksStorage<'a> {
                 // Abort early if not still open
                 match task_status.status {
                     TaskStatus::Completed => Err(VerifierError::TaskAlreadyCompleted),
-                    TaskStatus::Expired => Err(VerifierError::TaskExpired),
+                    // A cancelled task can no longer be completed, so treat it like an expired one
+                    TaskStatus::Expired | TaskStatus::Cancelled => Err(VerifierError::TaskExpired),
                     TaskStatus::Open => {
                         // If we create this, we need to calculate total vote power needed
                         let total_power: TotalPowerResponse = deps.querier.query_wasm_smart(
@@ -95,6 +121,7 @@ impl<'a> TasksStorage<'a> {
                             power_required,
                             status: TaskStatus::Open,
                             created_height: task_status.created_height,
+                            created_time: Some(task_status.created_time),
                             expires_time: task_status.expires_time,
                         };
                         tasks_storage.save_tasks(
@@ -116,13 +143,13 @@ impl<'a> TasksStorage<'a> {
 #[cw_serde]
 #[derive(ExecuteFns)]
 #[cw_orch(disable_fields_sorting)]
-pub enum TaskExecuteMsg {
+pub enum TaskExecuteMsg<Res = ResponseType> {
     /// This can only be called by the verifier contract
     Complete {
         /// The task ID to complete
         task_id: TaskId,
         /// The result of the task
-        response: ResponseType,
+        response: Res,
     },
 }
 
@@ -156,6 +183,7 @@ pub enum TaskStatus {
     Open,
     Completed,
     Expired,
+    Cancelled,
 }
 
 impl From<Status> for TaskStatus {
@@ -164,6 +192,7 @@ impl From<Status> for TaskStatus {
             Status::Open { .. } => TaskStatus::Open,
             Status::Completed { .. } => TaskStatus::Completed,
             Status::Expired { .. } => TaskStatus::Expired,
+            Status::Cancelled { .. } => TaskStatus::Cancelled,
         }
     }
 }
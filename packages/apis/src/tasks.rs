@@ -1,18 +1,14 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Addr, Coin, Env, Timestamp};
+use cosmwasm_std::{Addr, Coin, Decimal, Env, Timestamp};
 use cw_orch::{ExecuteFns, QueryFns};
 use cw_ownable::{cw_ownable_execute, cw_ownable_query, Ownership};
 
 pub use crate::interfaces::tasks::{
-    TaskExecuteMsg, TaskExecuteMsgFns, TaskQueryMsg, TaskQueryMsgFns, TaskStatus,
-    TaskStatusResponse,
+    RequestType, ResponseType, TaskExecuteMsg, TaskExecuteMsgFns, TaskQueryMsg, TaskQueryMsgFns,
+    TaskStatus, TaskStatusResponse,
 };
 use crate::{id::TaskId, interfaces::task_hooks::TaskHookType, time::Duration};
 
-// FIXME: make these generic
-pub type RequestType = serde_json::Value;
-pub type ResponseType = serde_json::Value;
-
 #[cw_serde]
 pub struct InstantiateMsg {
     /// Who can create new tasks
@@ -27,18 +23,58 @@ pub struct InstantiateMsg {
     /// - Can update task hooks
     /// - Can remove task hooks
     /// - Can update task specific whitelist
+    /// - Can update the requestor allowlist
+    /// - Can update the requestor, timeout and verifier configuration
     /// - Can transfer ownership
     ///
     /// Defaults to the message sender during initialization.
     pub owner: Option<String>,
     /// Optionally populate the task-specific whitelist at instantiation
     pub task_specific_whitelist: Option<Vec<String>>,
+    /// Optional JSON Schema every task payload must match on `Create`.
+    /// Only the subset supported by `lavs_helpers::json_schema` is accepted.
+    pub payload_schema: Option<serde_json::Value>,
+    /// Optional JSON Schema every result must match on `Complete`.
+    /// Only the subset supported by `lavs_helpers::json_schema` is accepted.
+    pub result_schema: Option<serde_json::Value>,
+    /// How task deposits (with `Requestor::OpenPayment`) are split upon completion.
+    ///
+    /// If unset, deposits are kept by the task queue.
+    pub fees: Option<FeeSplit>,
+}
+
+/// How a task deposit is split upon completion. Operators receive whatever is left
+/// after the treasury and verifier shares, which must not add up to more than 1.
+///
+/// The operator share is sent to the verifier in a `RewardsExecuteMsg::DistributeRewards`,
+/// so it, like the verifier share, is rejected unless the verifier supports operator rewards.
+#[cw_serde]
+pub struct FeeSplit {
+    /// Where the protocol share is sent. Required if `treasury_share` is not zero.
+    pub treasury: Option<String>,
+    /// Share of each deposit sent to the treasury
+    pub treasury_share: Decimal,
+    /// Share of each deposit sent to the verifier
+    pub verifier_share: Decimal,
+    /// Share of the deposit of an expired task paid to whoever expires it with `TimeoutExpired`.
+    /// The rest is refunded to the task creator.
+    #[serde(default)]
+    pub sweep_bounty: Decimal,
 }
 
 #[cw_serde]
 pub enum Requestor {
     Fixed(String),
     OpenPayment(Coin),
+    /// Any address on the allowlist, without payment
+    Allowlist(Vec<String>),
+    /// Any address on the allowlist, paying one of the coins
+    AllowlistWithPayment {
+        addrs: Vec<String>,
+        coins: Vec<Coin>,
+    },
+    /// Anyone paying one of the coins
+    OpenPaymentAnyOf(Vec<Coin>),
 }
 
 #[cw_serde]
@@ -63,20 +99,24 @@ impl TimeoutInfo {
     }
 }
 
+/// Generic over the request and response types, which default to any JSON value
 #[cw_serde]
 #[serde(untagged)]
-pub enum ExecuteMsg {
+pub enum ExecuteMsg<Req = RequestType, Res = ResponseType> {
     /// Complete and any other public APIs in the interface
-    Api(TaskExecuteMsg),
+    Api(TaskExecuteMsg<Res>),
     /// The messages unique to this contract implementation
-    Custom(CustomExecuteMsg),
+    Custom(CustomExecuteMsg<Req>),
 }
 
 #[cw_ownable_execute]
 #[cw_serde]
 #[derive(ExecuteFns)]
 #[cw_orch(disable_fields_sorting)]
-pub enum CustomExecuteMsg {
+pub enum CustomExecuteMsg<Req = RequestType> {
+    /// Anything paid above the price set by the requestor config is a tip. Tips raise the task
+    /// in `ListOpenByPriority`, and are paid in full to the operators who complete it.
+    /// They are only accepted in the first denom of the requestor config.
     #[cw_orch(payable)]
     Create {
         /// Human-readable description of the task
@@ -84,8 +124,7 @@ pub enum CustomExecuteMsg {
         /// Specify a task timeout, or use the default
         timeout: Option<Duration>,
         /// Machine-readable data for the AVS to use
-        /// FIXME: use generic T to enforce a AVS-specific format
-        payload: RequestType,
+        payload: Req,
         /// Optionally register timeout task hooks for a set of receivers
         /// Requires the sender to be in the task-specific whitelist
         with_timeout_hooks: Option<Vec<String>>,
@@ -97,6 +136,16 @@ pub enum CustomExecuteMsg {
         /// The task ID to complete
         task_id: TaskId,
     },
+    /// Expires open tasks past their timeout, earliest expiration first, up to `limit` tasks.
+    /// Fires their timeout hooks and refunds deposits, minus the sweep bounty which goes to the sender.
+    /// Anyone can call this, it fails if there is nothing to expire.
+    TimeoutExpired { limit: Option<u32> },
+    /// Cancels an open task and refunds its deposit in full.
+    /// Only the task creator or the owner can cancel.
+    Cancel {
+        /// The task ID to cancel
+        task_id: TaskId,
+    },
     /// Adds hooks to a set of receivers for the given task hook type
     AddHooks {
         /// Optional task id for task-specific hooks. If None, adds a global hook.
@@ -110,7 +159,7 @@ pub enum CustomExecuteMsg {
     RemoveHook {
         /// Optional task id to remove a task-specific hook. If None, removes a global hook.
         task_id: Option<TaskId>,
-        /// The type of hook to remove (Created, Completed, or Timeout)
+        /// The type of hook to remove (Created, Completed, Timeout or Cancelled)
         hook_type: TaskHookType,
         /// The receiver address that will stop receiving hook messages
         receiver: String,
@@ -121,16 +170,35 @@ pub enum CustomExecuteMsg {
         to_add: Option<Vec<String>>,
         to_remove: Option<Vec<String>>,
     },
+    /// Add or remove addresses from the requestor allowlist. Only callable by the owner.
+    UpdateRequestorAllowlist {
+        to_add: Option<Vec<String>>,
+        to_remove: Option<Vec<String>>,
+    },
+    /// Update the configuration, only callable by the owner. Fields set to None are left unchanged.
+    /// Open tasks keep the deposit and expiration they were created with.
+    UpdateConfig {
+        /// Switch between requestor modes, or change the payment coins.
+        /// This replaces the allowlist with the addresses given here (if any).
+        requestor: Option<Requestor>,
+        /// Timeout bounds for tasks created from now on
+        timeout: Option<TimeoutInfo>,
+        /// The new verifier, which will be the only one able to complete open tasks
+        verifier: Option<String>,
+    },
 }
 
-impl From<CustomExecuteMsg> for ExecuteMsg {
-    fn from(value: CustomExecuteMsg) -> Self {
+#[cw_serde]
+pub struct MigrateMsg {}
+
+impl<Req, Res> From<CustomExecuteMsg<Req>> for ExecuteMsg<Req, Res> {
+    fn from(value: CustomExecuteMsg<Req>) -> Self {
         Self::Custom(value)
     }
 }
 
-impl From<TaskExecuteMsg> for ExecuteMsg {
-    fn from(value: TaskExecuteMsg) -> Self {
+impl<Req, Res> From<TaskExecuteMsg<Res>> for ExecuteMsg<Req, Res> {
+    fn from(value: TaskExecuteMsg<Res>) -> Self {
         Self::Api(value)
     }
 }
@@ -164,6 +232,12 @@ pub enum CustomQueryMsg {
         start_after: Option<TaskId>,
         limit: Option<u32>,
     },
+    /// List open tasks, ordered descending by tip, then by task ID
+    #[returns(ListOpenResponse)]
+    ListOpenByPriority {
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
     /// List completed tasks, ordered descending by task ID
     #[returns(ListCompletedResponse)]
     ListCompleted {
@@ -187,6 +261,12 @@ pub enum CustomQueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Lists the addresses allowed to create tasks with the allowlist requestor modes
+    #[returns(RequestorAllowlistResponse)]
+    RequestorAllowlist {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 impl From<TaskQueryMsg> for QueryMsg {
@@ -204,12 +284,12 @@ impl From<CustomQueryMsg> for QueryMsg {
 /// This is detailed information about a task for listing, including the
 /// payload, result, and timing information.
 #[cw_serde]
-pub struct TaskInfoResponse {
+pub struct TaskInfoResponse<Req = RequestType, Res = ResponseType> {
     pub id: TaskId,
     pub description: String,
     pub status: InfoStatus,
-    pub payload: RequestType,
-    pub result: Option<ResponseType>,
+    pub payload: Req,
+    pub result: Option<Res>,
     pub created_at: Timestamp,
 }
 
@@ -218,16 +298,17 @@ pub enum InfoStatus {
     Open { expires: Timestamp },
     Completed { completed: Timestamp },
     Expired { expired: Timestamp },
+    Cancelled { cancelled: Timestamp },
 }
 
 #[cw_serde]
-pub struct ListResponse {
-    pub tasks: Vec<TaskInfoResponse>,
+pub struct ListResponse<Req = RequestType, Res = ResponseType> {
+    pub tasks: Vec<TaskInfoResponse<Req, Res>>,
 }
 
 #[cw_serde]
-pub struct ListOpenResponse {
-    pub tasks: Vec<OpenTaskOverview>,
+pub struct ListOpenResponse<Req = RequestType> {
+    pub tasks: Vec<OpenTaskOverview<Req>>,
 }
 
 #[cw_serde]
@@ -236,32 +317,44 @@ pub struct TaskSpecificWhitelistResponse {
 }
 
 #[cw_serde]
-pub struct ListCompletedResponse {
-    pub tasks: Vec<CompletedTaskOverview>,
+pub struct RequestorAllowlistResponse {
+    pub addrs: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct ListCompletedResponse<Res = ResponseType> {
+    pub tasks: Vec<CompletedTaskOverview<Res>>,
 }
 
 /// Minimal information about a task
 #[cw_serde]
-pub struct OpenTaskOverview {
+pub struct OpenTaskOverview<Req = RequestType> {
     pub id: TaskId,
     pub expires: Timestamp,
-    pub payload: RequestType,
+    pub payload: Req,
+    /// Paid on top of the task price, if any
+    pub tip: Option<Coin>,
 }
 
 /// Minimal information about a task
 #[cw_serde]
-pub struct CompletedTaskOverview {
+pub struct CompletedTaskOverview<Res = ResponseType> {
     pub id: TaskId,
     pub completed: Timestamp,
-    pub result: ResponseType,
+    pub result: Res,
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
     pub ownership: Ownership<Addr>,
+    /// For the allowlist modes `addrs` is always empty, use the `RequestorAllowlist` query instead
     pub requestor: Requestor,
     pub timeout: TimeoutConfig,
     pub verifier: String,
+    pub payload_schema: Option<serde_json::Value>,
+    pub result_schema: Option<serde_json::Value>,
+    /// Unset if deposits are kept by the task queue
+    pub fees: Option<FeeSplit>,
 }
 
 /// All timeouts are defined in seconds
@@ -275,12 +368,17 @@ pub struct TimeoutConfig {
 
 /// This is detailed information about a task, including the payload
 #[cw_serde]
-pub struct TaskResponse {
+pub struct TaskResponse<Req = RequestType, Res = ResponseType> {
     pub id: TaskId,
     pub description: String,
     pub status: Status,
-    pub payload: RequestType,
-    pub result: Option<ResponseType>,
+    pub payload: Req,
+    pub result: Option<Res>,
+    /// Block height at which the task was created
+    #[serde(default)]
+    pub created_height: u64,
+    #[serde(default)]
+    pub expires: Timestamp,
 }
 
 #[cw_serde]
@@ -288,6 +386,7 @@ pub enum Status {
     Open {},
     Completed { completed: Timestamp },
     Expired {},
+    Cancelled { cancelled: Timestamp },
 }
 
 impl Default for Status {
@@ -307,11 +406,18 @@ impl Status {
         }
     }
 
+    pub fn cancelled(env: &Env) -> Self {
+        Status::Cancelled {
+            cancelled: env.block.time,
+        }
+    }
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Status::Open {} => "open",
             Status::Completed { .. } => "completed",
             Status::Expired {} => "expired",
+            Status::Cancelled { .. } => "cancelled",
         }
     }
 }
@@ -333,10 +439,12 @@ mod deployment {
 
     pub const CONTRACT_ID: &str = "lavs_task_queue";
 
-    #[interface(InstantiateMsg, ExecuteMsg, QueryMsg, Empty, id = CONTRACT_ID)]
-    pub struct Contract;
+    /// Generic over the request and response types, so scripts can send their AVS-specific types.
+    /// Use `RequestType` and `ResponseType` to work with any JSON.
+    #[interface(InstantiateMsg, ExecuteMsg<Req, Res>, QueryMsg, MigrateMsg, id = CONTRACT_ID)]
+    pub struct Contract<Req, Res>;
 
-    impl<Chain> Uploadable for Contract<Chain> {
+    impl<Chain, Req, Res> Uploadable for Contract<Chain, Req, Res> {
         /// Return the path to the wasm file corresponding to the contract
         fn wasm(_chain: &ChainInfoOwned) -> WasmPath {
             artifacts_dir_from_workspace!()
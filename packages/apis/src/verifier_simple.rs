@@ -3,14 +3,14 @@ use cosmwasm_std::{Env, Timestamp, Uint128};
 use cw_orch::{ExecuteFns, QueryFns};
 
 use crate::id::TaskId;
+pub use crate::interfaces::operator_stats::{ListOperatorStatsResponse, OperatorStatsResponse};
+pub use crate::interfaces::rewards::{FeeRecipientResponse, PendingRewardsResponse};
 pub use crate::interfaces::tasks::TaskStatus;
 use cosmwasm_std::StdError;
 use cw_utils::PaymentError;
 use thiserror::Error;
 
-// FIXME: make these generic
-pub type RequestType = serde_json::Value;
-pub type ResponseType = serde_json::Value;
+pub use crate::interfaces::tasks::{RequestType, ResponseType};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -18,6 +18,13 @@ pub struct InstantiateMsg {
     pub operator_contract: String,
     /// The percentage of voting power needed to agree in order to complete a task
     pub required_percentage: u32,
+    /// Results above this size are rejected, if set
+    pub max_result_bytes: Option<u32>,
+    /// Schema every result must match, if any
+    pub result_schema: Option<serde_json::Value>,
+    /// Credited with the verifier's share of task fees, which it claims with `ClaimRewards`.
+    /// Task queues only pay the verifier a share if set.
+    pub fee_recipient: Option<String>,
 }
 
 #[derive(Error, Debug)]
@@ -41,6 +48,15 @@ pub enum VerifierError {
 
     #[error("Unauthorized")]
     Unauthorized,
+
+    #[error("Task is not completed")]
+    TaskNotCompleted,
+
+    #[error("No rewards to claim")]
+    NoRewards,
+
+    #[error("No fee recipient is configured")]
+    NoFeeRecipient,
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
@@ -55,9 +71,38 @@ pub enum ExecuteMsg {
         /// The ID of the task that was completed
         task_id: TaskId,
         /// The result of the task, (JSON) serialized as a string
-        /// It is serialized to allow for easy comparison and to avoid field sorting issues when verifying signatures
+        /// It is serialized to allow for easy comparison and to avoid field sorting issues when verifying signatures.
+        /// Results which are not valid JSON, or don't match the size limit or schema of the verifier, are rejected:
+        /// they are not counted as a vote, and the operator's invalid submissions count is increased.
         result: String,
     },
+    /// Sent by the task queue with the operators' share of the task fee.
+    /// It is split between the operators who voted for the accepted result, by power.
+    #[cw_orch(payable)]
+    DistributeRewards {
+        /// The completed task the fee was paid for
+        task_id: TaskId,
+    },
+    /// Sent by the task queue with the verifier's share of the task fee.
+    /// It is credited to the fee recipient, and rejected if there is none.
+    #[cw_orch(payable)]
+    CollectFee {
+        /// The completed task the fee was paid for
+        task_id: TaskId,
+    },
+    /// Withdraw all rewards accrued by the sender
+    ClaimRewards {},
+    /// Counts the completed task as missed by each of the given operators which had voting power on it
+    /// but didn't vote. Other operators, and ones already counted for the task, are skipped.
+    /// Anyone can send this, as missed tasks are not counted when the task is completed.
+    RecordMissed {
+        /// Task queue contract of the completed task
+        task_queue_contract: String,
+        /// The ID of the completed task
+        task_id: TaskId,
+        /// The operators to check
+        operators: Vec<String>,
+    },
 }
 
 #[cw_serde]
@@ -76,6 +121,26 @@ pub enum QueryMsg {
         /// The ID of the task we are interested in
         task_id: TaskId,
     },
+    /// All votes on the task, ordered by operator address
+    #[returns(ListTaskVotesResponse)]
+    ListTaskVotes {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// The ID of the task we are interested in
+        task_id: TaskId,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// The tasks of the task contract which received votes, ordered by id
+    #[returns(ListTasksResponse)]
+    ListTasks {
+        /// The task contract we are interested in
+        task_contract: String,
+        /// Only list the tasks with this status, if set
+        status: Option<TaskStatus>,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    },
     /// Ordered by completion time descending (last completed first)
     #[returns(Option<OperatorVoteInfoResponse>)]
     OperatorVote {
@@ -86,6 +151,24 @@ pub enum QueryMsg {
         /// The operator whose vote we are interested in
         operator: String,
     },
+    /// The rewards an operator can claim
+    #[returns(PendingRewardsResponse)]
+    PendingRewards { operator: String },
+    /// Who the verifier's share of task fees is credited to
+    #[returns(FeeRecipientResponse)]
+    FeeRecipient {},
+    /// How many rejected results an operator submitted
+    #[returns(InvalidSubmissionsResponse)]
+    InvalidSubmissions { operator: String },
+    /// How an operator performed on the tasks of this verifier
+    #[returns(OperatorStatsResponse)]
+    OperatorStats { operator: String },
+    /// The stats of every operator which took part in a task, ordered by address
+    #[returns(ListOperatorStatsResponse)]
+    ListOperatorStats {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
 }
 
 #[cw_serde]
@@ -94,6 +177,17 @@ pub struct ConfigResponse {
     pub operator_contract: String,
     /// The percentage of voting power needed to agree in order to complete a task
     pub required_percentage: u32,
+    /// Results above this size are rejected, if set
+    pub max_result_bytes: Option<u32>,
+    /// Schema every result must match, if any
+    pub result_schema: Option<serde_json::Value>,
+    /// Credited with the verifier's share of task fees, if set
+    pub fee_recipient: Option<String>,
+}
+
+#[cw_serde]
+pub struct InvalidSubmissionsResponse {
+    pub count: u64,
 }
 
 #[cw_serde]
@@ -113,6 +207,45 @@ pub struct TaskTally {
     pub result: String,
     /// The total voting power for this result
     pub power: Uint128,
+    /// The operators who voted for this result, ordered by address
+    pub voters: Vec<TaskVoter>,
+}
+
+#[cw_serde]
+pub struct TaskVoter {
+    pub operator: String,
+    /// The voting power of the operator for this task
+    pub power: Uint128,
+}
+
+#[cw_serde]
+pub struct ListTaskVotesResponse {
+    pub votes: Vec<TaskVoteResponse>,
+}
+
+#[cw_serde]
+pub struct TaskVoteResponse {
+    pub operator: String,
+    /// The voting power of the operator for this task
+    pub power: Uint128,
+    /// The result this operator voted for
+    pub result: String,
+}
+
+#[cw_serde]
+pub struct ListTasksResponse {
+    pub tasks: Vec<TaskSummary>,
+}
+
+#[cw_serde]
+pub struct TaskSummary {
+    pub task_id: TaskId,
+    /// The current state of the task
+    pub status: TaskStatus,
+    /// Total voting power needed to complete the task
+    pub power_needed: Uint128,
+    pub created_height: u64,
+    pub expires_time: Timestamp,
 }
 
 #[cw_serde]
@@ -129,6 +262,9 @@ pub struct TaskMetadata {
     pub power_required: Uint128,
     pub status: TaskStatus,
     pub created_height: u64,
+    /// Unset for tasks stored before it was tracked
+    #[serde(default)]
+    pub created_time: Option<Timestamp>,
     pub expires_time: Timestamp,
 }
 
@@ -136,6 +272,30 @@ impl TaskMetadata {
     pub fn is_expired(&self, env: &Env) -> bool {
         env.block.time >= self.expires_time
     }
+
+    /// The status, marking open tasks past their expiration as expired
+    pub fn current_status(&self, env: &Env) -> TaskStatus {
+        match self.status {
+            TaskStatus::Open if self.is_expired(env) => TaskStatus::Expired,
+            status => status,
+        }
+    }
+
+    pub fn summary(&self, env: &Env, task_id: TaskId) -> TaskSummary {
+        TaskSummary {
+            task_id,
+            status: self.current_status(env),
+            power_needed: self.power_required,
+            created_height: self.created_height,
+            expires_time: self.expires_time,
+        }
+    }
+
+    /// Seconds since the task was created, if the creation time is known
+    pub fn latency(&self, env: &Env) -> Option<u64> {
+        self.created_time
+            .map(|created| env.block.time.seconds().saturating_sub(created.seconds()))
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
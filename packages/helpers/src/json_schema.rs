@@ -0,0 +1,345 @@
+//! A small JSON Schema validator, cheap enough to run inside a contract.
+//!
+//! Only a subset of draft 7 is supported:
+//! `type`, `enum`, `const`, `properties`, `required`, `additionalProperties` (boolean or schema),
+//! `items` (single schema), `minItems`, `maxItems`, `minLength`, `maxLength`, `minimum` and `maximum`.
+//! Annotations like `title` or `description` are ignored.
+//! Any other keyword is rejected by [`check_schema`], so a schema is never silently ignored.
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SchemaError {
+    #[error("Invalid schema at {path}: {reason}")]
+    InvalidSchema { path: String, reason: String },
+
+    #[error("Mismatch at {path}: {reason}")]
+    Mismatch { path: String, reason: String },
+}
+
+const TYPES: &[&str] = &[
+    "null", "boolean", "object", "array", "number", "integer", "string",
+];
+const ANNOTATIONS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$comment",
+    "title",
+    "description",
+    "default",
+    "examples",
+];
+
+/// Ensures the schema only uses supported keywords, and that they are well-formed.
+/// Call this before storing a schema, so validation can't fail on the schema itself later.
+pub fn check_schema(schema: &Value) -> Result<(), SchemaError> {
+    check_at(schema, "$")
+}
+
+/// Validates the instance against a schema that passed [`check_schema`]
+pub fn validate(schema: &Value, instance: &Value) -> Result<(), SchemaError> {
+    validate_at(schema, instance, "$")
+}
+
+fn invalid(path: &str, reason: impl Into<String>) -> SchemaError {
+    SchemaError::InvalidSchema {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn mismatch(path: &str, reason: impl Into<String>) -> SchemaError {
+    SchemaError::Mismatch {
+        path: path.to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn as_object<'a>(schema: &'a Value, path: &str) -> Result<&'a Map<String, Value>, SchemaError> {
+    schema
+        .as_object()
+        .ok_or_else(|| invalid(path, "schema must be an object"))
+}
+
+fn check_at(schema: &Value, path: &str) -> Result<(), SchemaError> {
+    for (key, value) in as_object(schema, path)? {
+        match key.as_str() {
+            "type" => {
+                let names = match value {
+                    Value::String(name) => vec![name.as_str()],
+                    Value::Array(names) => names
+                        .iter()
+                        .map(|n| {
+                            n.as_str()
+                                .ok_or_else(|| invalid(path, "type must be strings"))
+                        })
+                        .collect::<Result<_, _>>()?,
+                    _ => return Err(invalid(path, "type must be a string or array")),
+                };
+                if let Some(name) = names.iter().find(|n| !TYPES.contains(n)) {
+                    return Err(invalid(path, format!("unknown type {name}")));
+                }
+            }
+            "enum" => {
+                if !value.is_array() {
+                    return Err(invalid(path, "enum must be an array"));
+                }
+            }
+            "properties" => {
+                for (name, sub) in value
+                    .as_object()
+                    .ok_or_else(|| invalid(path, "properties must be an object"))?
+                {
+                    check_at(sub, &format!("{path}.{name}"))?;
+                }
+            }
+            "required" => {
+                let all_strings = value
+                    .as_array()
+                    .map(|r| r.iter().all(Value::is_string))
+                    .unwrap_or(false);
+                if !all_strings {
+                    return Err(invalid(path, "required must be an array of strings"));
+                }
+            }
+            "additionalProperties" => {
+                if !value.is_boolean() {
+                    check_at(value, &format!("{path}.*"))?;
+                }
+            }
+            "items" => check_at(value, &format!("{path}[]"))?,
+            "minItems" | "maxItems" | "minLength" | "maxLength" => {
+                if !value.is_u64() {
+                    return Err(invalid(path, format!("{key} must be a positive integer")));
+                }
+            }
+            "minimum" | "maximum" => {
+                if !value.is_number() {
+                    return Err(invalid(path, format!("{key} must be a number")));
+                }
+            }
+            "const" => {}
+            k if ANNOTATIONS.contains(&k) => {}
+            k => return Err(invalid(path, format!("unsupported keyword {k}"))),
+        }
+    }
+    Ok(())
+}
+
+fn type_matches(name: &str, instance: &Value) -> bool {
+    match name {
+        "null" => instance.is_null(),
+        "boolean" => instance.is_boolean(),
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "number" => instance.is_number(),
+        "integer" => match instance {
+            Value::Number(n) => {
+                n.is_i64() || n.is_u64() || n.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            _ => false,
+        },
+        "string" => instance.is_string(),
+        _ => false,
+    }
+}
+
+fn validate_at(schema: &Value, instance: &Value, path: &str) -> Result<(), SchemaError> {
+    let schema = as_object(schema, path)?;
+
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            Value::String(name) => type_matches(name, instance),
+            Value::Array(names) => names
+                .iter()
+                .filter_map(Value::as_str)
+                .any(|name| type_matches(name, instance)),
+            _ => false,
+        };
+        if !matches {
+            return Err(mismatch(path, format!("expected type {expected}")));
+        }
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(instance) {
+            return Err(mismatch(path, "not one of the enum values"));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != instance {
+            return Err(mismatch(path, format!("expected {expected}")));
+        }
+    }
+
+    match instance {
+        Value::Object(fields) => validate_object(schema, fields, path)?,
+        Value::Array(items) => {
+            check_len(schema, "minItems", "maxItems", items.len(), path)?;
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}[{i}]"))?;
+                }
+            }
+        }
+        Value::String(s) => check_len(schema, "minLength", "maxLength", s.chars().count(), path)?,
+        Value::Number(n) => {
+            // f64 is precise enough for bounds checks on oracle style data
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    return Err(mismatch(path, format!("below minimum {min}")));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    return Err(mismatch(path, format!("above maximum {max}")));
+                }
+            }
+        }
+        Value::Null | Value::Bool(_) => {}
+    }
+
+    Ok(())
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    fields: &Map<String, Value>,
+    path: &str,
+) -> Result<(), SchemaError> {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !fields.contains_key(name) {
+                return Err(mismatch(path, format!("missing required field {name}")));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (name, value) in fields {
+        let field_path = format!("{path}.{name}");
+        match properties.and_then(|p| p.get(name)) {
+            Some(sub) => validate_at(sub, value, &field_path)?,
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    return Err(mismatch(&field_path, "additional field not allowed"))
+                }
+                Some(Value::Bool(true)) | None => {}
+                Some(sub) => validate_at(sub, value, &field_path)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+fn check_len(
+    schema: &Map<String, Value>,
+    min_key: &str,
+    max_key: &str,
+    len: usize,
+    path: &str,
+) -> Result<(), SchemaError> {
+    let len = len as u64;
+    if let Some(min) = schema.get(min_key).and_then(Value::as_u64) {
+        if len < min {
+            return Err(mismatch(
+                path,
+                format!("length {len} below {min_key} {min}"),
+            ));
+        }
+    }
+    if let Some(max) = schema.get(max_key).and_then(Value::as_u64) {
+        if len > max {
+            return Err(mismatch(
+                path,
+                format!("length {len} above {max_key} {max}"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn price_schema() -> Value {
+        json!({
+            "title": "price",
+            "type": "object",
+            "properties": {
+                "asset": { "type": "string", "enum": ["btc", "eth"] },
+                "price": { "type": "number", "minimum": 0 },
+                "sources": { "type": "array", "items": { "type": "string" }, "minItems": 1 }
+            },
+            "required": ["asset", "price"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn accepts_matching_values() {
+        let schema = price_schema();
+        check_schema(&schema).unwrap();
+        validate(&schema, &json!({"asset": "btc", "price": 123.4})).unwrap();
+        validate(
+            &schema,
+            &json!({"asset": "eth", "price": 0, "sources": ["coingecko"]}),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_mismatches() {
+        let schema = price_schema();
+        let err = validate(&schema, &json!({"price": 1})).unwrap_err();
+        assert_eq!(err, mismatch("$", "missing required field asset"),);
+
+        let err = validate(&schema, &json!({"asset": "doge", "price": 1})).unwrap_err();
+        assert_eq!(err, mismatch("$.asset", "not one of the enum values"));
+
+        let err = validate(&schema, &json!({"asset": "btc", "price": -1})).unwrap_err();
+        assert_eq!(err, mismatch("$.price", "below minimum 0"));
+
+        let err =
+            validate(&schema, &json!({"asset": "btc", "price": 1, "sources": []})).unwrap_err();
+        assert_eq!(err, mismatch("$.sources", "length 0 below minItems 1"));
+
+        let err = validate(
+            &schema,
+            &json!({"asset": "btc", "price": 1, "sources": [7]}),
+        )
+        .unwrap_err();
+        assert_eq!(err, mismatch("$.sources[0]", "expected type \"string\""));
+
+        let err = validate(&schema, &json!({"asset": "btc", "price": 1, "x": 1})).unwrap_err();
+        assert_eq!(err, mismatch("$.x", "additional field not allowed"));
+    }
+
+    #[test]
+    fn integer_and_union_types() {
+        let schema = json!({ "type": ["integer", "null"] });
+        check_schema(&schema).unwrap();
+        validate(&schema, &json!(17)).unwrap();
+        validate(&schema, &json!(null)).unwrap();
+        validate(&schema, &json!(1.5)).unwrap_err();
+        validate(&schema, &json!("17")).unwrap_err();
+    }
+
+    #[test]
+    fn rejects_unsupported_schemas() {
+        let err = check_schema(&json!({ "oneOf": [] })).unwrap_err();
+        assert_eq!(err, invalid("$", "unsupported keyword oneOf"));
+
+        let err = check_schema(&json!({ "properties": { "x": { "type": "float" } } })).unwrap_err();
+        assert_eq!(err, invalid("$.x", "unknown type float"));
+
+        let err = check_schema(&json!({ "minItems": -1 })).unwrap_err();
+        assert_eq!(err, invalid("$", "minItems must be a positive integer"));
+
+        check_schema(&json!(true)).unwrap_err();
+    }
+}
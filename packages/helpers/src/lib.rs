@@ -1 +1,2 @@
+pub mod json_schema;
 pub mod verifier;
@@ -1,4 +1,7 @@
-use cosmwasm_std::{Addr, DepsMut, Env, Uint128};
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, StdResult, Uint128};
+use serde_json::Value;
+use thiserror::Error;
+
 use lavs_apis::{
     id::TaskId,
     interfaces::{tasks::TasksStorage, voting::VotingPowerResponse},
@@ -7,6 +10,8 @@ use lavs_apis::{
 
 use lavs_apis::interfaces::voting::QueryMsg as OperatorQueryMsg;
 
+use crate::json_schema::{self, SchemaError};
+
 /// Does all checks to ensure the voter is valid and has not voted yet.
 /// Also checks the task is valid and still open.
 /// Returns the metadata for the task (creating it if first voter), along with the voting power of this operator.
@@ -51,3 +56,90 @@ pub fn ensure_valid_vote(
 
     Ok(Some((metadata, power.power)))
 }
+
+/// Whether the operator had voting power when the task was created
+pub fn had_voting_power(
+    deps: Deps,
+    operators_addr: &Addr,
+    metadata: &TaskMetadata,
+    operator: &Addr,
+) -> StdResult<bool> {
+    let power: VotingPowerResponse = deps.querier.query_wasm_smart(
+        operators_addr.to_string(),
+        &OperatorQueryMsg::VotingPowerAtHeight {
+            address: operator.to_string(),
+            height: Some(metadata.created_height),
+        },
+    )?;
+    Ok(!power.power.is_zero())
+}
+
+/// Why an operator's result was rejected before being counted as a vote
+#[derive(Error, Debug, PartialEq)]
+pub enum ResultError {
+    #[error("Result is {size} bytes, above the limit of {max} bytes")]
+    TooLarge { size: usize, max: u32 },
+
+    #[error("Result is not valid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("Result does not match the schema: {0}")]
+    Schema(SchemaError),
+}
+
+/// Checks an operator's result against the optional size limit and schema of the verifier,
+/// returning the parsed JSON. The schema must have passed [`json_schema::check_schema`].
+pub fn check_result(
+    result: &str,
+    max_bytes: Option<u32>,
+    schema: Option<&Value>,
+) -> Result<Value, ResultError> {
+    if let Some(max) = max_bytes {
+        if result.len() > max as usize {
+            return Err(ResultError::TooLarge {
+                size: result.len(),
+                max,
+            });
+        }
+    }
+
+    let value: Value =
+        serde_json::from_str(result).map_err(|err| ResultError::InvalidJson(err.to_string()))?;
+
+    if let Some(schema) = schema {
+        json_schema::validate(schema, &value).map_err(ResultError::Schema)?;
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn check_result_limits() {
+        let result = r#"{"price": "100"}"#;
+        assert_eq!(
+            check_result(result, None, None),
+            Ok(json!({"price": "100"}))
+        );
+        assert_eq!(
+            check_result(result, Some(8), None),
+            Err(ResultError::TooLarge { size: 16, max: 8 })
+        );
+        assert!(matches!(
+            check_result("{\"price\": ", None, None),
+            Err(ResultError::InvalidJson(_))
+        ));
+
+        let schema = json!({"type": "object", "required": ["price"]});
+        json_schema::check_schema(&schema).unwrap();
+        assert!(check_result(result, Some(16), Some(&schema)).is_ok());
+        assert!(matches!(
+            check_result(r#"{"prices": {}}"#, None, Some(&schema)),
+            Err(ResultError::Schema(SchemaError::Mismatch { .. }))
+        ));
+    }
+}
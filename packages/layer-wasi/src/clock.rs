@@ -0,0 +1,9 @@
+//! The time according to the host, which can be fixed when running locally.
+use std::time::Duration;
+
+use crate::bindings::lay3r::avs0_4_0::clock as host;
+
+/// Get the time since the unix epoch.
+pub fn now() -> Duration {
+    Duration::from_nanos(host::now())
+}
@@ -0,0 +1,30 @@
+//! The key-value store the host keeps for the app across runs, with values stored as JSON.
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{bindings::lay3r::avs0_4_0::kv as host, Result};
+
+/// Get the value for `key`, if set.
+pub fn get<T: DeserializeOwned>(key: &str) -> Result<Option<T>> {
+    match host::get(key)? {
+        Some(bytes) => serde_json::from_slice(&bytes)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// Set the value for `key`.
+pub fn set<T: Serialize + ?Sized>(key: &str, value: &T) -> Result<()> {
+    let bytes = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+    host::set(key, &bytes)
+}
+
+/// Delete `key`, if set.
+pub fn delete(key: &str) -> Result<()> {
+    host::delete(key)
+}
+
+/// List the keys starting with `prefix`, in order.
+pub fn list_prefix(prefix: &str) -> Result<Vec<String>> {
+    host::list_prefix(prefix)
+}
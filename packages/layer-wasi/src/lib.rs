@@ -1,7 +1,23 @@
 #![allow(async_fn_in_trait)]
 
+pub mod clock;
+pub mod kv;
+
+/// `wit/deps` also holds `lay3r:avs@0.3.0`, so the modules are named after the version, e.g. `lay3r::avs0_4_0`
+mod bindings {
+    wit_bindgen::generate!({
+        path: "../../wit",
+        world: "lay3r:avs/host@0.4.0",
+    });
+}
+
 use serde::{de::DeserializeOwned, Serialize};
-use std::cmp::min;
+use std::{
+    cmp::min,
+    future::Future,
+    task::{Context, Poll},
+    time::Duration,
+};
 pub use url::Url;
 pub use wasi::http::types::Method;
 pub use wstd::runtime::{block_on, Reactor};
@@ -13,12 +29,46 @@ pub type Error = String;
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// An HTTP request.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Request {
     pub method: Method,
     pub url: Url,
     pub headers: Vec<(String, String)>,
     pub body: Vec<u8>,
+    /// Timeout for connecting, for the first byte of the response, and between bytes of the response.
+    pub timeout: Option<Duration>,
+    /// Retry policy for server errors and timeouts, not retried if not set.
+    pub retry: Option<Retry>,
+}
+
+/// Retry with exponential backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Retry {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Wait before the first retry, doubled for every retry after that.
+    pub initial_backoff: Duration,
+    /// Longest wait between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Retry {
+    /// Wait before the retry following `attempt`, counted from 0.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
 }
 
 impl Request {
@@ -29,6 +79,8 @@ impl Request {
             url: Url::parse(url).map_err(|e| e.to_string())?,
             headers: vec![],
             body: vec![],
+            timeout: None,
+            retry: None,
         })
     }
 
@@ -60,17 +112,57 @@ impl Request {
     /// Set JSON body.
     pub fn json<T: Serialize + ?Sized>(&mut self, json: &T) -> Result<&mut Self> {
         self.body = serde_json::to_vec(json).map_err(|e| e.to_string())?;
+        Ok(self.default_header("content-type", "application/json"))
+    }
 
+    /// Set URL-encoded form body.
+    pub fn form(&mut self, pairs: &[(&str, &str)]) -> &mut Self {
+        self.body = url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish()
+            .into_bytes();
+        self.default_header("content-type", "application/x-www-form-urlencoded")
+    }
+
+    /// Append URL-encoded query parameters.
+    pub fn query(&mut self, pairs: &[(&str, &str)]) -> &mut Self {
+        self.url.query_pairs_mut().extend_pairs(pairs);
+        self
+    }
+
+    /// Add a header, keeping the ones already set.
+    pub fn header(&mut self, key: &str, value: &str) -> &mut Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the `authorization` header to a bearer token.
+    pub fn bearer_auth(&mut self, token: &str) -> &mut Self {
+        self.header("authorization", &format!("Bearer {token}"))
+    }
+
+    /// Set the timeout.
+    pub fn timeout(&mut self, timeout: Duration) -> &mut Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the retry policy.
+    pub fn retry(&mut self, retry: Retry) -> &mut Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Add a header, unless it is already set.
+    fn default_header(&mut self, key: &str, value: &str) -> &mut Self {
         if !self
             .headers
             .iter()
-            .any(|(k, _)| &k.to_lowercase() == "content-type")
+            .any(|(k, _)| k.eq_ignore_ascii_case(key))
         {
-            self.headers
-                .push(("content-type".to_string(), "application/json".to_string()));
+            self.header(key, value);
         }
-
-        Ok(self)
+        self
     }
 }
 
@@ -87,7 +179,50 @@ impl Response {
     pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
         serde_json::from_slice(&self.body).map_err(|e| e.to_string())
     }
+
+    /// Get UTF-8 body.
+    pub fn text(&self) -> Result<String> {
+        String::from_utf8(self.body.clone()).map_err(|e| e.to_string())
+    }
+
+    /// Get raw body.
+    pub fn bytes(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Get the first value of a header, case-insensitively.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Status is 2xx.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Status is 4xx.
+    pub fn is_client_error(&self) -> bool {
+        (400..500).contains(&self.status)
+    }
+
+    /// Status is 5xx.
+    pub fn is_server_error(&self) -> bool {
+        (500..600).contains(&self.status)
+    }
+
+    /// Turn a 4xx or 5xx status into an error.
+    pub fn error_for_status(self) -> Result<Self> {
+        if self.is_client_error() || self.is_server_error() {
+            Err(format!("unexpected status code: {}", self.status))
+        } else {
+            Ok(self)
+        }
+    }
 }
+
 /// Trait adding WASI methods to the `wstd::runtime::Reactor`.
 pub trait WasiPollable {
     async fn read_all(
@@ -97,6 +232,8 @@ pub trait WasiPollable {
     ) -> Result<Vec<u8>>;
     async fn write_all(&self, stream: wasi::io::streams::OutputStream, bytes: &[u8]) -> Result<()>;
     async fn send(&self, req: Request) -> Result<Response>;
+    async fn send_all(&self, reqs: Vec<Request>) -> Vec<Result<Response>>;
+    async fn sleep(&self, duration: Duration);
 }
 
 impl WasiPollable for wstd::runtime::Reactor {
@@ -165,94 +302,322 @@ impl WasiPollable for wstd::runtime::Reactor {
         Ok(())
     }
 
-    /// Send the HTTP request.
+    /// Send the HTTP request, retrying on server errors and timeouts if the request has a retry policy.
     async fn send(&self, req: Request) -> Result<Response> {
-        let wasi_headers = wasi::http::types::Fields::from_list(
-            &req.headers
-                .into_iter()
-                .map(|(k, v)| (k, v.into_bytes()))
-                .collect::<Vec<(String, Vec<u8>)>>(),
-        )
-        .or(Err("invalid header".to_string()))?;
-
-        let wasi_req = wasi::http::types::OutgoingRequest::new(wasi_headers);
-
-        // set the HTTP method
-        wasi_req
-            .set_method(&req.method)
-            .or(Err("invalid method".to_string()))?;
-
-        // Set the url scheme
-        use wasi::http::types::Scheme;
-        let scheme = match req.url.scheme() {
-            "http" => Scheme::Http,
-            "https" => Scheme::Https,
-            other => Scheme::Other(other.to_owned()),
-        };
-        wasi_req
-            .set_scheme(Some(&scheme))
-            .or(Err("invalid url scheme".to_string()))?;
-
-        // Set the url path + query string
-        let path = match req.url.query() {
-            Some(query) => format!("{}?{query}", req.url.path()),
-            None => req.url.path().to_owned(),
-        };
-        wasi_req
-            .set_path_with_query(Some(&path))
-            .or(Err("invalid url path".to_string()))?;
-        wasi_req
-            .set_authority(Some(req.url.authority()))
-            .or(Err("invalid url authority".to_string()))?;
-
-        let wasi_body = wasi_req.body().unwrap();
-        let body_stream = wasi_body.write().unwrap();
-
-        // start sending the request
-        let res = wasi::http::outgoing_handler::handle(wasi_req, None)
-            .or(Err("failed to send request".to_string()))?;
-
-        // send the request body
-        self.write_all(body_stream, &req.body).await?;
-
-        // finish sending the request body with no trailers
-        wasi::http::types::OutgoingBody::finish(wasi_body, None).unwrap();
-
-        // wait for the response
-        self.wait_for(res.subscribe()).await;
-
-        let res = res
-            .get()
-            .unwrap()
-            .unwrap()
-            .map_err(|err| format!("response error: {err}"))?;
-
-        let res_status = res.status();
-        let mut content_length = None;
-        let res_headers = res
-            .headers()
-            .entries()
+        let mut attempt = 0;
+        loop {
+            let result = send_once(self, req.clone()).await;
+            let retryable = match &result {
+                Ok(res) => res.is_server_error(),
+                Err(err) => err.timed_out,
+            };
+
+            match req.retry {
+                Some(retry) if retryable && attempt < retry.max_retries => {
+                    self.sleep(retry.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                _ => return result.map_err(|err| err.message),
+            }
+        }
+    }
+
+    /// Send the HTTP requests concurrently, returning the responses in order.
+    async fn send_all(&self, reqs: Vec<Request>) -> Vec<Result<Response>> {
+        join_all(reqs.into_iter().map(|req| self.send(req))).await
+    }
+
+    /// Wait for the duration, letting other futures on the reactor progress.
+    async fn sleep(&self, duration: Duration) {
+        let nanos = duration.as_nanos().try_into().unwrap_or(u64::MAX);
+        self.wait_for(wasi::clocks::monotonic_clock::subscribe_duration(nanos))
+            .await;
+    }
+}
+
+/// A failed attempt at sending a request.
+struct SendError {
+    message: Error,
+    timed_out: bool,
+}
+
+impl From<Error> for SendError {
+    fn from(message: Error) -> Self {
+        Self {
+            message,
+            timed_out: false,
+        }
+    }
+}
+
+/// Send the HTTP request once.
+async fn send_once(reactor: &Reactor, req: Request) -> std::result::Result<Response, SendError> {
+    let wasi_headers = wasi::http::types::Fields::from_list(
+        &req.headers
             .into_iter()
-            .map(|(k, v)| {
-                if k.to_lowercase() == "content-length" {
-                    content_length = std::str::from_utf8(&v)
-                        .ok()
-                        .and_then(|s| s.parse::<usize>().ok());
+            .map(|(k, v)| (k, v.into_bytes()))
+            .collect::<Vec<(String, Vec<u8>)>>(),
+    )
+    .or(Err("invalid header".to_string()))?;
+
+    let wasi_req = wasi::http::types::OutgoingRequest::new(wasi_headers);
+
+    // set the HTTP method
+    wasi_req
+        .set_method(&req.method)
+        .or(Err("invalid method".to_string()))?;
+
+    // Set the url scheme
+    use wasi::http::types::Scheme;
+    let scheme = match req.url.scheme() {
+        "http" => Scheme::Http,
+        "https" => Scheme::Https,
+        other => Scheme::Other(other.to_owned()),
+    };
+    wasi_req
+        .set_scheme(Some(&scheme))
+        .or(Err("invalid url scheme".to_string()))?;
+
+    // Set the url path + query string
+    let path = match req.url.query() {
+        Some(query) => format!("{}?{query}", req.url.path()),
+        None => req.url.path().to_owned(),
+    };
+    wasi_req
+        .set_path_with_query(Some(&path))
+        .or(Err("invalid url path".to_string()))?;
+    wasi_req
+        .set_authority(Some(req.url.authority()))
+        .or(Err("invalid url authority".to_string()))?;
+
+    let wasi_body = wasi_req.body().unwrap();
+    let body_stream = wasi_body.write().unwrap();
+
+    // set the timeouts
+    let options = match req.timeout {
+        Some(timeout) => {
+            let nanos = timeout.as_nanos().try_into().unwrap_or(u64::MAX);
+            let options = wasi::http::types::RequestOptions::new();
+            options
+                .set_connect_timeout(Some(nanos))
+                .and(options.set_first_byte_timeout(Some(nanos)))
+                .and(options.set_between_bytes_timeout(Some(nanos)))
+                .or(Err("timeouts are not supported".to_string()))?;
+            Some(options)
+        }
+        None => None,
+    };
+
+    // start sending the request
+    let res = wasi::http::outgoing_handler::handle(wasi_req, options)
+        .or(Err("failed to send request".to_string()))?;
+
+    // send the request body
+    reactor.write_all(body_stream, &req.body).await?;
+
+    // finish sending the request body with no trailers
+    wasi::http::types::OutgoingBody::finish(wasi_body, None).unwrap();
+
+    // wait for the response
+    reactor.wait_for(res.subscribe()).await;
+
+    let res = res.get().unwrap().unwrap().map_err(|err| {
+        use wasi::http::types::ErrorCode;
+        SendError {
+            timed_out: matches!(
+                err,
+                ErrorCode::ConnectionTimeout
+                    | ErrorCode::ConnectionReadTimeout
+                    | ErrorCode::ConnectionWriteTimeout
+                    | ErrorCode::HttpResponseTimeout
+            ),
+            message: format!("response error: {err}"),
+        }
+    })?;
+
+    let res_status = res.status();
+    let mut content_length = None;
+    let res_headers = res
+        .headers()
+        .entries()
+        .into_iter()
+        .map(|(k, v)| {
+            if k.to_lowercase() == "content-length" {
+                content_length = std::str::from_utf8(&v)
+                    .ok()
+                    .and_then(|s| s.parse::<usize>().ok());
+            }
+            let v = std::string::String::from_utf8(v)
+                .or(Err(format!("invalid response header value for `{k}`")))?;
+            Ok((k, v))
+        })
+        .collect::<Result<Vec<(String, String)>>>()?;
+
+    // read response body
+    let res_body = res.consume().unwrap();
+    let res_body_stream = res_body.stream().unwrap();
+
+    Ok(Response {
+        status: res_status,
+        headers: res_headers,
+        body: reactor.read_all(res_body_stream, content_length).await?,
+    })
+}
+
+/// Poll the futures concurrently, returning their outputs in order.
+pub async fn join_all<F: Future>(futures: impl IntoIterator<Item = F>) -> Vec<F::Output> {
+    let mut futures: Vec<_> = futures.into_iter().map(|f| Some(Box::pin(f))).collect();
+    let mut outputs: Vec<Option<F::Output>> = futures.iter().map(|_| None).collect();
+
+    std::future::poll_fn(|cx: &mut Context<'_>| {
+        let mut pending = false;
+        for (future, output) in futures.iter_mut().zip(outputs.iter_mut()) {
+            if let Some(f) = future {
+                match f.as_mut().poll(cx) {
+                    Poll::Ready(value) => {
+                        *output = Some(value);
+                        *future = None;
+                    }
+                    Poll::Pending => pending = true,
                 }
-                let v = std::string::String::from_utf8(v)
-                    .or(Err(format!("invalid response header value for `{k}`")))?;
-                Ok((k, v))
-            })
-            .collect::<Result<Vec<(String, String)>>>()?;
-
-        // read response body
-        let res_body = res.consume().unwrap();
-        let res_body_stream = res_body.stream().unwrap();
-
-        Ok(Response {
-            status: res_status,
-            headers: res_headers,
-            body: self.read_all(res_body_stream, content_length).await?,
+            }
+        }
+
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(())
+        }
+    })
+    .await;
+
+    outputs.into_iter().flatten().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        pin::pin,
+        sync::Arc,
+        task::{Wake, Waker},
+    };
+
+    /// Poll the future to completion on the current thread.
+    fn poll_to_end<F: Future>(future: F) -> F::Output {
+        struct NoopWake;
+        impl Wake for NoopWake {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    /// Ready with `value` after being polled `polls` times.
+    async fn ready_after(polls: u32, value: u32) -> u32 {
+        let mut remaining = polls;
+        std::future::poll_fn(|_| {
+            if remaining == 0 {
+                Poll::Ready(())
+            } else {
+                remaining -= 1;
+                Poll::Pending
+            }
         })
+        .await;
+        value
+    }
+
+    #[test]
+    fn test_backoff() {
+        let retry = Retry::default();
+        assert_eq!(retry.backoff(0), Duration::from_millis(200));
+        assert_eq!(retry.backoff(1), Duration::from_millis(400));
+        assert_eq!(retry.backoff(4), Duration::from_millis(3200));
+        // capped at max_backoff
+        assert_eq!(retry.backoff(5), Duration::from_secs(5));
+        // no overflow on huge attempt counts
+        assert_eq!(retry.backoff(u32::MAX), Duration::from_secs(5));
+
+        let retry = Retry {
+            initial_backoff: Duration::MAX,
+            max_backoff: Duration::MAX,
+            ..Retry::default()
+        };
+        assert_eq!(retry.backoff(3), Duration::MAX);
+    }
+
+    #[test]
+    fn test_query() {
+        let mut req = Request::get("https://example.com/prices?base=usd").unwrap();
+        req.query(&[("symbol", "BTC&ETH"), ("note", "a b")]);
+        assert_eq!(
+            req.url.as_str(),
+            "https://example.com/prices?base=usd&symbol=BTC%26ETH&note=a+b"
+        );
+        assert!(req.headers.is_empty());
+    }
+
+    #[test]
+    fn test_form() {
+        let mut req = Request::post("https://example.com/token").unwrap();
+        req.form(&[
+            ("grant_type", "client_credentials"),
+            ("scope", "read write"),
+        ]);
+        assert_eq!(
+            req.body,
+            b"grant_type=client_credentials&scope=read+write".to_vec()
+        );
+        assert_eq!(
+            req.headers,
+            vec![(
+                "content-type".to_string(),
+                "application/x-www-form-urlencoded".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_default_header() {
+        let mut req = Request::post("https://example.com").unwrap();
+        req.header("Content-Type", "text/plain")
+            .json(&serde_json::json!({"x": 1}))
+            .unwrap();
+        // an existing header is kept, whatever its case
+        assert_eq!(
+            req.headers,
+            vec![("Content-Type".to_string(), "text/plain".to_string())]
+        );
+
+        let mut req = Request::post("https://example.com").unwrap();
+        req.json(&serde_json::json!({"x": 1})).unwrap();
+        req.form(&[("x", "1")]);
+        // set only once
+        assert_eq!(
+            req.headers,
+            vec![("content-type".to_string(), "application/json".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_join_all_order() {
+        let outputs = poll_to_end(join_all([
+            ready_after(3, 1),
+            ready_after(0, 2),
+            ready_after(5, 3),
+            ready_after(1, 4),
+        ]));
+        assert_eq!(outputs, vec![1, 2, 3, 4]);
+
+        let empty: Vec<u32> = poll_to_end(join_all(Vec::<std::future::Ready<u32>>::new()));
+        assert!(empty.is_empty());
     }
 }
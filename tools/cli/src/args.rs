@@ -141,6 +141,12 @@ pub enum DeployCommand {
         /// "fixed(slayaddresshere)" - will require the caller be this specific address
         ///
         /// "deployer" - will require the caller be the same as the deployer
+        ///
+        /// "payment_any_of(100 uslay, 250 ibc/ABC)" - will require a payment in any of these coins
+        ///
+        /// "allowlist(slayaddr1, slayaddr2)" - will require the caller be one of these addresses
+        ///
+        /// "allowlist_payment(slayaddr1, slayaddr2; 100 uslay)" - both of the above
         #[clap(short, long, default_value_t = DeployContractArgsRequestor::default())]
         requestor: DeployContractArgsRequestor,
     },
@@ -243,6 +249,33 @@ pub enum TaskQueueCommand {
         #[clap(short, long)]
         limit: Option<u32>,
     },
+
+    /// Expires open tasks past their timeout in a loop, refunding their deposits
+    /// and collecting the sweep bounty (if configured)
+    Sweep {
+        /// Maximum number of tasks to expire per transaction
+        #[clap(short, long)]
+        limit: Option<u32>,
+        /// Seconds to wait between sweeps once no expired tasks are left
+        #[clap(short, long, default_value_t = 30)]
+        interval: u64,
+    },
+
+    /// Updates the allowlist of task requestors (for the allowlist requestor modes)
+    UpdateRequestorAllowlist {
+        #[clap(long, value_delimiter = ',')]
+        to_add: Option<Vec<String>>,
+        #[clap(long, value_delimiter = ',')]
+        to_remove: Option<Vec<String>>,
+    },
+
+    /// View the allowlist of task requestors
+    ViewRequestorAllowlist {
+        #[clap(short, long)]
+        start_after: Option<String>,
+        #[clap(short, long)]
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Debug, Clone, Copy, ValueEnum)]
@@ -253,6 +286,8 @@ pub enum CliHookType {
     Timeout,
     /// Hook triggered when a task is created
     Created,
+    /// Hook triggered when a task is cancelled
+    Cancelled,
 }
 
 impl fmt::Display for CliHookType {
@@ -261,6 +296,7 @@ impl fmt::Display for CliHookType {
             CliHookType::Completed => write!(f, "Completed"),
             CliHookType::Timeout => write!(f, "Timeout"),
             CliHookType::Created => write!(f, "Created"),
+            CliHookType::Cancelled => write!(f, "Cancelled"),
         }
     }
 }
@@ -271,6 +307,7 @@ impl From<CliHookType> for TaskHookType {
             CliHookType::Completed => TaskHookType::Completed,
             CliHookType::Timeout => TaskHookType::Timeout,
             CliHookType::Created => TaskHookType::Created,
+            CliHookType::Cancelled => TaskHookType::Cancelled,
         }
     }
 }
@@ -382,6 +419,93 @@ pub enum WasmaticCommand {
         /// Optional input for the test
         #[clap(short, long)]
         input: Option<String>,
+
+        /// Permissions to run with, in the same format as for deploy
+        #[clap(short, long, default_value = "{}")]
+        permissions: String,
+
+        /// Fuel limit, roughly one unit per wasm instruction, unlimited by default
+        #[clap(long)]
+        fuel: Option<u64>,
+
+        /// Maximum linear memory in MiB, defaults to 1024
+        #[clap(long)]
+        max_memory_mb: Option<usize>,
+
+        /// Maximum number of elements per table, defaults to 1000000
+        #[clap(long)]
+        max_table_elements: Option<u32>,
+
+        /// Timeout in seconds, defaults to 60
+        #[clap(long)]
+        timeout: Option<u64>,
+
+        /// Run an existing task, fetched from the task queue. Its payload is used unless an input is given
+        #[clap(long)]
+        task_id: Option<u64>,
+
+        /// Task queue address given to the component. With a task id, it defaults to
+        /// LOCAL_TASK_QUEUE_ADDRESS or TEST_TASK_QUEUE_ADDRESS depending on the target environment
+        #[clap(long)]
+        task_queue: Option<String>,
+
+        /// Task description given to the component, without a task id
+        #[clap(long, default_value = "")]
+        description: String,
+
+        /// Task creation height given to the component, without a task id
+        #[clap(long, default_value = "0")]
+        created_height: u64,
+
+        /// Task expiration given to the component in nanoseconds since the unix epoch, without a task id
+        #[clap(long, default_value = "0")]
+        expires_time: u64,
+
+        /// JSON file backing the key-value store, kept between runs. Otherwise the store starts empty
+        #[clap(long)]
+        kv: Option<PathBuf>,
+
+        /// Fixed time given to the component in nanoseconds since the unix epoch, otherwise the system time
+        #[clap(long)]
+        now: Option<u64>,
+
+        /// Record the outgoing HTTP requests and their responses to this fixture file
+        #[clap(long, conflicts_with = "replay")]
+        record: Option<PathBuf>,
+
+        /// Answer the outgoing HTTP requests from this fixture file, without network access
+        #[clap(long)]
+        replay: Option<PathBuf>,
+
+        /// Keep running the cron component on its schedule, logging each output
+        #[clap(long, requires_all = ["cron_trigger", "schedule"])]
+        watch: bool,
+
+        /// Cron schedule to watch with, like `0 */5 * * * *`
+        #[clap(long)]
+        schedule: Option<String>,
+
+        /// Time compression factor when watching, 60 runs an every-minute schedule every second
+        #[clap(long, default_value = "1")]
+        speedup: f64,
+    },
+
+    /// Create a new component crate, which can be built with cargo-component
+    New {
+        /// Name of the crate, and of the directory it is created in, in kebab-case (eg. `my-oracle`)
+        name: String,
+
+        /// World the component targets
+        #[clap(long, value_enum, default_value_t = ComponentWorld::TaskQueue)]
+        world: ComponentWorld,
+
+        /// What the component does to start with
+        #[clap(long, value_enum, default_value_t = ComponentTemplate::Square)]
+        template: ComponentTemplate,
+
+        /// Directory to create the crate in
+        #[clap(long, default_value = ".")]
+        dir: PathBuf,
     },
 
     /// Test a Wasm application
@@ -405,6 +529,24 @@ pub enum WasmaticCommand {
     },
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ComponentWorld {
+    /// Runs the tasks of a task queue, with their payload as input
+    TaskQueue,
+    /// Runs on a cron schedule, without input
+    CronJob,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ComponentTemplate {
+    /// Squares `x` from the input, only for task queue components
+    Square,
+    /// Fetches JSON from a URL
+    HttpJson,
+    /// Gets the BTCUSD price from CoinGecko
+    Oracle,
+}
+
 #[derive(Copy, Clone, Debug, clap::ValueEnum)]
 pub enum LogLevel {
     Trace,
@@ -1,2 +1,3 @@
+pub mod scaffold;
 pub mod upload;
 pub mod wasmatic;
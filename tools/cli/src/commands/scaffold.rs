@@ -0,0 +1,301 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+use crate::args::{ComponentTemplate, ComponentWorld};
+
+/// The project WIT package and its dependencies, so the component builds without this repo
+const WIT_FILES: &[(&str, &str)] = &[
+    (
+        "lay3r_avs@0.4.0.wit",
+        include_str!("../../../../wit/lay3r_avs@0.4.0.wit"),
+    ),
+    (
+        "deps/wasi-cli-0.2.0/package.wit",
+        include_str!("../../../../wit/deps/wasi-cli-0.2.0/package.wit"),
+    ),
+    (
+        "deps/wasi-clocks-0.2.0/package.wit",
+        include_str!("../../../../wit/deps/wasi-clocks-0.2.0/package.wit"),
+    ),
+    (
+        "deps/wasi-http-0.2.0/package.wit",
+        include_str!("../../../../wit/deps/wasi-http-0.2.0/package.wit"),
+    ),
+    (
+        "deps/wasi-io-0.2.0/package.wit",
+        include_str!("../../../../wit/deps/wasi-io-0.2.0/package.wit"),
+    ),
+    (
+        "deps/wasi-random-0.2.0/package.wit",
+        include_str!("../../../../wit/deps/wasi-random-0.2.0/package.wit"),
+    ),
+];
+
+const CARGO_TOML: &str = include_str!("../../templates/component/Cargo.toml.template");
+const README: &str = include_str!("../../templates/component/README.md");
+const TEST: &str = include_str!("../../templates/component/tests/run.rs");
+const TASK_QUEUE: &str = include_str!("../../templates/component/task.rs");
+const CRON_JOB: &str = include_str!("../../templates/component/cron.rs");
+const SQUARE: &str = include_str!("../../templates/component/square.rs");
+const HTTP_JSON: &str = include_str!("../../templates/component/http_json.rs");
+const ORACLE: &str = include_str!("../../templates/component/oracle.rs");
+
+/// Used by the http-json template, since it doesn't need an API key
+const PING_URL: &str = "https://api.coingecko.com/api/v3/ping";
+const EXCHANGE_RATES_URL: &str = "https://api.coingecko.com/api/v3/exchange_rates";
+
+/// Creates a cargo-component crate for the world, in a new `name` directory under `dir`
+pub async fn new_component(
+    name: &str,
+    world: ComponentWorld,
+    template: ComponentTemplate,
+    dir: &Path,
+) -> Result<PathBuf> {
+    validate_name(name)?;
+    if let (ComponentTemplate::Square, ComponentWorld::CronJob) = (template, world) {
+        bail!("The square template needs an input, use it with `--world task-queue`");
+    }
+
+    let path = dir.join(name);
+    if path.exists() {
+        bail!("{} already exists", path.display());
+    }
+
+    let files = Files::new(name, world, template);
+    for (file, contents) in files.all()? {
+        let file_path = path.join(file);
+        if let Some(parent) = file_path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&file_path, contents)
+            .await
+            .context(format!("Failed to write {}", file_path.display()))?;
+    }
+
+    Ok(path)
+}
+
+/// The name is also the WIT package name, so it must be kebab-case:
+/// words of lowercase letters and digits, each starting with a letter, joined by `-`
+fn validate_name(name: &str) -> Result<()> {
+    let valid = name.split('-').all(|word| {
+        word.starts_with(|c: char| c.is_ascii_lowercase())
+            && word
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+    });
+    if !valid {
+        bail!(
+            "Invalid component name: `{name}`, expected kebab-case words of lowercase letters and digits, \
+             each starting with a letter (eg. `my-oracle2`)"
+        );
+    }
+    Ok(())
+}
+
+/// The generated files, filled in for the world and the template
+struct Files {
+    name: String,
+    wasm_name: String,
+    world: ComponentWorld,
+    template: ComponentTemplate,
+}
+
+impl Files {
+    fn new(name: &str, world: ComponentWorld, template: ComponentTemplate) -> Self {
+        Self {
+            name: name.to_string(),
+            wasm_name: name.replace('-', "_"),
+            world,
+            template,
+        }
+    }
+
+    fn all(&self) -> Result<Vec<(String, String)>> {
+        let mut files = vec![
+            ("Cargo.toml".to_string(), self.fill(CARGO_TOML)),
+            ("README.md".to_string(), self.fill(README)),
+            ("src/lib.rs".to_string(), self.lib()),
+            ("tests/run.rs".to_string(), self.fill(TEST)),
+            (
+                "tests/fixtures.json".to_string(),
+                to_json(&self.fixtures())?,
+            ),
+            (
+                "tests/expected.json".to_string(),
+                to_json(&self.expected())?,
+            ),
+            (".gitignore".to_string(), "/target\n".to_string()),
+        ];
+        if let Some(input) = self.input() {
+            files.push(("input.json".to_string(), to_json(&input)?));
+        }
+        for (file, contents) in WIT_FILES {
+            files.push((format!("wit/{file}"), contents.to_string()));
+        }
+
+        Ok(files)
+    }
+
+    fn lib(&self) -> String {
+        let world = match self.world {
+            ComponentWorld::TaskQueue => TASK_QUEUE,
+            ComponentWorld::CronJob => CRON_JOB,
+        };
+        let template = match self.template {
+            ComponentTemplate::Square => SQUARE,
+            ComponentTemplate::HttpJson => HTTP_JSON,
+            ComponentTemplate::Oracle => ORACLE,
+        };
+
+        format!("{world}{template}")
+    }
+
+    fn fill(&self, template: &str) -> String {
+        let (world, trigger, imports, run_args, deploy_args) = match self.world {
+            ComponentWorld::TaskQueue => (
+                "task-queue",
+                r#"TriggerRequest::Queue(TaskInput::new(read("input.json")))"#,
+                "HttpFixtures, HttpMode, KvStore, Limits, Runner, TaskInput, TriggerRequest",
+                "--input \"$(cat input.json)\"",
+                "--task <TASK-ADDRESS>",
+            ),
+            ComponentWorld::CronJob => (
+                "cron-job",
+                "TriggerRequest::Cron",
+                "HttpFixtures, HttpMode, KvStore, Limits, Runner, TriggerRequest",
+                "--cron",
+                "--cron \"0 * * * * *\"",
+            ),
+        };
+        let envs = self.envs();
+        let env_args: String = envs
+            .iter()
+            .map(|(key, value)| format!(" --envs {key}={value}"))
+            .collect();
+        let envs = format!(
+            "vec![{}]",
+            envs.iter()
+                .map(|(key, value)| format!("({key:?}.to_string(), {value:?}.to_string())"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        template
+            .replace("{{name}}", &self.name)
+            .replace("{{wasm_name}}", &self.wasm_name)
+            .replace("{{world}}", world)
+            .replace("{{runner_imports}}", imports)
+            .replace("{{envs}}", &envs)
+            .replace("{{trigger}}", trigger)
+            .replace("{{run_args}}", &format!("{run_args}{env_args}"))
+            .replace("{{deploy_args}}", &format!("{deploy_args}{env_args}"))
+    }
+
+    /// Given to the component in the test, and in the README
+    fn envs(&self) -> Vec<(&'static str, &'static str)> {
+        match (self.template, self.world) {
+            (ComponentTemplate::Oracle, _) => vec![("API_KEY", "<YOUR-API-KEY>")],
+            (ComponentTemplate::HttpJson, ComponentWorld::CronJob) => vec![("URL", PING_URL)],
+            _ => vec![],
+        }
+    }
+
+    fn input(&self) -> Option<Value> {
+        match (self.template, self.world) {
+            (_, ComponentWorld::CronJob) => None,
+            (ComponentTemplate::Square, _) => Some(json!({ "x": 9 })),
+            (ComponentTemplate::HttpJson, _) => Some(json!({ "url": PING_URL })),
+            (ComponentTemplate::Oracle, _) => Some(json!({})),
+        }
+    }
+
+    /// Responses to the template's requests, in the format of `wasmatic run --record`
+    fn fixtures(&self) -> Value {
+        let (url, body) = match self.template {
+            ComponentTemplate::Square => return json!([]),
+            ComponentTemplate::HttpJson => (PING_URL, json!({ "gecko_says": "(V3) To the Moon!" })),
+            ComponentTemplate::Oracle => (
+                EXCHANGE_RATES_URL,
+                json!({
+                    "rates": {
+                        "usd": { "name": "US Dollar", "unit": "$", "value": 67187.34, "type": "fiat" }
+                    }
+                }),
+            ),
+        };
+
+        json!([{
+            "method": "GET",
+            "url": url,
+            "body": "",
+            "response": {
+                "status": 200,
+                "headers": [["content-type", "application/json"]],
+                "body": body.to_string(),
+            },
+        }])
+    }
+
+    fn expected(&self) -> Value {
+        match self.template {
+            ComponentTemplate::Square => json!({ "y": 81 }),
+            ComponentTemplate::HttpJson => json!({ "gecko_says": "(V3) To the Moon!" }),
+            ComponentTemplate::Oracle => json!({ "price": "67187.34" }),
+        }
+    }
+}
+
+fn to_json(value: &Value) -> Result<String> {
+    Ok(format!("{}\n", serde_json::to_string_pretty(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_cron() {
+        let files = Files::new(
+            "my-cron",
+            ComponentWorld::CronJob,
+            ComponentTemplate::HttpJson,
+        );
+        let test = files.fill(TEST);
+
+        assert!(test.contains("/target/wasm32-wasip1/debug/my_cron.wasm"));
+        assert!(test.contains("let trigger = TriggerRequest::Cron;"));
+        assert!(test.contains(
+            r#"let envs: Vec<(String, String)> = vec![("URL".to_string(), "https://api.coingecko.com/api/v3/ping".to_string())];"#
+        ));
+        assert!(!test.contains("TaskInput"));
+        assert!(!test.contains("{{"));
+        assert!(files.fill(CARGO_TOML).contains(r#"world = "cron-job""#));
+        assert!(files.input().is_none());
+    }
+
+    #[test]
+    fn test_validate_name() {
+        for name in ["square", "my-cron", "oracle2", "btc-usd2-feed"] {
+            assert!(validate_name(name).is_ok(), "{name}");
+        }
+        for name in [
+            "", "2fast", "my_cron", "my-2cron", "my--cron", "-cron", "cron-", "My-cron",
+        ] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_lib_exports_world() {
+        let lib = Files::new(
+            "square",
+            ComponentWorld::TaskQueue,
+            ComponentTemplate::Square,
+        )
+        .lib();
+        assert!(lib.contains("fn run_task(input: TaskQueueInput) -> Output"));
+        assert!(lib.contains("fn run(request: Option<&[u8]>) -> Output"));
+    }
+}
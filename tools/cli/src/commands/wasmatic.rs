@@ -1,18 +1,13 @@
-mod cron_bindings;
-mod task_bindings;
 use anyhow::{bail, Context, Result};
-use avs_toolkit_shared::file::WasmFile;
-use std::{
-    path::PathBuf,
-    time::{SystemTime, UNIX_EPOCH},
+use avs_toolkit_shared::{
+    file::WasmFile,
+    permissions::Permissions,
+    runner::{parse_envs, Clock, KvStore, RunOutput, Runner, TriggerRequest},
+    schedule::CronSchedule,
 };
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
 use tokio::fs;
-use wasmtime::{
-    component::{Component, Linker},
-    Config, Engine,
-};
-use wasmtime_wasi::{DirPerms, FilePerms, WasiCtx, WasiCtxBuilder, WasiView};
-use wasmtime_wasi_http::{WasiHttpCtx, WasiHttpView};
 
 pub async fn wasm_arg_to_file(wasm_arg: String) -> Result<WasmFile> {
     // Check if wasm_source is a URL or a local file path
@@ -26,15 +21,31 @@ pub async fn wasm_arg_to_file(wasm_arg: String) -> Result<WasmFile> {
     }
 }
 
-pub async fn run(
-    wasm_file: WasmFile,
-    cron_trigger: bool,
-    env_pairs: Vec<String>,
-    app_cache_path: PathBuf,
-    input: Option<String>,
-) -> Result<String> {
+/// What a component is run with locally
+pub struct LocalApp {
+    /// Environment variables in KEY=VALUE format
+    pub env_pairs: Vec<String>,
+    pub app_cache_path: PathBuf,
+    pub permissions: Permissions,
+    pub kv: KvStore,
+}
+
+impl LocalApp {
+    async fn prepare(&self, wasm_file: WasmFile) -> Result<(Vec<u8>, Vec<(String, String)>)> {
+        let wasm_binary = download(wasm_file).await?;
+        let envs = parse_envs(&self.env_pairs)?;
+
+        if !self.app_cache_path.is_dir() {
+            tokio::fs::create_dir(&self.app_cache_path).await?;
+        }
+
+        Ok((wasm_binary, envs))
+    }
+}
+
+async fn download(wasm_file: WasmFile) -> Result<Vec<u8>> {
     // Check if wasm_source is a URL or a local file path
-    let wasm_binary = match wasm_file {
+    Ok(match wasm_file {
         WasmFile::Url(url) => match reqwest::get(url).await {
             Ok(res) if res.status().is_success() => match res.bytes().await {
                 Ok(bytes) => bytes.to_vec(),
@@ -47,153 +58,83 @@ pub async fn run(
             Err(err) => Err(err).context("Failed to download from specified URL")?,
         },
         WasmFile::Bytes(bytes) => bytes,
-    };
-
-    let trigger = if cron_trigger {
-        TriggerRequest::Cron
-    } else {
-        TriggerRequest::Queue(input.unwrap_or_default().into_bytes())
-    };
-
-    let envs = env_pairs
-        .iter()
-        .map(|env| {
-            env.split_once('=')
-                .map(|(k, v)| (k.to_string(), v.to_string()))
-                .unwrap()
-        })
-        .collect::<Vec<(String, String)>>();
-
-    let mut config = Config::new();
-    config.wasm_component_model(true);
-    config.async_support(true);
-    let engine = Engine::new(&config).unwrap();
-
-    let mut linker = Linker::new(&engine);
-    wasmtime_wasi::add_to_linker_async(&mut linker).unwrap();
-    wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker).unwrap();
-
-    if !app_cache_path.is_dir() {
-        tokio::fs::create_dir(&app_cache_path).await?;
-    }
-
-    let component = Component::new(&engine, wasm_binary)?;
-
-    let output = instantiate_and_invoke(
-        &envs,
-        &app_cache_path,
-        &engine,
-        &linker,
-        &component,
-        trigger,
-    )
-    .await
-    .map_err(|msg| anyhow::anyhow!("{}", msg))?;
-
-    Ok(std::string::String::from_utf8(output).expect("Output is invalid utf8"))
+    })
 }
 
-enum TriggerRequest {
-    Cron,
-    Queue(Vec<u8>),
-}
-
-async fn instantiate_and_invoke(
-    envs: &[(String, String)],
-    app_cache_path: &PathBuf,
-    engine: &Engine,
-    linker: &Linker<Host>,
-    component: &Component,
+pub async fn run(
+    runner: Runner,
+    wasm_file: WasmFile,
+    app: LocalApp,
     trigger: TriggerRequest,
-) -> Result<Vec<u8>, String> {
-    let mut builder = WasiCtxBuilder::new();
-    if !envs.is_empty() {
-        builder.envs(envs);
-    }
-    builder
-        .preopened_dir(app_cache_path, ".", DirPerms::all(), FilePerms::all())
-        .expect("preopen failed");
-    builder.inherit_stdio();
-    let ctx = builder.build();
-
-    let host = Host {
-        table: wasmtime::component::ResourceTable::new(),
-        ctx,
-        http: WasiHttpCtx::new(),
-    };
-    let mut store = wasmtime::Store::new(engine, host);
-    match trigger {
-        TriggerRequest::Cron => {
-            if component
-                .component_type()
-                .get_export(engine, "run-cron")
-                .is_none()
-            {
-                return Err("Wasm component is missing the expected function export `run-cron` for CRON trigger app".to_string());
-            }
-            let bindings = cron_bindings::CronJob::instantiate_async(&mut store, component, linker)
-                .await
-                .expect("Wasm instantiate failed");
-
-            bindings
-                .call_run_cron(&mut store)
-                .await
-                .expect("Failed to call invoke cron job")
-        }
-        TriggerRequest::Queue(request) => {
-            if component
-                .component_type()
-                .get_export(engine, "run-task")
-                .is_none()
-            {
-                return Err("Wasm component is missing the expected function export `run-task` for task queue trigger app".to_string());
-            }
-            let bindings =
-                task_bindings::TaskQueue::instantiate_async(&mut store, component, linker)
-                    .await
-                    .expect("Wasm instantiate failed");
-
-            let input = task_bindings::lay3r::avs::types::TaskQueueInput {
-                timestamp: get_time(),
-                request,
-            };
-            bindings
-                .call_run_task(&mut store, &input)
-                .await
-                .expect("Failed to run task")
-        }
-    }
+) -> Result<RunOutput> {
+    let (wasm_binary, envs) = app.prepare(wasm_file).await?;
+
+    let output = runner
+        .run(
+            &wasm_binary,
+            &envs,
+            &app.app_cache_path,
+            &app.permissions,
+            &app.kv,
+            trigger,
+        )
+        .await?;
+
+    Ok(output)
 }
 
-fn get_time() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-}
-
-struct Host {
-    pub(crate) table: wasmtime::component::ResourceTable,
-    pub(crate) ctx: WasiCtx,
-    pub(crate) http: WasiHttpCtx,
-}
-
-impl WasiView for Host {
-    fn table(&mut self) -> &mut wasmtime_wasi::ResourceTable {
-        &mut self.table
-    }
-
-    fn ctx(&mut self) -> &mut WasiCtx {
-        &mut self.ctx
+/// Runs a cron component on its schedule until it stops firing, with `speedup` times shorter waits.
+///
+/// In real time, the next run is scheduled from the current time, so slow runs and timer drift don't add up.
+/// When sped up, the schedule is followed on a virtual clock, and the component's clock is set to the time each run
+/// was scheduled for, so it sees the compressed time.
+pub async fn watch(
+    runner: Runner,
+    wasm_file: WasmFile,
+    app: LocalApp,
+    schedule: CronSchedule,
+    speedup: f64,
+    on_output: impl Fn(DateTime<Utc>, Result<RunOutput>),
+) -> Result<()> {
+    if !(speedup.is_finite() && speedup > 0.0) {
+        bail!("Invalid speedup: {speedup}, expected a positive number");
     }
-}
 
-impl WasiHttpView for Host {
-    fn table(&mut self) -> &mut wasmtime::component::ResourceTable {
-        &mut self.table
+    let (wasm_binary, envs) = app.prepare(wasm_file).await?;
+    let component = runner.compile(&wasm_binary)?;
+
+    let real_time = speedup == 1.0;
+    let mut now = Utc::now();
+    while let Some(next) = schedule.next_after(now) {
+        let wait = (next - now).to_std().unwrap_or_default();
+        tokio::time::sleep(wait.div_f64(speedup)).await;
+
+        let runner = if real_time {
+            runner.clone()
+        } else {
+            let nanos = next.timestamp_nanos_opt().unwrap_or_default();
+            runner.clone().with_clock(Clock::Fixed(nanos as u64))
+        };
+        let output = runner
+            .invoke(
+                &component,
+                &envs,
+                &app.app_cache_path,
+                &app.permissions,
+                &app.kv,
+                TriggerRequest::Cron,
+            )
+            .await
+            .map_err(Into::into);
+        on_output(next, output);
+
+        // Never before `next`, so a run is not repeated if the system clock lags the timer
+        now = if real_time {
+            Utc::now().max(next)
+        } else {
+            next
+        };
     }
 
-    fn ctx(&mut self) -> &mut WasiHttpCtx {
-        &mut self.http
-    }
+    Ok(())
 }
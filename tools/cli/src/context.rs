@@ -45,6 +45,21 @@ impl AppContext {
         ))
     }
 
+    /// The given task queue address, or the one from the environment for the target
+    pub fn task_queue_address(&self, address: Option<String>) -> Result<Address> {
+        let addr_string = match address {
+            Some(x) => x,
+            None => match self.args.target {
+                TargetEnvironment::Local => std::env::var("LOCAL_TASK_QUEUE_ADDRESS")
+                    .context("LOCAL_TASK_QUEUE_ADDRESS not found")?,
+                TargetEnvironment::Testnet => std::env::var("TEST_TASK_QUEUE_ADDRESS")
+                    .context("TEST_TASK_QUEUE_ADDRESS not found")?,
+            },
+        };
+
+        self.chain_config()?.parse_address(&addr_string)
+    }
+
     pub fn client_mnemonic(&self) -> Result<String> {
         let mnemonic_var = match self.args.target {
             TargetEnvironment::Local => "LOCAL_MNEMONIC",
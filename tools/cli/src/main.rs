@@ -3,7 +3,7 @@ mod commands;
 mod config;
 mod context;
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use args::{
     CliArgs, Command, DeployCommand, DeployMode, FaucetCommand, TargetEnvironment,
     TaskQueueCommand, UploadCommand, WasmaticCommand,
@@ -11,16 +11,19 @@ use args::{
 use avs_toolkit_shared::{
     deploy::{DeployContractAddrs, DeployContractArgs, DeployContractArgsVerifierMode},
     faucet::tap_faucet,
-    task_queue::TaskQueue,
+    permissions::Permissions,
+    runner::{Clock, HttpFixtures, HttpMode, KvStore, Limits, Runner, TaskInput, TriggerRequest},
+    schedule::CronSchedule,
+    task_queue::{TaskQueue, TaskQueueQuerier},
     wasmatic,
 };
 use clap::Parser;
 use commands::{
     upload::{upload_contracts, WasmFiles},
-    wasmatic::wasm_arg_to_file,
+    wasmatic::{wasm_arg_to_file, LocalApp},
 };
 use context::AppContext;
-use lavs_apis::time::Duration;
+use lavs_apis::{id::TaskId, time::Duration};
 use layer_climb::prelude::*;
 use layer_climb_cli::command::{ContractLog, WalletLog};
 
@@ -109,17 +112,7 @@ async fn main() -> Result<()> {
             }
         },
         Command::TaskQueue(task_queue_args) => {
-            let addr_string = match task_queue_args.address.clone() {
-                Some(x) => x,
-                None => match ctx.args.target {
-                    TargetEnvironment::Local => std::env::var("LOCAL_TASK_QUEUE_ADDRESS")
-                        .context("LOCAL_TASK_QUEUE_ADDRESS not found")?,
-                    TargetEnvironment::Testnet => std::env::var("TEST_TASK_QUEUE_ADDRESS")
-                        .context("TEST_TASK_QUEUE_ADDRESS not found")?,
-                },
-            };
-
-            let contract_addr = ctx.chain_config()?.parse_address(&addr_string)?;
+            let contract_addr = ctx.task_queue_address(task_queue_args.address.clone())?;
 
             let task_queue = TaskQueue::new(ctx.signing_client().await?, contract_addr).await;
 
@@ -212,6 +205,42 @@ async fn main() -> Result<()> {
                         .update_task_specific_whitelist(to_add, to_remove)
                         .await?;
                 }
+                TaskQueueCommand::Sweep { limit, interval } => loop {
+                    // Keep going until there is nothing left to expire, then wait.
+                    // Failures are logged and retried after the interval.
+                    match task_queue.timeout_expired(limit).await {
+                        Ok(Some(_)) => continue,
+                        Ok(None) => tracing::debug!("No expired tasks to sweep"),
+                        Err(err) => tracing::error!("Sweep failed: {err:?}"),
+                    }
+                    tokio::time::sleep(std::time::Duration::from_secs(interval)).await;
+                },
+                TaskQueueCommand::ViewRequestorAllowlist { start_after, limit } => {
+                    let res = task_queue
+                        .querier
+                        .view_requestor_allowlist(start_after, limit)
+                        .await?;
+
+                    tracing::info!("Requestor Allowlist");
+                    tracing::info!("Address: {}", task_queue.contract_addr);
+                    println!(
+                        "Requestor Allowlist: {}",
+                        if res.addrs.is_empty() {
+                            "none".to_string()
+                        } else {
+                            res.addrs
+                                .into_iter()
+                                .map(|x| x.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        }
+                    );
+                }
+                TaskQueueCommand::UpdateRequestorAllowlist { to_add, to_remove } => {
+                    let _ = task_queue
+                        .update_requestor_allowlist(to_add, to_remove)
+                        .await?;
+                }
             }
         }
         Command::Faucet(faucet_args) => match faucet_args.command {
@@ -305,7 +334,10 @@ async fn main() -> Result<()> {
                 testable,
             } => {
                 let trigger = match (cron_trigger, task_trigger) {
-                    (Some(cron), None) => wasmatic::Trigger::Cron { schedule: cron },
+                    (Some(cron), None) => {
+                        print_upcoming(&CronSchedule::parse(&cron)?);
+                        wasmatic::Trigger::Cron { schedule: cron }
+                    }
                     (None, Some(task)) => wasmatic::Trigger::Queue {
                         task_queue_addr: task,
                         hd_index,
@@ -324,7 +356,9 @@ async fn main() -> Result<()> {
                     })
                     .collect::<Vec<(String, String)>>();
 
-                let permissions: serde_json::Value = serde_json::from_str(&permissions).unwrap();
+                // Sent as given, but checked against the schema first
+                serde_json::from_str::<Permissions>(&permissions).context("Invalid permissions")?;
+                let permissions: serde_json::Value = serde_json::from_str(&permissions)?;
 
                 wasmatic::deploy(
                     reqwest::Client::new(),
@@ -360,6 +394,23 @@ async fn main() -> Result<()> {
                 envs,
                 dir,
                 input,
+                permissions,
+                fuel,
+                max_memory_mb,
+                max_table_elements,
+                timeout,
+                task_id,
+                task_queue,
+                description,
+                created_height,
+                expires_time,
+                kv,
+                now,
+                record,
+                replay,
+                watch,
+                schedule,
+                speedup,
             } => {
                 let app_cache_path = if let Some(dir) = dir {
                     dir
@@ -369,12 +420,106 @@ async fn main() -> Result<()> {
                         .path()
                         .into()
                 };
+                let permissions =
+                    serde_json::from_str(&permissions).context("Invalid permissions")?;
+                let default_limits = Limits::default();
+                let limits = Limits {
+                    fuel,
+                    max_memory: max_memory_mb
+                        .map(|mb| mb * 1024 * 1024)
+                        .or(default_limits.max_memory),
+                    max_table_elements: max_table_elements.or(default_limits.max_table_elements),
+                    timeout: timeout
+                        .map(std::time::Duration::from_secs)
+                        .or(default_limits.timeout),
+                };
+                let trigger = if cron_trigger {
+                    TriggerRequest::Cron
+                } else {
+                    let mut task = match task_id {
+                        Some(task_id) => {
+                            let contract_addr = ctx.task_queue_address(task_queue)?;
+                            let querier = TaskQueueQuerier {
+                                contract_addr: contract_addr.clone(),
+                                query_client: ctx.query_client().await?,
+                            };
+                            let task = querier.task(TaskId::new(task_id)).await?;
+                            TaskInput::from_task(&contract_addr, task)?
+                        }
+                        None => TaskInput {
+                            task_queue_addr: task_queue.unwrap_or_default(),
+                            created_height,
+                            expires_time,
+                            description,
+                            ..TaskInput::default()
+                        },
+                    };
+                    if let Some(input) = input {
+                        task.request = input.into_bytes();
+                    }
+                    TriggerRequest::Queue(task)
+                };
                 let wasm_file = wasm_arg_to_file(wasm_source).await?;
-                println!(
-                    "{}",
-                    commands::wasmatic::run(wasm_file, cron_trigger, envs, app_cache_path, input)
-                        .await?
-                );
+                let clock = now.map(Clock::Fixed).unwrap_or_default();
+                let kv = match kv {
+                    Some(path) => KvStore::open(path)?,
+                    None => KvStore::in_memory(),
+                };
+                let http = match (record, replay) {
+                    (Some(path), _) => HttpMode::Record(HttpFixtures::open(path)?),
+                    (_, Some(path)) if !path.exists() => {
+                        bail!("Fixture file {} not found", path.display())
+                    }
+                    (_, Some(path)) => HttpMode::Replay(HttpFixtures::open(path)?),
+                    (None, None) => HttpMode::Live,
+                };
+                let runner = Runner::new(limits)?.with_clock(clock).with_http(http);
+                let app = LocalApp {
+                    env_pairs: envs,
+                    app_cache_path,
+                    permissions,
+                    kv,
+                };
+
+                if watch {
+                    // Required by clap along with --watch
+                    let schedule = CronSchedule::parse(&schedule.unwrap_or_default())?;
+                    print_upcoming(&schedule);
+                    commands::wasmatic::watch(
+                        runner,
+                        wasm_file,
+                        app,
+                        schedule,
+                        speedup,
+                        |time, output| match output {
+                            Ok(output) => println!(
+                                "[{}] {}",
+                                time.to_rfc3339(),
+                                String::from_utf8_lossy(&output.output)
+                            ),
+                            Err(err) => eprintln!("[{}] Run failed: {err:#}", time.to_rfc3339()),
+                        },
+                    )
+                    .await?;
+                } else {
+                    let output = commands::wasmatic::run(runner, wasm_file, app, trigger).await?;
+                    match output.json() {
+                        Some(json) => println!("{}", serde_json::to_string_pretty(&json)?),
+                        None => println!("{}", output.text()?),
+                    }
+                    // Not part of the output, so it can still be piped
+                    eprintln!("Fuel consumed: {}", output.fuel_consumed);
+                }
+            }
+            WasmaticCommand::New {
+                name,
+                world,
+                template,
+                dir,
+            } => {
+                let path = commands::scaffold::new_component(&name, world, template, &dir).await?;
+                println!("Created {}", path.display());
+                println!("See its README.md to build and test it");
             }
             WasmaticCommand::Test { name, input } => {
                 wasmatic::test(
@@ -423,3 +568,13 @@ async fn main() -> Result<()> {
 
     Ok(())
 }
+
+/// How many of the next fire times of a cron schedule are shown
+const UPCOMING_RUNS: usize = 5;
+
+fn print_upcoming(schedule: &CronSchedule) {
+    println!("Next runs of `{schedule}`:");
+    for time in schedule.upcoming(UPCOMING_RUNS) {
+        println!("  {}", time.to_rfc3339());
+    }
+}
@@ -0,0 +1,15 @@
+#[allow(warnings)]
+mod bindings;
+
+use bindings::{Guest, Output};
+
+struct Component;
+
+impl Guest for Component {
+    fn run_cron() -> Output {
+        run(None)
+    }
+}
+
+bindings::export!(Component with_types_in bindings);
+
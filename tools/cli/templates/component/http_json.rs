@@ -0,0 +1,34 @@
+use layer_wasi::{block_on, Reactor, Request, Retry, WasiPollable};
+use serde::Deserialize;
+use std::time::Duration;
+
+#[derive(Deserialize, Debug)]
+struct Input {
+    url: String,
+}
+
+/// Fetches JSON from the `url` in the input, or in the `URL` env variable without one
+fn run(request: Option<&[u8]>) -> Output {
+    let url = match request {
+        Some(request) => {
+            serde_json::from_slice::<Input>(request)
+                .map_err(|err| format!("invalid input: {err}"))?
+                .url
+        }
+        None => std::env::var("URL").or(Err("missing env var `URL`".to_string()))?,
+    };
+
+    block_on(|reactor| fetch(reactor, url))
+}
+
+async fn fetch(reactor: Reactor, url: String) -> Output {
+    let mut req = Request::get(&url)?;
+    req.header("accept", "application/json")
+        .timeout(Duration::from_secs(10))
+        .retry(Retry::default());
+
+    let res = reactor.send(req).await?.error_for_status()?;
+    let json: serde_json::Value = res.json()?;
+
+    serde_json::to_vec(&json).map_err(|err| err.to_string())
+}
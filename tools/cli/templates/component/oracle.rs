@@ -0,0 +1,40 @@
+use layer_wasi::{block_on, Reactor, Request, Retry, WasiPollable};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, time::Duration};
+
+#[derive(Deserialize, Debug)]
+struct ExchangeRates {
+    rates: HashMap<String, Rate>,
+}
+
+#[derive(Deserialize, Debug)]
+struct Rate {
+    value: f64,
+}
+
+#[derive(Serialize, Debug)]
+struct Price {
+    price: String,
+}
+
+/// Gets the BTCUSD price from CoinGecko, with the `API_KEY` env variable
+fn run(_request: Option<&[u8]>) -> Output {
+    let api_key = std::env::var("API_KEY").or(Err("missing env var `API_KEY`".to_string()))?;
+    block_on(|reactor| btc_usd(reactor, api_key))
+}
+
+async fn btc_usd(reactor: Reactor, api_key: String) -> Output {
+    let mut req = Request::get("https://api.coingecko.com/api/v3/exchange_rates")?;
+    req.header("x-cg-pro-api-key", &api_key)
+        .timeout(Duration::from_secs(10))
+        .retry(Retry::default());
+
+    let res = reactor.send(req).await?.error_for_status()?;
+    let rates: ExchangeRates = res.json()?;
+    let price = rates.rates.get("usd").ok_or("missing USD rate")?.value;
+
+    serde_json::to_vec(&Price {
+        price: price.to_string(),
+    })
+    .map_err(|err| err.to_string())
+}
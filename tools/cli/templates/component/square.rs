@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Debug)]
+struct Input {
+    x: u64,
+}
+
+#[derive(Serialize, Debug)]
+struct Square {
+    y: u64,
+}
+
+/// Squares `x`
+fn run(request: Option<&[u8]>) -> Output {
+    let Input { x } = serde_json::from_slice(request.unwrap_or_default())
+        .map_err(|err| format!("invalid input: {err}"))?;
+    let y = x.checked_mul(x).ok_or("overflow")?;
+
+    serde_json::to_vec(&Square { y }).map_err(|err| err.to_string())
+}
@@ -0,0 +1,15 @@
+#[allow(warnings)]
+mod bindings;
+
+use bindings::{Guest, Output, TaskQueueInput};
+
+struct Component;
+
+impl Guest for Component {
+    fn run_task(input: TaskQueueInput) -> Output {
+        run(Some(&input.request))
+    }
+}
+
+bindings::export!(Component with_types_in bindings);
+
@@ -0,0 +1,45 @@
+//! Runs the component with the sample input through the local runner, with HTTP requests answered from
+//! `tests/fixtures.json`. Build it first with `cargo component build`.
+use avs_toolkit_shared::{
+    permissions::Permissions,
+    runner::{{{runner_imports}}},
+};
+use serde_json::Value;
+
+const WASM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/target/wasm32-wasip1/debug/{{wasm_name}}.wasm"
+);
+
+fn read(path: &str) -> Vec<u8> {
+    let path = format!("{}/{path}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::read(&path).unwrap_or_else(|err| panic!("failed to read {path}: {err}"))
+}
+
+#[tokio::test]
+async fn test_sample_input() {
+    let wasm = std::fs::read(WASM).expect("build the component first with `cargo component build`");
+    let fixtures = HttpFixtures::open(format!("{}/tests/fixtures.json", env!("CARGO_MANIFEST_DIR")))
+        .unwrap();
+    let runner = Runner::new(Limits::default())
+        .unwrap()
+        .with_http(HttpMode::Replay(fixtures));
+
+    let envs: Vec<(String, String)> = {{envs}};
+    let trigger = {{trigger}};
+    let dir = tempfile::tempdir().unwrap();
+    let output = runner
+        .run(
+            &wasm,
+            &envs,
+            dir.path(),
+            &Permissions::default(),
+            &KvStore::in_memory(),
+            trigger,
+        )
+        .await
+        .unwrap();
+
+    let expected: Value = serde_json::from_slice(&read("tests/expected.json")).unwrap();
+    assert_eq!(output.to_value().unwrap(), expected);
+}
@@ -3,11 +3,12 @@ use crate::{
     prelude::*,
 };
 use avs_toolkit_shared::{
+    operator::Operator,
     task_queue::{TaskQueue, TaskQueueView, TaskView},
     wasmatic::Trigger,
 };
 use dominator_helpers::futures::AsyncLoader;
-use lavs_apis::tasks::TaskResponse;
+use lavs_apis::{tasks::TaskResponse, verifier_simple::OperatorStatsResponse};
 use lavs_mock_operators::contract::query;
 
 pub struct TaskQueueViewQueueUi {
@@ -222,8 +223,7 @@ impl TaskQueueViewQueueUi {
                                     .child(html!("ul", {
                                         .children(result.operators.iter().map(|operator| {
                                             html!("li", {
-                                                .class(FontWeight::Bold.class())
-                                                .text(&operator.address.to_string())
+                                                .child(render_operator(&result.verifier_addr, operator))
                                             })
                                         }))
                                     }))
@@ -262,6 +262,51 @@ impl TaskQueueViewQueueUi {
     }
 }
 
+fn render_operator(verifier_addr: &Address, operator: &Operator) -> Dom {
+    let stats: Mutable<Option<std::result::Result<OperatorStatsResponse, String>>> =
+        Mutable::new(None);
+
+    let address = operator.address.to_string();
+
+    html!("div", {
+        .future(clone!(stats, address, verifier_addr => async move {
+            let res:Result<OperatorStatsResponse> = query_client().contract_smart(&verifier_addr, &lavs_apis::verifier_simple::QueryMsg::OperatorStats {
+                operator: address,
+            }).await;
+
+            match res {
+                Ok(result) => {
+                    stats.set(Some(Ok(result)));
+                },
+                Err(err) => {
+                    stats.set(Some(Err(err.to_string())));
+                }
+            }
+        }))
+        .child(html!("div", {
+            .class(FontWeight::Bold.class())
+            .text(&address)
+        }))
+        .child_signal(stats.signal_cloned().map(|stats| {
+            let text = match stats? {
+                Ok(stats) => format!(
+                    "Voted: {}, Matched: {}, Outside spread: {}, Missed: {}, Average latency: {}",
+                    stats.tasks_voted,
+                    stats.votes_matched,
+                    stats.votes_outside_spread,
+                    stats.tasks_missed,
+                    stats.average_latency.map(|seconds| format!("{seconds}s")).unwrap_or("none".to_string())
+                ),
+                // not every verifier tracks operator stats
+                Err(err) => format!("Stats unavailable: {}", err),
+            };
+            Some(html!("div", {
+                .text(&text)
+            }))
+        }))
+    })
+}
+
 fn render_task(task_queue_addr: &Address, task: &TaskView) -> Dom {
     static TASK: LazyLock<String> = LazyLock::new(|| {
         class! {
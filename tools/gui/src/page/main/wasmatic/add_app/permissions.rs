@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use avs_toolkit_shared::permissions::Permissions;
 
 pub struct PermissionsUi {
     permissions: Mutable<Option<serde_json::Value>>,
@@ -50,7 +51,10 @@ impl PermissionsUi {
                                 state.error.set_neq(None);
                             },
                             Some(value) => {
-                                match serde_json::from_str(&value) {
+                                // Checked against the schema, but sent as given
+                                let parsed = serde_json::from_str::<serde_json::Value>(&value)
+                                    .and_then(|value| Permissions::deserialize(&value).map(|_| value));
+                                match parsed {
                                     Ok(value) => {
                                         state.error.set_neq(None);
                                         state.permissions.set(Some(value));
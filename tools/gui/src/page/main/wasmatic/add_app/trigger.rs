@@ -5,6 +5,7 @@ use avs_toolkit_shared::{
         CodeIds, DeployContractArgs, DeployContractArgsRequestor, DeployContractArgsVerifierMode,
     },
     file::WasmFile,
+    schedule::CronSchedule,
     wasmatic::Trigger,
 };
 use cosmwasm_std::Decimal;
@@ -59,6 +60,9 @@ impl TaskQueueArgs {
     }
 }
 
+/// How many of the next fire times of a cron schedule are shown
+const UPCOMING_RUNS: usize = 5;
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum TriggerChoice {
     Cron,
@@ -126,7 +130,8 @@ impl TriggerUi {
                 let cron_job = state
                     .cron_job
                     .get_cloned()
-                    .ok_or_else(|| anyhow!("Cron job name is required"))?;
+                    .ok_or_else(|| anyhow!("Cron schedule is required"))?;
+                CronSchedule::parse(&cron_job)?;
                 Ok(TriggerData::Cron { schedule: cron_job })
             }
         }
@@ -143,7 +148,9 @@ impl TriggerUi {
                         state.valid_task_queue_signal().boxed()
                     },
                     TriggerChoice::Cron => {
-                        state.cron_job.signal_ref(|address| address.is_some()).boxed()
+                        state.cron_job.signal_ref(|schedule| {
+                            schedule.as_deref().is_some_and(|schedule| CronSchedule::parse(schedule).is_ok())
+                        }).boxed()
                     }
                 }
             }))
@@ -343,18 +350,47 @@ impl TriggerUi {
     fn render_cron(self: &Arc<Self>) -> Dom {
         let state = self;
 
-        Label::new()
-            .with_text("Cron job")
-            .with_direction(LabelDirection::Column)
-            .render(
-                TextInput::new()
-                    .with_placeholder("some cron job name")
-                    .with_on_input(clone!(state => move |cron_job| {
-                        state.error.set_neq(None);
-                        state.cron_job.set(cron_job);
-                    }))
-                    .render(),
+        static CONTAINER: LazyLock<String> = LazyLock::new(|| {
+            class! {
+                .style("display", "flex")
+                .style("flex-direction", "column")
+                .style("gap", "0.5rem")
+            }
+        });
+
+        html!("div", {
+            .class(&*CONTAINER)
+            .child(Label::new()
+                .with_text("Cron schedule")
+                .with_direction(LabelDirection::Column)
+                .render(
+                    TextInput::new()
+                        .with_placeholder("sec min hour day month weekday, like 0 */5 * * * *")
+                        .with_on_input(clone!(state => move |cron_job| {
+                            state.error.set_neq(None);
+                            state.cron_job.set(cron_job);
+                        }))
+                        .render(),
+                )
             )
+            .child_signal(state.cron_job.signal_cloned().map(|cron_job| {
+                cron_job.map(|cron_job| match CronSchedule::parse(&cron_job) {
+                    Ok(schedule) => html!("div", {
+                        .class(FontSize::Body.class())
+                        .text("Next runs:")
+                        .children(schedule.upcoming(UPCOMING_RUNS).into_iter().map(|time| {
+                            html!("div", {
+                                .text(&time.to_rfc2822())
+                            })
+                        }))
+                    }),
+                    Err(err) => html!("div", {
+                        .class([FontSize::Body.class(), &*COLOR_TEXT_INTERACTIVE_ERROR])
+                        .text(&format!("{err:#}"))
+                    }),
+                })
+            }))
+        })
     }
 
     fn render_from_str<T: FromStr + ToString + Clone + 'static>(
@@ -0,0 +1,52 @@
+use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+pub struct NodeArgs {
+    #[arg(long, value_enum, default_value_t = TargetEnvironment::Local)]
+    pub target: TargetEnvironment,
+
+    /// Set the logging level
+    #[arg(long, value_enum, default_value_t = LogLevel::Info)]
+    pub log_level: LogLevel,
+
+    /// Port to serve the wasmatic API on
+    #[arg(long, default_value_t = 8081)]
+    pub port: u16,
+
+    /// Directory for uploaded wasm files and app caches
+    #[arg(long, default_value = ".wasmatic-node")]
+    pub dir: PathBuf,
+
+    /// Mnemonic of the operator, apps sign with the account at their `hd_index`
+    #[arg(long, env = "OPERATOR_MNEMONIC", hide_env_values = true)]
+    pub mnemonic: String,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TargetEnvironment {
+    Local,
+    Testnet,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl From<LogLevel> for tracing::Level {
+    fn from(log_level: LogLevel) -> Self {
+        match log_level {
+            LogLevel::Trace => tracing::Level::TRACE,
+            LogLevel::Debug => tracing::Level::DEBUG,
+            LogLevel::Info => tracing::Level::INFO,
+            LogLevel::Warn => tracing::Level::WARN,
+            LogLevel::Error => tracing::Level::ERROR,
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::{Context, Result};
+use layer_climb::prelude::*;
+use serde::Deserialize;
+
+use crate::args::TargetEnvironment;
+
+/// The chains from the config shared with the cli, other fields are ignored
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub local: Option<ChainInfo>,
+    pub testnet: Option<ChainInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChainInfo {
+    pub chain: ChainConfig,
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        serde_json::from_str(include_str!("../../config.json")).context("Failed to parse config")
+    }
+
+    pub fn chain_config(self, target: TargetEnvironment) -> Result<ChainConfig> {
+        match target {
+            TargetEnvironment::Local => self.local,
+            TargetEnvironment::Testnet => self.testnet,
+        }
+        .map(|info| info.chain)
+        .context(format!(
+            "Chain config for environment {:?} not found",
+            target
+        ))
+    }
+}
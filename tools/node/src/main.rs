@@ -0,0 +1,40 @@
+mod args;
+mod config;
+mod node;
+mod server;
+mod triggers;
+
+use anyhow::Result;
+use args::NodeArgs;
+use clap::Parser;
+use config::Config;
+use node::Node;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Load the .env file before anything, in case it's used by args
+    if dotenvy::dotenv().is_err() {
+        println!(
+            "Failed to load .env file. Ensure values are surrounded by quotes in the .env file."
+        );
+    }
+
+    // load the args before setting up the logger, since it uses the log level
+    let args = NodeArgs::parse();
+
+    tracing_subscriber::fmt()
+        .with_target(false)
+        .with_max_level(tracing::Level::from(args.log_level))
+        .init();
+
+    let chain = Config::load()?.chain_config(args.target)?;
+    let node = Node::new(chain, args.mnemonic, args.dir).await?;
+    tracing::info!("Operator address: {}", node.operator_address(0).await?);
+
+    let listener = tokio::net::TcpListener::bind(("127.0.0.1", args.port)).await?;
+    tracing::info!("Listening on http://{}", listener.local_addr()?);
+
+    axum::serve(listener, server::router(node)).await?;
+
+    Ok(())
+}
@@ -0,0 +1,308 @@
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use avs_toolkit_shared::{
+    permissions::Permissions,
+    runner::{KvStore, Limits, RunOutput, Runner, TaskInput, TriggerRequest},
+    wasmatic::{wasm_digest, AppInfo, AppResponse, Trigger},
+};
+use layer_climb::prelude::*;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::{sync::Mutex, task::JoinHandle};
+use wasmtime::component::Component;
+
+use crate::triggers;
+
+/// The body of `POST /app`, as sent by `wasmatic::deploy`
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AddAppRequest {
+    pub name: String,
+    pub digest: String,
+    pub trigger: Trigger,
+    #[serde(default)]
+    pub permissions: Value,
+    #[serde(default)]
+    pub envs: Vec<(String, String)>,
+    #[serde(default)]
+    pub testable: bool,
+    /// Where to download the wasm from, if it was not uploaded
+    pub wasm_url: Option<String>,
+}
+
+/// An app registered on the node, its trigger runs until it is removed
+struct App {
+    info: AppInfo,
+    envs: Vec<(String, String)>,
+    permissions: Permissions,
+    kv: KvStore,
+    component: Component,
+    trigger: JoinHandle<()>,
+}
+
+impl Drop for App {
+    fn drop(&mut self) {
+        self.trigger.abort();
+    }
+}
+
+pub struct Node {
+    pub chain: ChainConfig,
+    runner: Runner,
+    mnemonic: String,
+    dir: PathBuf,
+    http: reqwest::Client,
+    apps: Mutex<BTreeMap<String, App>>,
+    /// Signing clients by hd index, shared by the apps using the same account
+    clients: Mutex<BTreeMap<u32, Arc<SigningClient>>>,
+}
+
+impl Node {
+    pub async fn new(chain: ChainConfig, mnemonic: String, dir: PathBuf) -> Result<Arc<Self>> {
+        tokio::fs::create_dir_all(dir.join("wasm")).await?;
+        tokio::fs::create_dir_all(dir.join("apps")).await?;
+        tokio::fs::create_dir_all(dir.join("kv")).await?;
+
+        Ok(Arc::new(Self {
+            chain,
+            runner: Runner::new(Limits::default())?,
+            mnemonic,
+            dir,
+            http: reqwest::Client::new(),
+            apps: Mutex::new(BTreeMap::new()),
+            clients: Mutex::new(BTreeMap::new()),
+        }))
+    }
+
+    /// Stores the wasm, returning its digest
+    pub async fn upload(&self, wasm_binary: &[u8]) -> Result<String> {
+        // Reject anything which is not a component right away
+        self.runner.compile(wasm_binary)?;
+
+        let digest = wasm_digest(wasm_binary);
+        tokio::fs::write(self.wasm_path(&digest)?, wasm_binary).await?;
+
+        Ok(digest)
+    }
+
+    pub async fn add_app(self: &Arc<Self>, req: AddAppRequest) -> Result<()> {
+        if req.name.is_empty()
+            || !req
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!("Invalid app name: `{}`", req.name);
+        }
+
+        let mut apps = self.apps.lock().await;
+        if apps.contains_key(&req.name) {
+            bail!("App `{}` already exists", req.name);
+        }
+
+        let permissions: Permissions = match &req.permissions {
+            Value::Null => Permissions::default(),
+            value => serde_json::from_value(value.clone()).context("Invalid permissions")?,
+        };
+
+        let wasm_path = self.wasm_path(&req.digest)?;
+        if !wasm_path.exists() {
+            if let Some(wasm_url) = &req.wasm_url {
+                let wasm_binary = self.http.get(wasm_url).send().await?.bytes().await?;
+                if wasm_digest(&wasm_binary) != req.digest {
+                    bail!("Digest of {wasm_url} does not match {}", req.digest);
+                }
+                self.upload(&wasm_binary).await?;
+            }
+        }
+        let wasm_binary = tokio::fs::read(&wasm_path)
+            .await
+            .context(format!("Wasm with digest {} was not uploaded", req.digest))?;
+        let component = self.runner.compile(&wasm_binary)?;
+
+        tokio::fs::create_dir_all(self.app_cache_path(&req.name)).await?;
+        // Kept when the app is removed, so it picks up where it left off when deployed again
+        let kv = KvStore::open(self.dir.join("kv").join(format!("{}.json", req.name)))?;
+        let trigger = triggers::spawn(
+            self.clone(),
+            req.name.clone(),
+            component.clone(),
+            req.envs.clone(),
+            permissions.clone(),
+            kv.clone(),
+            req.trigger.clone(),
+        )?;
+
+        tracing::info!("Added app `{}`", req.name);
+        apps.insert(
+            req.name.clone(),
+            App {
+                info: AppInfo {
+                    name: req.name,
+                    digest: req.digest,
+                    trigger: req.trigger,
+                    permissions: req.permissions,
+                    testable: req.testable,
+                },
+                envs: req.envs,
+                permissions,
+                kv,
+                component,
+                trigger,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Removes the apps and stops their triggers, fails without removing any if one is missing
+    pub async fn remove_apps(&self, names: &[String]) -> Result<()> {
+        let mut apps = self.apps.lock().await;
+        if let Some(name) = names.iter().find(|name| !apps.contains_key(*name)) {
+            bail!("App `{name}` not found");
+        }
+        for name in names {
+            apps.remove(name);
+            tracing::info!("Removed app `{name}`");
+        }
+
+        Ok(())
+    }
+
+    pub async fn apps(&self) -> Result<AppResponse> {
+        let apps = self
+            .apps
+            .lock()
+            .await
+            .values()
+            .map(|app| app.info.clone())
+            .collect();
+
+        let mut digests = vec![];
+        let mut entries = tokio::fs::read_dir(self.dir.join("wasm")).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Some(hex) = entry.file_name().to_string_lossy().strip_suffix(".wasm") {
+                digests.push(format!("sha256:{hex}"));
+            }
+        }
+        digests.sort();
+
+        Ok(AppResponse { apps, digests })
+    }
+
+    /// Runs a testable app once, with the input as the task payload for task queue apps
+    pub async fn test_app(&self, name: &str, input: Option<Value>) -> Result<RunOutput> {
+        let (component, envs, permissions, kv, trigger) = {
+            let apps = self.apps.lock().await;
+            let app = apps.get(name).context(format!("App `{name}` not found"))?;
+            if !app.info.testable {
+                bail!("App `{name}` is not testable");
+            }
+            (
+                app.component.clone(),
+                app.envs.clone(),
+                app.permissions.clone(),
+                app.kv.clone(),
+                app.info.trigger.clone(),
+            )
+        };
+
+        let request = match trigger {
+            Trigger::Cron { .. } => TriggerRequest::Cron,
+            Trigger::Queue {
+                task_queue_addr, ..
+            } => TriggerRequest::Queue(TaskInput {
+                task_queue_addr,
+                ..TaskInput::new(
+                    input
+                        .map(|input| serde_json::to_vec(&input))
+                        .transpose()?
+                        .unwrap_or_default(),
+                )
+            }),
+        };
+
+        self.invoke(name, &component, &envs, &permissions, &kv, request)
+            .await
+    }
+
+    pub async fn invoke(
+        &self,
+        name: &str,
+        component: &Component,
+        envs: &[(String, String)],
+        permissions: &Permissions,
+        kv: &KvStore,
+        request: TriggerRequest,
+    ) -> Result<RunOutput> {
+        let output = self
+            .runner
+            .invoke(
+                component,
+                envs,
+                &self.app_cache_path(name),
+                permissions,
+                kv,
+                request,
+            )
+            .await?;
+        tracing::debug!("{name}: fuel consumed: {}", output.fuel_consumed);
+
+        Ok(output)
+    }
+
+    /// The operator accounts: the first one, and those used by task queue apps
+    pub async fn operators(&self) -> Result<Vec<Address>> {
+        let mut hd_indexes = vec![0];
+        for app in self.apps.lock().await.values() {
+            if let Trigger::Queue { hd_index, .. } = app.info.trigger {
+                hd_indexes.push(hd_index);
+            }
+        }
+        hd_indexes.sort();
+        hd_indexes.dedup();
+
+        let mut operators = vec![];
+        for hd_index in hd_indexes {
+            operators.push(self.operator_address(hd_index).await?);
+        }
+
+        Ok(operators)
+    }
+
+    pub async fn operator_address(&self, hd_index: u32) -> Result<Address> {
+        let signer = self.signer(hd_index)?;
+        self.chain.address_from_pub_key(&signer.public_key().await?)
+    }
+
+    pub async fn client(&self, hd_index: u32) -> Result<Arc<SigningClient>> {
+        let mut clients = self.clients.lock().await;
+        if let Some(client) = clients.get(&hd_index) {
+            return Ok(client.clone());
+        }
+
+        let client =
+            Arc::new(SigningClient::new(self.chain.clone(), self.signer(hd_index)?).await?);
+        clients.insert(hd_index, client.clone());
+
+        Ok(client)
+    }
+
+    fn signer(&self, hd_index: u32) -> Result<KeySigner> {
+        KeySigner::new_mnemonic_str(&self.mnemonic, Some(&cosmos_hub_derivation(hd_index)?))
+    }
+
+    fn wasm_path(&self, digest: &str) -> Result<PathBuf> {
+        match digest.strip_prefix("sha256:") {
+            Some(hex) if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                Ok(self.dir.join("wasm").join(format!("{hex}.wasm")))
+            }
+            _ => bail!("Invalid digest: `{digest}`, expected `sha256:<hex>`"),
+        }
+    }
+
+    fn app_cache_path(&self, name: &str) -> PathBuf {
+        self.dir.join("apps").join(name)
+    }
+}
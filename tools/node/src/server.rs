@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use avs_toolkit_shared::wasmatic::{AppResponse, EndpointInfoResponse, TestOutput};
+use axum::{
+    body::Bytes,
+    extract::{DefaultBodyLimit, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tower_http::cors::CorsLayer;
+
+use crate::node::{AddAppRequest, Node};
+
+/// Components can be much larger than the default 2MB limit
+const MAX_BODY_SIZE: usize = 50 * 1024 * 1024;
+
+/// The subset of the wasmatic API used by the cli and the gui
+pub fn router(node: Arc<Node>) -> Router {
+    Router::new()
+        .route("/upload", post(upload))
+        .route("/app", get(list_apps).post(add_app).delete(remove_apps))
+        .route("/info", get(info))
+        .route("/test", post(test_app))
+        .layer(DefaultBodyLimit::max(MAX_BODY_SIZE))
+        .layer(CorsLayer::permissive())
+        .with_state(node)
+}
+
+async fn upload(State(node): State<Arc<Node>>, body: Bytes) -> Result<Json<Value>, ServerError> {
+    let digest = node.upload(&body).await?;
+    Ok(Json(json!({ "digest": digest })))
+}
+
+async fn list_apps(State(node): State<Arc<Node>>) -> Result<Json<AppResponse>, ServerError> {
+    Ok(Json(node.apps().await?))
+}
+
+async fn add_app(
+    State(node): State<Arc<Node>>,
+    Json(req): Json<AddAppRequest>,
+) -> Result<(), ServerError> {
+    node.add_app(req).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct RemoveAppsRequest {
+    apps: Vec<String>,
+}
+
+async fn remove_apps(
+    State(node): State<Arc<Node>>,
+    Json(req): Json<RemoveAppsRequest>,
+) -> Result<(), ServerError> {
+    node.remove_apps(&req.apps).await?;
+    Ok(())
+}
+
+async fn info(State(node): State<Arc<Node>>) -> Result<Json<EndpointInfoResponse>, ServerError> {
+    let operators = node
+        .operators()
+        .await?
+        .iter()
+        .map(|addr| addr.to_string())
+        .collect();
+
+    Ok(Json(EndpointInfoResponse { operators }))
+}
+
+#[derive(Deserialize)]
+struct TestRequest {
+    name: String,
+    input: Option<Value>,
+}
+
+async fn test_app(
+    State(node): State<Arc<Node>>,
+    Json(req): Json<TestRequest>,
+) -> Result<Json<TestOutput>, ServerError> {
+    let output = node.test_app(&req.name, req.input).await?;
+
+    // Like wasmatic, return the output as JSON when it is, and as a string otherwise
+    Ok(Json(TestOutput {
+        message: None,
+        output: Some(output.to_value()?),
+    }))
+}
+
+/// Any failure is reported as a bad request, with the error in `message`
+struct ServerError(anyhow::Error);
+
+impl IntoResponse for ServerError {
+    fn into_response(self) -> Response {
+        tracing::warn!("Request failed: {:#}", self.0);
+
+        let body = TestOutput {
+            message: Some(format!("{:#}", self.0)),
+            output: None,
+        };
+        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ServerError {
+    fn from(err: E) -> Self {
+        Self(err.into())
+    }
+}
@@ -0,0 +1,190 @@
+use std::{collections::BTreeSet, sync::Arc, time::Duration};
+
+use anyhow::Result;
+use avs_toolkit_shared::{
+    permissions::Permissions,
+    runner::{KvStore, RunOutput, TaskInput, TriggerRequest},
+    schedule::CronSchedule,
+    task_queue::TaskQueueQuerier,
+    wasmatic::Trigger,
+};
+use chrono::Utc;
+use lavs_apis::{id::TaskId, verifier_simple::ExecuteMsg};
+use layer_climb::prelude::*;
+use tokio::task::JoinHandle;
+use wasmtime::component::Component;
+
+use crate::node::Node;
+
+/// Starts running the app on its trigger, until the handle is aborted
+pub fn spawn(
+    node: Arc<Node>,
+    name: String,
+    component: Component,
+    envs: Vec<(String, String)>,
+    permissions: Permissions,
+    kv: KvStore,
+    trigger: Trigger,
+) -> Result<JoinHandle<()>> {
+    let app = AppRunner {
+        node,
+        name,
+        component,
+        envs,
+        permissions,
+        kv,
+    };
+
+    match trigger {
+        Trigger::Cron { schedule } => {
+            let schedule = CronSchedule::parse(&schedule)?;
+            Ok(tokio::spawn(app.run_cron(schedule)))
+        }
+        Trigger::Queue {
+            task_queue_addr,
+            hd_index,
+            poll_interval,
+        } => {
+            let task_queue = app.node.chain.parse_address(&task_queue_addr)?;
+            Ok(tokio::spawn(app.poll_queue(
+                task_queue,
+                hd_index,
+                poll_interval,
+            )))
+        }
+    }
+}
+
+struct AppRunner {
+    node: Arc<Node>,
+    name: String,
+    component: Component,
+    envs: Vec<(String, String)>,
+    permissions: Permissions,
+    kv: KvStore,
+}
+
+impl AppRunner {
+    async fn run_cron(self, schedule: CronSchedule) {
+        while let Some(next) = schedule.next_after(Utc::now()) {
+            let wait = (next - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(wait).await;
+
+            match self.invoke(TriggerRequest::Cron).await {
+                Ok(output) => tracing::info!(
+                    "{}: cron output: {}",
+                    self.name,
+                    String::from_utf8_lossy(&output.output)
+                ),
+                Err(err) => tracing::error!("{}: cron run failed: {err:#}", self.name),
+            }
+        }
+    }
+
+    async fn poll_queue(self, task_queue: Address, hd_index: u32, poll_interval: u32) {
+        // Tasks already executed by this app, which are still open
+        let mut done = BTreeSet::new();
+        let mut interval = tokio::time::interval(Duration::from_secs(poll_interval.max(1).into()));
+
+        loop {
+            interval.tick().await;
+            if let Err(err) = self.poll_once(&task_queue, hd_index, &mut done).await {
+                tracing::error!("{}: failed to poll {task_queue}: {err:#}", self.name);
+            }
+        }
+    }
+
+    /// Runs the open tasks with the highest tips first, and submits the results to the verifier
+    async fn poll_once(
+        &self,
+        task_queue: &Address,
+        hd_index: u32,
+        done: &mut BTreeSet<TaskId>,
+    ) -> Result<()> {
+        let client = self.node.client(hd_index).await?;
+        let querier = TaskQueueQuerier {
+            contract_addr: task_queue.clone(),
+            query_client: client.querier.clone(),
+        };
+
+        let tasks = querier.open_tasks_by_priority(None, None).await?;
+        done.retain(|id| tasks.iter().any(|task| task.id == *id));
+        if tasks.iter().all(|task| done.contains(&task.id)) {
+            return Ok(());
+        }
+
+        let config = querier.config().await?;
+        let verifier = self.node.chain.parse_address(&config.verifier)?;
+
+        for task in tasks {
+            if done.contains(&task.id) {
+                continue;
+            }
+
+            // A failure on one task doesn't stop the others, it is retried on the next poll
+            let input = match querier
+                .task(task.id)
+                .await
+                .and_then(|details| TaskInput::from_task(task_queue, details))
+            {
+                Ok(input) => input,
+                Err(err) => {
+                    tracing::error!("{}: failed to load task {}: {err:#}", self.name, task.id);
+                    continue;
+                }
+            };
+            let result = match self.invoke(TriggerRequest::Queue(input)).await {
+                Ok(output) => output.text().map_err(Into::into),
+                Err(err) => Err(err),
+            };
+            let result = match result {
+                Ok(result) => result,
+                Err(err) => {
+                    // Running it again would fail the same way
+                    tracing::error!("{}: task {} failed: {err:#}", self.name, task.id);
+                    done.insert(task.id);
+                    continue;
+                }
+            };
+
+            let submitted = client
+                .contract_execute(
+                    &verifier,
+                    &ExecuteMsg::ExecutedTask {
+                        task_queue_contract: task_queue.to_string(),
+                        task_id: task.id,
+                        result,
+                    },
+                    vec![],
+                    None,
+                )
+                .await;
+            // Either way it is not run again: a rejected result would be rejected again
+            done.insert(task.id);
+
+            match submitted {
+                Ok(_) => tracing::info!("{}: submitted result for task {}", self.name, task.id),
+                Err(err) => tracing::error!(
+                    "{}: failed to submit result for task {}: {err:#}",
+                    self.name,
+                    task.id
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn invoke(&self, request: TriggerRequest) -> Result<RunOutput> {
+        self.node
+            .invoke(
+                &self.name,
+                &self.component,
+                &self.envs,
+                &self.permissions,
+                &self.kv,
+                request,
+            )
+            .await
+    }
+}
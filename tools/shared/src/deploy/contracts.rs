@@ -43,7 +43,23 @@ pub enum DeployContractArgsVerifierMode {
 pub enum DeployContractArgsRequestor {
     Deployer,
     Fixed(String),
-    Payment { amount: u128, denom: Option<String> },
+    Payment {
+        amount: u128,
+        denom: Option<String>,
+    },
+    PaymentAnyOf(Vec<DeployContractArgsCoin>),
+    Allowlist(Vec<String>),
+    AllowlistWithPayment {
+        addrs: Vec<String>,
+        coins: Vec<DeployContractArgsCoin>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeployContractArgsCoin {
+    pub amount: u128,
+    // implementation fills out chain_config.gas_denom in case of None
+    pub denom: Option<String>,
 }
 
 impl Default for DeployContractArgsRequestor {
@@ -135,6 +151,18 @@ impl DeployContractArgs {
                     denom.unwrap_or(signing_client.querier.chain_config.gas_denom.clone()),
                 ))
             }
+            DeployContractArgsRequestor::PaymentAnyOf(coins) => {
+                Requestor::OpenPaymentAnyOf(parse_coins(&signing_client, coins))
+            }
+            DeployContractArgsRequestor::Allowlist(addrs) => {
+                Requestor::Allowlist(parse_addrs(&signing_client, addrs)?)
+            }
+            DeployContractArgsRequestor::AllowlistWithPayment { addrs, coins } => {
+                Requestor::AllowlistWithPayment {
+                    addrs: parse_addrs(&signing_client, addrs)?,
+                    coins: parse_coins(&signing_client, coins),
+                }
+            }
         };
 
         let task_timeout = TimeoutInfo::new(task_timeout);
@@ -163,6 +191,34 @@ impl DeployContractArgs {
     }
 }
 
+fn parse_addrs(signing_client: &SigningClient, addrs: Vec<String>) -> Result<Vec<String>> {
+    addrs
+        .iter()
+        .map(|s| {
+            Ok(signing_client
+                .querier
+                .chain_config
+                .parse_address(s)?
+                .to_string())
+        })
+        .collect()
+}
+
+fn parse_coins(
+    signing_client: &SigningClient,
+    coins: Vec<DeployContractArgsCoin>,
+) -> Vec<cosmwasm_std::Coin> {
+    coins
+        .into_iter()
+        .map(|DeployContractArgsCoin { amount, denom }| {
+            cosmwasm_std::coin(
+                amount,
+                denom.unwrap_or(signing_client.querier.chain_config.gas_denom.clone()),
+            )
+        })
+        .collect()
+}
+
 #[derive(Debug, Clone)]
 pub enum DeployVerifierMode {
     Simple {
@@ -213,6 +269,9 @@ impl DeployContractAddrs {
                         &lavs_verifier_simple::msg::InstantiateMsg {
                             operator_contract: operators_addr.to_string(),
                             required_percentage: required_voting_percentage,
+                            max_result_bytes: None,
+                            result_schema: None,
+                            fee_recipient: None,
                         },
                         vec![],
                         None,
@@ -236,6 +295,12 @@ impl DeployContractAddrs {
                             threshold_percentage,
                             allowed_spread,
                             slashable_spread,
+                            // mock operators don't implement slashing
+                            slash_amount: None,
+                            aggregation: None,
+                            owner: None,
+                            max_result_bytes: None,
+                            result_schema: None,
                         },
                         vec![],
                         None,
@@ -258,6 +323,9 @@ impl DeployContractAddrs {
                     verifier: verifier_addr.to_string(),
                     owner,
                     task_specific_whitelist: None,
+                    payload_schema: None,
+                    result_schema: None,
+                    fees: None,
                 },
                 vec![],
                 None,
@@ -310,12 +378,84 @@ impl FromStr for DeployContractArgsRequestor {
         } else if s.starts_with("fixed(") && s.ends_with(')') {
             let inner = &s[6..s.len() - 1]; // Extract content inside parentheses
             Ok(DeployContractArgsRequestor::Fixed(inner.trim().to_string()))
+        } else if s.starts_with("payment_any_of(") && s.ends_with(')') {
+            let inner = &s[15..s.len() - 1];
+            Ok(DeployContractArgsRequestor::PaymentAnyOf(parse_coin_list(
+                inner,
+            )?))
+        } else if s.starts_with("allowlist(") && s.ends_with(')') {
+            let inner = &s[10..s.len() - 1];
+            Ok(DeployContractArgsRequestor::Allowlist(parse_addr_list(
+                inner,
+            )?))
+        } else if s.starts_with("allowlist_payment(") && s.ends_with(')') {
+            let inner = &s[18..s.len() - 1];
+            let (addrs, coins) = inner
+                .split_once(';')
+                .ok_or_else(|| anyhow!("expected addresses and coins separated by ';'"))?;
+            Ok(DeployContractArgsRequestor::AllowlistWithPayment {
+                addrs: parse_addr_list(addrs)?,
+                coins: parse_coin_list(coins)?,
+            })
         } else {
             Err(anyhow!("unknown variant"))
         }
     }
 }
 
+/// Comma separated, non-empty list of addresses
+fn parse_addr_list(s: &str) -> Result<Vec<String>> {
+    let addrs: Vec<String> = s
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if addrs.is_empty() {
+        bail!("at least one address is required");
+    }
+    Ok(addrs)
+}
+
+/// Comma separated, non-empty list of coins, each as "amount" or "amount denom"
+fn parse_coin_list(s: &str) -> Result<Vec<DeployContractArgsCoin>> {
+    let coins = s
+        .split(',')
+        .map(|coin| {
+            let parts: Vec<&str> = coin.split_whitespace().collect();
+            let (amount, denom) = match parts.as_slice() {
+                [amount] => (amount, None),
+                [amount, denom] => (amount, Some(denom.to_string())),
+                _ => bail!("invalid coin format"),
+            };
+            let amount = amount
+                .parse::<u128>()
+                .map_err(|_| anyhow!("invalid amount"))?;
+            Ok(DeployContractArgsCoin { amount, denom })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if coins.is_empty() {
+        bail!("at least one coin is required");
+    }
+    Ok(coins)
+}
+
+impl std::fmt::Display for DeployContractArgsCoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.denom {
+            Some(denom) => write!(f, "{} {}", self.amount, denom),
+            None => write!(f, "{}", self.amount),
+        }
+    }
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 impl std::fmt::Display for DeployContractArgsRequestor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -329,6 +469,15 @@ impl std::fmt::Display for DeployContractArgsRequestor {
             DeployContractArgsRequestor::Deployer => {
                 write!(f, "deployer")
             }
+            DeployContractArgsRequestor::PaymentAnyOf(coins) => {
+                write!(f, "payment_any_of({})", join(coins))
+            }
+            DeployContractArgsRequestor::Allowlist(addrs) => {
+                write!(f, "allowlist({})", join(addrs))
+            }
+            DeployContractArgsRequestor::AllowlistWithPayment { addrs, coins } => {
+                write!(f, "allowlist_payment({}; {})", join(addrs), join(coins))
+            }
         }
     }
 }
@@ -460,4 +609,76 @@ mod tests {
         let requestor = DeployContractArgsRequestor::Deployer;
         assert_eq!(format!("{}", requestor), "deployer");
     }
+
+    #[test]
+    fn test_parse_payment_any_of() {
+        let input = "payment_any_of(100 uslay, 250 ibc/ABC, 300)";
+        let result = DeployContractArgsRequestor::from_str(input).unwrap();
+        assert_eq!(
+            result,
+            DeployContractArgsRequestor::PaymentAnyOf(vec![
+                DeployContractArgsCoin {
+                    amount: 100,
+                    denom: Some("uslay".to_string()),
+                },
+                DeployContractArgsCoin {
+                    amount: 250,
+                    denom: Some("ibc/ABC".to_string()),
+                },
+                DeployContractArgsCoin {
+                    amount: 300,
+                    denom: None,
+                },
+            ])
+        );
+        assert_eq!(
+            format!("{}", result),
+            "payment_any_of(100 uslay, 250 ibc/ABC, 300)"
+        );
+    }
+
+    #[test]
+    fn test_parse_allowlist() {
+        let input = " allowlist( addr1,addr2 ) ";
+        let result = DeployContractArgsRequestor::from_str(input).unwrap();
+        assert_eq!(
+            result,
+            DeployContractArgsRequestor::Allowlist(vec!["addr1".to_string(), "addr2".to_string()])
+        );
+        assert_eq!(format!("{}", result), "allowlist(addr1, addr2)");
+    }
+
+    #[test]
+    fn test_parse_allowlist_payment() {
+        let input = "allowlist_payment(addr1, addr2; 100 uslay)";
+        let result = DeployContractArgsRequestor::from_str(input).unwrap();
+        assert_eq!(
+            result,
+            DeployContractArgsRequestor::AllowlistWithPayment {
+                addrs: vec!["addr1".to_string(), "addr2".to_string()],
+                coins: vec![DeployContractArgsCoin {
+                    amount: 100,
+                    denom: Some("uslay".to_string()),
+                }],
+            }
+        );
+        assert_eq!(format!("{}", result), input);
+    }
+
+    #[test]
+    fn test_parse_invalid_lists() {
+        for input in [
+            "allowlist()",
+            "allowlist_payment(addr1)",
+            "allowlist_payment(; 100)",
+            "payment_any_of()",
+            "payment_any_of(100 uslay extra)",
+            "payment_any_of(lots uslay)",
+        ] {
+            assert!(
+                DeployContractArgsRequestor::from_str(input).is_err(),
+                "{input}"
+            );
+        }
+    }
 }
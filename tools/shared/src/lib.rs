@@ -2,6 +2,10 @@ pub mod deploy;
 pub mod faucet;
 pub mod file;
 pub mod operator;
+pub mod permissions;
+#[cfg(feature = "runner")]
+pub mod runner;
+pub mod schedule;
 pub mod task_queue;
 pub mod verifier;
 pub mod wasmatic;
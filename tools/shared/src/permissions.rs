@@ -0,0 +1,123 @@
+use serde::{Deserialize, Serialize};
+
+/// What a wasmatic component is allowed to do, as sent with `deploy`
+///
+/// Anything left out is unrestricted, so `{}` grants everything
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Permissions {
+    /// Hosts the component can send HTTP requests to
+    pub allowed_http_hosts: AllowedHosts,
+    /// HTTP methods the component can use, any if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_http_methods: Option<Vec<String>>,
+    /// Access to the app cache directory
+    pub file_system: FileSystemAccess,
+    /// Environment variables the app can be given, any if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_env_keys: Option<Vec<String>>,
+    /// Maximum size in bytes of an HTTP response body, unlimited if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_response_size: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum AllowedHosts {
+    #[default]
+    All,
+    /// Exact hosts like `api.coingecko.com`, or all subdomains with `*.coingecko.com`
+    Only(Vec<String>),
+    None,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum FileSystemAccess {
+    None,
+    ReadOnly,
+    #[default]
+    ReadWrite,
+}
+
+impl Permissions {
+    pub fn allows_http(&self, method: &str, host: &str) -> bool {
+        let method_allowed = match &self.allowed_http_methods {
+            Some(methods) => methods.iter().any(|m| m.eq_ignore_ascii_case(method)),
+            None => true,
+        };
+
+        method_allowed && self.allowed_http_hosts.allows(host)
+    }
+
+    pub fn allows_env(&self, key: &str) -> bool {
+        match &self.allowed_env_keys {
+            Some(keys) => keys.iter().any(|k| k == key),
+            None => true,
+        }
+    }
+}
+
+impl AllowedHosts {
+    pub fn allows(&self, host: &str) -> bool {
+        match self {
+            AllowedHosts::All => true,
+            AllowedHosts::None => false,
+            AllowedHosts::Only(hosts) => {
+                let host = host.to_ascii_lowercase();
+                hosts.iter().any(|allowed| {
+                    let allowed = allowed.to_ascii_lowercase();
+                    match allowed.strip_prefix("*.") {
+                        Some(domain) => host
+                            .strip_suffix(domain)
+                            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+                        None => allowed == host,
+                    }
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_grants_everything() {
+        let permissions: Permissions = serde_json::from_str("{}").unwrap();
+        assert_eq!(permissions, Permissions::default());
+        assert!(permissions.allows_http("POST", "example.com"));
+        assert!(permissions.allows_env("API_KEY"));
+        assert_eq!(permissions.file_system, FileSystemAccess::ReadWrite);
+    }
+
+    #[test]
+    fn test_parse_restricted() {
+        let permissions: Permissions = serde_json::from_str(
+            r#"{
+                "allowedHttpHosts": {"only": ["api.coingecko.com", "*.example.com"]},
+                "allowedHttpMethods": ["GET"],
+                "fileSystem": "readOnly",
+                "allowedEnvKeys": ["API_KEY"],
+                "maxResponseSize": 1024
+            }"#,
+        )
+        .unwrap();
+
+        assert!(permissions.allows_http("get", "api.coingecko.com"));
+        assert!(permissions.allows_http("GET", "foo.example.com"));
+        assert!(!permissions.allows_http("GET", "example.com"));
+        assert!(!permissions.allows_http("GET", "badexample.com"));
+        assert!(!permissions.allows_http("POST", "api.coingecko.com"));
+        assert!(permissions.allows_env("API_KEY"));
+        assert!(!permissions.allows_env("OTHER"));
+        assert_eq!(permissions.file_system, FileSystemAccess::ReadOnly);
+        assert_eq!(permissions.max_response_size, Some(1024));
+    }
+
+    #[test]
+    fn test_reject_unknown_fields() {
+        assert!(serde_json::from_str::<Permissions>(r#"{"allowedHosts": "all"}"#).is_err());
+    }
+}
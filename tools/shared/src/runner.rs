@@ -0,0 +1,781 @@
+//! Runs wasmatic components locally, with the same host setup for `wasmatic run` and the local node
+mod cron_bindings;
+mod fixtures;
+mod kv;
+mod legacy_task_bindings;
+mod task_bindings;
+
+pub use fixtures::{Body, Exchange, HttpFixtures, HttpMode, RecordedResponse};
+pub use kv::KvStore;
+
+use std::{
+    path::{Path, PathBuf},
+    string::FromUtf8Error,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use lavs_apis::tasks::TaskResponse;
+use layer_climb::prelude::Address;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use wasmtime::{
+    component::{Component, Linker},
+    Config, Engine, ResourceLimiter, Store, Trap, WasmBacktrace,
+};
+use wasmtime_wasi::{DirPerms, FilePerms, HostWallClock, WasiCtx, WasiCtxBuilder, WasiView};
+use wasmtime_wasi_http::{
+    bindings::http::types::ErrorCode,
+    body::HyperOutgoingBody,
+    types::{
+        default_send_request_handler, HostFutureIncomingResponse, IncomingResponse,
+        OutgoingRequestConfig,
+    },
+    HttpResult, WasiHttpCtx, WasiHttpView,
+};
+
+use crate::permissions::{FileSystemAccess, Permissions};
+
+/// How often a component yields back to the host, so it can be timed out even if it never calls the host
+const FUEL_YIELD_INTERVAL: u64 = 100_000;
+
+pub enum TriggerRequest {
+    Cron,
+    Queue(TaskInput),
+}
+
+/// The task given to `run-task`, components targeting `lay3r:avs@0.3.0` only get the request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskInput {
+    pub task_id: u64,
+    pub task_queue_addr: String,
+    pub created_height: u64,
+    /// Nanoseconds since the unix epoch
+    pub expires_time: u64,
+    pub description: String,
+    pub request: Vec<u8>,
+}
+
+impl TaskInput {
+    /// A task with only a request, for running components outside of a task queue
+    pub fn new(request: Vec<u8>) -> Self {
+        Self {
+            request,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_task(task_queue_addr: &Address, task: TaskResponse) -> Result<Self> {
+        Ok(Self {
+            task_id: task.id.u64(),
+            task_queue_addr: task_queue_addr.to_string(),
+            created_height: task.created_height,
+            expires_time: task.expires.nanos(),
+            description: task.description,
+            request: serde_json::to_vec(&task.payload)?,
+        })
+    }
+}
+
+/// The time given to components, through `lay3r:avs/clock` and the wasi wall clock
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Clock {
+    #[default]
+    System,
+    /// Nanoseconds since the unix epoch, to get the same output from every run
+    Fixed(u64),
+}
+
+impl Clock {
+    /// Nanoseconds since the unix epoch
+    pub fn now_nanos(&self) -> u64 {
+        match self {
+            Clock::System => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64,
+            Clock::Fixed(nanos) => *nanos,
+        }
+    }
+}
+
+/// A wasi wall clock which never moves
+struct FixedWallClock(Duration);
+
+impl HostWallClock for FixedWallClock {
+    fn resolution(&self) -> Duration {
+        Duration::from_nanos(1)
+    }
+
+    fn now(&self) -> Duration {
+        self.0
+    }
+}
+
+/// Resources a single invocation can use, `None` is unlimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// Roughly one unit per wasm instruction
+    pub fuel: Option<u64>,
+    /// Linear memory in bytes, per memory
+    pub max_memory: Option<usize>,
+    /// Elements per table
+    pub max_table_elements: Option<u32>,
+    /// Wall-clock time, including time spent waiting on the host like for HTTP requests
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            fuel: None,
+            max_memory: Some(1024 * 1024 * 1024),
+            max_table_elements: Some(1_000_000),
+            timeout: Some(Duration::from_secs(60)),
+        }
+    }
+}
+
+/// The limit a component ran into
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    Fuel(u64),
+    Memory(usize),
+    TableElements(u32),
+    Timeout(Duration),
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Fuel(fuel) => write!(f, "ran out of fuel after {fuel} units"),
+            Limit::Memory(bytes) => write!(f, "exceeded the memory limit of {bytes} bytes"),
+            Limit::TableElements(elements) => {
+                write!(f, "exceeded the table limit of {elements} elements")
+            }
+            Limit::Timeout(timeout) => write!(f, "timed out after {timeout:?}"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RunnerError {
+    #[error("Invalid wasm component: {0}")]
+    Compile(String),
+    #[error("Invalid env variable `{0}`, expected KEY=VALUE")]
+    InvalidEnv(String),
+    #[error("Env variable `{0}` is not allowed by the permissions")]
+    EnvNotAllowed(String),
+    #[error("Failed to preopen {0}: {1}")]
+    Preopen(PathBuf, String),
+    #[error("Wasm component is missing the expected function export `{export}` for {trigger} trigger app")]
+    MissingExport {
+        export: &'static str,
+        trigger: &'static str,
+    },
+    #[error("Failed to instantiate the component: {0}")]
+    Instantiate(String),
+    #[error("Component trapped: {message}{}", on_new_line(.backtrace))]
+    Trap {
+        message: String,
+        backtrace: Option<String>,
+    },
+    #[error("Component {0}")]
+    LimitExceeded(Limit),
+    /// The component ran, and returned an error
+    #[error("Component returned an error: {0}")]
+    Guest(String),
+    #[error("Output is invalid utf8: {0}")]
+    InvalidUtf8(#[from] FromUtf8Error),
+    #[error("Failed to decode the output: {0}")]
+    Decode(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct RunOutput {
+    pub output: Vec<u8>,
+    pub fuel_consumed: u64,
+}
+
+impl RunOutput {
+    pub fn text(&self) -> Result<String, RunnerError> {
+        Ok(String::from_utf8(self.output.clone())?)
+    }
+
+    /// The output parsed as JSON, if it is
+    pub fn json(&self) -> Option<Value> {
+        serde_json::from_slice(&self.output).ok()
+    }
+
+    /// The output as JSON if it is, or as a JSON string otherwise
+    pub fn to_value(&self) -> Result<Value, RunnerError> {
+        match self.json() {
+            Some(value) => Ok(value),
+            None => Ok(Value::String(self.text()?)),
+        }
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self) -> Result<T, RunnerError> {
+        Ok(serde_json::from_slice(&self.output)?)
+    }
+}
+
+fn on_new_line(text: &Option<String>) -> String {
+    text.as_ref()
+        .map(|text| format!("\n{text}"))
+        .unwrap_or_default()
+}
+
+/// Parses env variables given like `KEY=VALUE`
+pub fn parse_envs(pairs: &[String]) -> Result<Vec<(String, String)>, RunnerError> {
+    pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once('=')
+                .filter(|(key, _)| !key.is_empty())
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .ok_or_else(|| RunnerError::InvalidEnv(pair.clone()))
+        })
+        .collect()
+}
+
+/// Compiles and invokes components. It is cheap to clone, and can be shared between apps.
+#[derive(Clone)]
+pub struct Runner {
+    engine: Engine,
+    linker: Linker<Host>,
+    limits: Limits,
+    clock: Clock,
+    http: HttpMode,
+}
+
+impl Runner {
+    pub fn new(limits: Limits) -> Result<Self> {
+        let mut config = Config::new();
+        config.wasm_component_model(true);
+        config.async_support(true);
+        // Always metered, to report the fuel consumed even without a limit
+        config.consume_fuel(true);
+        let engine = Engine::new(&config)?;
+
+        let mut linker = Linker::new(&engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+        wasmtime_wasi_http::add_only_http_to_linker_async(&mut linker)?;
+        task_bindings::lay3r::avs0_4_0::kv::add_to_linker(&mut linker, |host: &mut Host| host)?;
+        task_bindings::lay3r::avs0_4_0::clock::add_to_linker(&mut linker, |host: &mut Host| host)?;
+
+        Ok(Self {
+            engine,
+            linker,
+            limits,
+            clock: Clock::default(),
+            http: HttpMode::default(),
+        })
+    }
+
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Records or replays the outgoing HTTP requests, to test components without network access
+    pub fn with_http(mut self, http: HttpMode) -> Self {
+        self.http = http;
+        self
+    }
+
+    pub fn compile(&self, wasm_binary: &[u8]) -> Result<Component, RunnerError> {
+        Component::new(&self.engine, wasm_binary)
+            .map_err(|err| RunnerError::Compile(format!("{err:#}")))
+    }
+
+    /// Compiles and invokes the component once
+    pub async fn run(
+        &self,
+        wasm_binary: &[u8],
+        envs: &[(String, String)],
+        app_cache_path: &Path,
+        permissions: &Permissions,
+        kv: &KvStore,
+        trigger: TriggerRequest,
+    ) -> Result<RunOutput, RunnerError> {
+        let component = self.compile(wasm_binary)?;
+        self.invoke(&component, envs, app_cache_path, permissions, kv, trigger)
+            .await
+    }
+
+    /// Instantiates the component in a fresh store, with `app_cache_path` preopened as its working dir
+    /// and only what the permissions allow. The kv store is kept between invocations.
+    pub async fn invoke(
+        &self,
+        component: &Component,
+        envs: &[(String, String)],
+        app_cache_path: &Path,
+        permissions: &Permissions,
+        kv: &KvStore,
+        trigger: TriggerRequest,
+    ) -> Result<RunOutput, RunnerError> {
+        if let Some((key, _)) = envs.iter().find(|(key, _)| !permissions.allows_env(key)) {
+            return Err(RunnerError::EnvNotAllowed(key.clone()));
+        }
+
+        let mut builder = WasiCtxBuilder::new();
+        if !envs.is_empty() {
+            builder.envs(envs);
+        }
+        let perms = match permissions.file_system {
+            FileSystemAccess::None => None,
+            FileSystemAccess::ReadOnly => Some((DirPerms::READ, FilePerms::READ)),
+            FileSystemAccess::ReadWrite => Some((DirPerms::all(), FilePerms::all())),
+        };
+        if let Some((dir_perms, file_perms)) = perms {
+            builder
+                .preopened_dir(app_cache_path, ".", dir_perms, file_perms)
+                .map_err(|err| {
+                    RunnerError::Preopen(app_cache_path.to_path_buf(), format!("{err:#}"))
+                })?;
+        }
+        // Components can log, but not read from the host's stdin
+        builder.inherit_stdout();
+        builder.inherit_stderr();
+        if let Clock::Fixed(nanos) = self.clock {
+            builder.wall_clock(FixedWallClock(Duration::from_nanos(nanos)));
+        }
+        let ctx = builder.build();
+
+        let host = Host {
+            table: wasmtime::component::ResourceTable::new(),
+            ctx,
+            http: WasiHttpCtx::new(),
+            permissions: permissions.clone(),
+            kv: kv.clone(),
+            clock: self.clock,
+            http_mode: self.http.clone(),
+            limiter: Limiter {
+                limits: self.limits,
+                exceeded: None,
+            },
+        };
+        let engine = &self.engine;
+        let linker = &self.linker;
+        let timestamp = self.clock.now_nanos() / 1_000_000_000;
+        let mut store = Store::new(engine, host);
+        store.limiter(|host| &mut host.limiter);
+        let fuel = self.limits.fuel.unwrap_or(u64::MAX);
+        store
+            .set_fuel(fuel)
+            .and_then(|_| store.fuel_async_yield_interval(Some(FUEL_YIELD_INTERVAL)))
+            .map_err(|err| RunnerError::Instantiate(format!("{err:#}")))?;
+
+        let (export, trigger_name) = match trigger {
+            TriggerRequest::Cron => ("run-cron", "CRON"),
+            TriggerRequest::Queue(_) => ("run-task", "task queue"),
+        };
+        if component
+            .component_type()
+            .get_export(engine, export)
+            .is_none()
+        {
+            return Err(RunnerError::MissingExport {
+                export,
+                trigger: trigger_name,
+            });
+        }
+
+        let run = {
+            let store = &mut store;
+            async move {
+                match trigger {
+                    TriggerRequest::Cron => {
+                        let bindings = cron_bindings::CronJob::instantiate_async(
+                            &mut *store,
+                            component,
+                            linker,
+                        )
+                        .await
+                        .map_err(|err| wasm_error(store, err, fuel, true))?;
+
+                        bindings
+                            .call_run_cron(&mut *store)
+                            .await
+                            .map_err(|err| wasm_error(store, err, fuel, false))
+                    }
+                    TriggerRequest::Queue(task) if targets_v0_3(engine, component) => {
+                        let bindings = legacy_task_bindings::TaskQueue::instantiate_async(
+                            &mut *store,
+                            component,
+                            linker,
+                        )
+                        .await
+                        .map_err(|err| wasm_error(store, err, fuel, true))?;
+
+                        let input = legacy_task_bindings::lay3r::avs0_3_0::types::TaskQueueInput {
+                            timestamp,
+                            request: task.request,
+                        };
+                        bindings
+                            .call_run_task(&mut *store, &input)
+                            .await
+                            .map_err(|err| wasm_error(store, err, fuel, false))
+                    }
+                    TriggerRequest::Queue(task) => {
+                        let bindings = task_bindings::TaskQueue::instantiate_async(
+                            &mut *store,
+                            component,
+                            linker,
+                        )
+                        .await
+                        .map_err(|err| wasm_error(store, err, fuel, true))?;
+
+                        let input = task_bindings::lay3r::avs0_4_0::types::TaskQueueInput {
+                            task_id: task.task_id,
+                            task_queue_addr: task.task_queue_addr,
+                            created_height: task.created_height,
+                            expires_time: task.expires_time,
+                            description: task.description,
+                            timestamp,
+                            request: task.request,
+                        };
+                        bindings
+                            .call_run_task(&mut *store, &input)
+                            .await
+                            .map_err(|err| wasm_error(store, err, fuel, false))
+                    }
+                }
+            }
+        };
+
+        let result = match self.limits.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, run)
+                .await
+                .map_err(|_| RunnerError::LimitExceeded(Limit::Timeout(timeout)))?,
+            None => run.await,
+        };
+
+        match result? {
+            Ok(output) => Ok(RunOutput {
+                output,
+                fuel_consumed: fuel - store.get_fuel().unwrap_or_default(),
+            }),
+            Err(msg) => Err(RunnerError::Guest(msg)),
+        }
+    }
+}
+
+/// Tells apart the limits from other failures while instantiating or calling the component
+fn wasm_error(
+    store: &Store<Host>,
+    err: anyhow::Error,
+    fuel: u64,
+    instantiating: bool,
+) -> RunnerError {
+    if err.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+        return RunnerError::LimitExceeded(Limit::Fuel(fuel));
+    }
+    // Failing to grow usually traps later on, in the guest
+    if let Some(limit) = store.data().limiter.exceeded {
+        return RunnerError::LimitExceeded(limit);
+    }
+
+    if instantiating {
+        RunnerError::Instantiate(format!("{err:#}"))
+    } else {
+        RunnerError::Trap {
+            message: err.root_cause().to_string(),
+            backtrace: err.downcast_ref::<WasmBacktrace>().map(|bt| bt.to_string()),
+        }
+    }
+}
+
+/// Components built for the previous version of the `lay3r:avs` package import its types
+fn targets_v0_3(engine: &Engine, component: &Component) -> bool {
+    component
+        .component_type()
+        .imports(engine)
+        .any(|(name, _)| name.starts_with("lay3r:avs/") && name.ends_with("@0.3.0"))
+}
+
+struct Host {
+    pub(crate) table: wasmtime::component::ResourceTable,
+    pub(crate) ctx: WasiCtx,
+    pub(crate) http: WasiHttpCtx,
+    pub(crate) permissions: Permissions,
+    pub(crate) kv: KvStore,
+    pub(crate) clock: Clock,
+    pub(crate) http_mode: HttpMode,
+    pub(crate) limiter: Limiter,
+}
+
+/// Denies growing memories and tables past the limits, and remembers it
+struct Limiter {
+    limits: Limits,
+    exceeded: Option<Limit>,
+}
+
+impl ResourceLimiter for Limiter {
+    fn memory_growing(
+        &mut self,
+        _current: usize,
+        desired: usize,
+        _maximum: Option<usize>,
+    ) -> Result<bool> {
+        match self.limits.max_memory {
+            Some(max) if desired > max => {
+                self.exceeded = Some(Limit::Memory(max));
+                Ok(false)
+            }
+            _ => Ok(true),
+        }
+    }
+
+    fn table_growing(
+        &mut self,
+        _current: u32,
+        desired: u32,
+        _maximum: Option<u32>,
+    ) -> Result<bool> {
+        match self.limits.max_table_elements {
+            Some(max) if desired > max => {
+                self.exceeded = Some(Limit::TableElements(max));
+                Ok(false)
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+impl WasiView for Host {
+    fn table(&mut self) -> &mut wasmtime_wasi::ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.ctx
+    }
+}
+
+impl WasiHttpView for Host {
+    fn table(&mut self) -> &mut wasmtime::component::ResourceTable {
+        &mut self.table
+    }
+
+    fn ctx(&mut self) -> &mut WasiHttpCtx {
+        &mut self.http
+    }
+
+    fn send_request(
+        &mut self,
+        request: hyper::Request<HyperOutgoingBody>,
+        config: OutgoingRequestConfig,
+    ) -> HttpResult<HostFutureIncomingResponse> {
+        let host = request.uri().host().unwrap_or_default();
+        if !self
+            .permissions
+            .allows_http(request.method().as_str(), host)
+        {
+            return Err(ErrorCode::HttpRequestDenied.into());
+        }
+
+        let max_response_size = self.permissions.max_response_size;
+        let http_mode = self.http_mode.clone();
+        let handle = wasmtime_wasi::runtime::spawn(async move {
+            let response = send_request(request, config, http_mode).await;
+            Ok(match max_response_size {
+                Some(max) => response.and_then(|response| limit_response(response, max)),
+                None => response,
+            })
+        });
+
+        Ok(HostFutureIncomingResponse::pending(handle))
+    }
+}
+
+#[async_trait::async_trait]
+impl task_bindings::lay3r::avs0_4_0::kv::Host for Host {
+    async fn get(&mut self, key: String) -> Result<Option<Vec<u8>>, String> {
+        Ok(self.kv.get(&key))
+    }
+
+    async fn set(&mut self, key: String, value: Vec<u8>) -> Result<(), String> {
+        self.kv.set(&key, value).map_err(|err| format!("{err:#}"))
+    }
+
+    async fn delete(&mut self, key: String) -> Result<(), String> {
+        self.kv.delete(&key).map_err(|err| format!("{err:#}"))
+    }
+
+    async fn list_prefix(&mut self, prefix: String) -> Result<Vec<String>, String> {
+        Ok(self.kv.list_prefix(&prefix))
+    }
+}
+
+#[async_trait::async_trait]
+impl task_bindings::lay3r::avs0_4_0::clock::Host for Host {
+    async fn now(&mut self) -> u64 {
+        self.clock.now_nanos()
+    }
+}
+
+/// Sends the request, recording it along with its response, or answers it from the fixtures
+async fn send_request(
+    request: hyper::Request<HyperOutgoingBody>,
+    config: OutgoingRequestConfig,
+    http_mode: HttpMode,
+) -> Result<IncomingResponse, ErrorCode> {
+    let (fixtures, recording) = match http_mode {
+        HttpMode::Live => return default_send_request_handler(request, config).await,
+        HttpMode::Record(fixtures) => (fixtures, true),
+        HttpMode::Replay(fixtures) => (fixtures, false),
+    };
+
+    let method = request.method().to_string();
+    let url = request.uri().to_string();
+    let (parts, body) = request.into_parts();
+    let body = body.collect().await?.to_bytes();
+
+    if recording {
+        let request = hyper::Request::from_parts(parts, full_body(body.clone()));
+        let mut response = default_send_request_handler(request, config).await?;
+        let (parts, response_body) = response.resp.into_parts();
+        let response_body = response_body.collect().await?.to_bytes();
+
+        let headers = parts
+            .headers
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        fixtures
+            .record(Exchange {
+                method,
+                url,
+                body: body.as_ref().into(),
+                response: RecordedResponse {
+                    status: parts.status.as_u16(),
+                    headers,
+                    body: response_body.as_ref().into(),
+                },
+            })
+            .map_err(|err| ErrorCode::InternalError(Some(format!("{err:#}"))))?;
+
+        response.resp = hyper::Response::from_parts(parts, full_body(response_body));
+        return Ok(response);
+    }
+
+    let recorded = fixtures.replay(&method, &url, &body).ok_or_else(|| {
+        ErrorCode::InternalError(Some(format!("No recorded response for {method} {url}")))
+    })?;
+    let mut builder = hyper::Response::builder().status(recorded.status);
+    for (key, value) in &recorded.headers {
+        builder = builder.header(key, value);
+    }
+    let resp = builder
+        .body(full_body(Bytes::copy_from_slice(recorded.body.as_bytes())))
+        .map_err(|err| ErrorCode::InternalError(Some(err.to_string())))?;
+
+    Ok(IncomingResponse {
+        resp,
+        worker: None,
+        between_bytes_timeout: config.between_bytes_timeout,
+    })
+}
+
+fn full_body(bytes: Bytes) -> HyperOutgoingBody {
+    Full::new(bytes).map_err(|never| match never {}).boxed()
+}
+
+/// Rejects responses which say they are too large, and fails reading the body past `max` bytes otherwise
+fn limit_response(mut response: IncomingResponse, max: u64) -> Result<IncomingResponse, ErrorCode> {
+    let content_length = response
+        .resp
+        .headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if content_length.is_some_and(|len| len > max) {
+        return Err(ErrorCode::HttpResponseBodySize(content_length));
+    }
+
+    response.resp = response.resp.map(|body| {
+        Limited::new(body.map_err(BodyError), max as usize)
+            .map_err(move |err| match err.downcast::<BodyError>() {
+                Ok(err) => err.0,
+                Err(_) => ErrorCode::HttpResponseBodySize(None),
+            })
+            .boxed()
+    });
+
+    Ok(response)
+}
+
+/// Keeps the original error through `Limited`, which only deals with boxed errors
+#[derive(Debug)]
+struct BodyError(ErrorCode);
+
+impl std::fmt::Display for BodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_envs() {
+        let envs = parse_envs(&["API_KEY=abc=def".to_string(), "EMPTY=".to_string()]).unwrap();
+        assert_eq!(
+            envs,
+            vec![
+                ("API_KEY".to_string(), "abc=def".to_string()),
+                ("EMPTY".to_string(), String::new())
+            ]
+        );
+
+        let err = parse_envs(&["API_KEY".to_string()]).unwrap_err();
+        assert!(matches!(err, RunnerError::InvalidEnv(env) if env == "API_KEY"));
+        assert!(parse_envs(&["=abc".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_output_decoding() {
+        let output = RunOutput {
+            output: br#"{"y": 81}"#.to_vec(),
+            fuel_consumed: 0,
+        };
+        assert_eq!(output.to_value().unwrap(), serde_json::json!({"y": 81}));
+
+        #[derive(serde::Deserialize)]
+        struct Square {
+            y: u64,
+        }
+        assert_eq!(output.decode::<Square>().unwrap().y, 81);
+
+        let output = RunOutput {
+            output: b"not json".to_vec(),
+            fuel_consumed: 0,
+        };
+        assert_eq!(output.json(), None);
+        assert_eq!(
+            output.to_value().unwrap(),
+            Value::String("not json".to_string())
+        );
+        assert!(matches!(
+            output.decode::<Square>(),
+            Err(RunnerError::Decode(_))
+        ));
+
+        let output = RunOutput {
+            output: vec![0xff, 0xfe],
+            fuel_consumed: 0,
+        };
+        assert!(matches!(
+            output.to_value(),
+            Err(RunnerError::InvalidUtf8(_))
+        ));
+    }
+}
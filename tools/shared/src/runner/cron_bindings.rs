@@ -0,0 +1,14 @@
+use wasmtime::component::bindgen;
+bindgen!({
+  path: "../../wit",
+  world: "cron-job",
+  async: true,
+  with: {
+      "wasi": wasmtime_wasi::bindings,
+      "wasi:http@0.2.0": wasmtime_wasi_http::bindings::http,
+      // Shared with the task queue world, so the host implements them once
+      "lay3r:avs/types@0.4.0": super::task_bindings::lay3r::avs0_4_0::types,
+      "lay3r:avs/kv@0.4.0": super::task_bindings::lay3r::avs0_4_0::kv,
+      "lay3r:avs/clock@0.4.0": super::task_bindings::lay3r::avs0_4_0::clock,
+  },
+});
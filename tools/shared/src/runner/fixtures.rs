@@ -0,0 +1,227 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// How a component's outgoing HTTP requests are handled
+#[derive(Clone, Debug, Default)]
+pub enum HttpMode {
+    /// Sent as usual
+    #[default]
+    Live,
+    /// Sent as usual, and every request and its response are added to the fixtures
+    Record(HttpFixtures),
+    /// Answered from the fixtures without network access, unmatched requests fail
+    Replay(HttpFixtures),
+}
+
+/// A recorded request and its response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Exchange {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub body: Body,
+    pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: Body,
+}
+
+/// Bodies are kept as text when they are, so the fixtures can be read and edited
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Body {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl Default for Body {
+    fn default() -> Self {
+        Body::Text(String::new())
+    }
+}
+
+impl From<&[u8]> for Body {
+    fn from(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Body::Text(text.to_string()),
+            Err(_) => Body::Bytes(bytes.to_vec()),
+        }
+    }
+}
+
+impl Body {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Body::Text(text) => text.as_bytes(),
+            Body::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Recorded exchanges, shared by clones. When opened from a file, recording writes every exchange back to it.
+///
+/// Request headers are not recorded, so API keys sent in headers don't end up in the fixtures
+#[derive(Clone, Debug, Default)]
+pub struct HttpFixtures {
+    state: Arc<Mutex<State>>,
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    exchanges: Vec<Exchange>,
+    /// Whether each exchange was already replayed
+    used: Vec<bool>,
+}
+
+impl HttpFixtures {
+    pub fn new(exchanges: Vec<Exchange>) -> Self {
+        let used = vec![false; exchanges.len()];
+        Self {
+            state: Arc::new(Mutex::new(State { exchanges, used })),
+            path: None,
+        }
+    }
+
+    /// Loads the fixtures from a JSON file, which is created on the first recorded exchange if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let exchanges = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid HTTP fixtures at {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => vec![],
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()))
+            }
+        };
+
+        Ok(Self {
+            path: Some(path),
+            ..Self::new(exchanges)
+        })
+    }
+
+    pub fn exchanges(&self) -> Vec<Exchange> {
+        self.state.lock().unwrap().exchanges.clone()
+    }
+
+    pub fn record(&self, exchange: Exchange) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.exchanges.push(exchange);
+        state.used.push(false);
+
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_vec_pretty(&state.exchanges)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// The response to the first exchange matching the request which wasn't replayed yet,
+    /// or to the last matching one once they all were, so repeated requests are answered in the recorded order
+    pub fn replay(&self, method: &str, url: &str, body: &[u8]) -> Option<RecordedResponse> {
+        let mut state = self.state.lock().unwrap();
+        let matching: Vec<usize> = state
+            .exchanges
+            .iter()
+            .enumerate()
+            .filter(|(_, exchange)| {
+                exchange.method.eq_ignore_ascii_case(method)
+                    && exchange.url == url
+                    && exchange.body.as_bytes() == body
+            })
+            .map(|(index, _)| index)
+            .collect();
+
+        let index = matching
+            .iter()
+            .copied()
+            .find(|index| !state.used[*index])
+            .or(matching.last().copied())?;
+        state.used[index] = true;
+
+        Some(state.exchanges[index].response.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(url: &str, body: &str, response: &str) -> Exchange {
+        Exchange {
+            method: "GET".to_string(),
+            url: url.to_string(),
+            body: Body::Text(body.to_string()),
+            response: RecordedResponse {
+                status: 200,
+                headers: vec![],
+                body: Body::Text(response.to_string()),
+            },
+        }
+    }
+
+    #[test]
+    fn test_replay_in_order() {
+        let fixtures = HttpFixtures::new(vec![
+            exchange("https://example.com/price", "", "1"),
+            exchange("https://example.com/price", "", "2"),
+            exchange("https://example.com/other", "", "3"),
+        ]);
+
+        let replay = |url: &str, body: &str| {
+            fixtures
+                .replay("get", url, body.as_bytes())
+                .map(|response| response.body)
+        };
+        assert_eq!(
+            replay("https://example.com/price", ""),
+            Some(Body::Text("1".to_string()))
+        );
+        assert_eq!(
+            replay("https://example.com/price", ""),
+            Some(Body::Text("2".to_string()))
+        );
+        assert_eq!(
+            replay("https://example.com/price", ""),
+            Some(Body::Text("2".to_string()))
+        );
+        assert_eq!(replay("https://example.com/other", "{}"), None);
+        assert_eq!(replay("https://example.com/missing", ""), None);
+    }
+
+    #[test]
+    fn test_record_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fixtures.json");
+
+        let fixtures = HttpFixtures::open(&path).unwrap();
+        fixtures
+            .record(exchange("https://example.com/price", "", "1"))
+            .unwrap();
+        let mut binary = exchange("https://example.com/blob", "", "");
+        binary.response.body = Body::from([0xff, 0x00].as_slice());
+        fixtures.record(binary).unwrap();
+
+        let reopened = HttpFixtures::open(&path).unwrap();
+        assert_eq!(reopened.exchanges(), fixtures.exchanges());
+        assert_eq!(
+            reopened.exchanges()[1].response.body,
+            Body::Bytes(vec![0xff, 0x00])
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+
+/// The key-value store behind `lay3r:avs/kv`, one per app. It is cheap to clone, clones share the same entries.
+///
+/// When opened from a file, every change is written back to it so the entries outlive the process
+#[derive(Clone, Debug, Default)]
+pub struct KvStore {
+    entries: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+    path: Option<PathBuf>,
+}
+
+impl KvStore {
+    /// A store which is dropped with the last clone
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Loads the store from a JSON file, which is created on the first change if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Invalid kv store at {}", path.display()))?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to read {}", path.display()))
+            }
+        };
+
+        Ok(Self {
+            entries: Arc::new(Mutex::new(entries)),
+            path: Some(path),
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    pub fn set(&self, key: &str, value: Vec<u8>) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(key.to_string(), value);
+        self.save(&entries)
+    }
+
+    pub fn delete(&self, key: &str) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.remove(key).is_some() {
+            self.save(&entries)?;
+        }
+        Ok(())
+    }
+
+    /// The keys starting with `prefix`, in order
+    pub fn list_prefix(&self, prefix: &str) -> Vec<String> {
+        self.entries
+            .lock()
+            .unwrap()
+            .range(prefix.to_string()..)
+            .map(|(key, _)| key)
+            .take_while(|key| key.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    fn save(&self, entries: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, serde_json::to_vec(entries)?)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_prefix() {
+        let kv = KvStore::in_memory();
+        for key in ["price/btc", "price/eth", "prices", "last"] {
+            kv.set(key, key.as_bytes().to_vec()).unwrap();
+        }
+
+        assert_eq!(kv.list_prefix("price/"), vec!["price/btc", "price/eth"]);
+        assert_eq!(kv.list_prefix("").len(), 4);
+        assert!(kv.list_prefix("z").is_empty());
+
+        kv.delete("price/btc").unwrap();
+        assert_eq!(kv.get("price/btc"), None);
+        assert_eq!(kv.get("price/eth"), Some(b"price/eth".to_vec()));
+    }
+
+    #[test]
+    fn test_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kv").join("app.json");
+
+        let kv = KvStore::open(&path).unwrap();
+        kv.set("count", b"1".to_vec()).unwrap();
+        kv.set("gone", b"soon".to_vec()).unwrap();
+        kv.delete("gone").unwrap();
+
+        let reopened = KvStore::open(&path).unwrap();
+        assert_eq!(reopened.get("count"), Some(b"1".to_vec()));
+        assert_eq!(reopened.get("gone"), None);
+    }
+}
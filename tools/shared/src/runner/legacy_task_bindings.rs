@@ -1,7 +1,7 @@
 use wasmtime::component::bindgen;
 bindgen!({
   path: "../../wit",
-  world: "cron-job",
+  world: "lay3r:avs/task-queue@0.3.0",
   async: true,
   with: {
       "wasi": wasmtime_wasi::bindings,
@@ -0,0 +1,96 @@
+use std::{fmt, str::FromStr};
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+
+/// A cron schedule as given to cron triggers, with seconds:
+/// `sec min hour day-of-month month day-of-week [year]`
+#[derive(Debug, Clone)]
+pub struct CronSchedule {
+    expression: String,
+    schedule: cron::Schedule,
+}
+
+impl CronSchedule {
+    /// Fails if the expression is invalid, or if it never fires again
+    pub fn parse(expression: &str) -> Result<Self> {
+        let expression = expression.trim();
+        let schedule = cron::Schedule::from_str(expression).context(format!(
+            "Invalid cron schedule: `{expression}`, expected `sec min hour day-of-month month day-of-week [year]`"
+        ))?;
+        let schedule = Self {
+            expression: expression.to_string(),
+            schedule,
+        };
+
+        if schedule.next_after(Utc::now()).is_none() {
+            bail!("Cron schedule `{expression}` never fires again");
+        }
+
+        Ok(schedule)
+    }
+
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.schedule.after(&after).next()
+    }
+
+    /// The next `count` fire times after `after`, fewer if the schedule ends
+    pub fn upcoming_after(&self, after: DateTime<Utc>, count: usize) -> Vec<DateTime<Utc>> {
+        self.schedule.after(&after).take(count).collect()
+    }
+
+    pub fn upcoming(&self, count: usize) -> Vec<DateTime<Utc>> {
+        self.upcoming_after(Utc::now(), count)
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = anyhow::Error;
+
+    fn from_str(expression: &str) -> Result<Self> {
+        Self::parse(expression)
+    }
+}
+
+impl fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upcoming() {
+        let schedule = CronSchedule::parse("0 */15 * * * *").unwrap();
+        let after = DateTime::parse_from_rfc3339("2024-10-01T12:07:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let times: Vec<String> = schedule
+            .upcoming_after(after, 3)
+            .iter()
+            .map(|time| time.to_rfc3339())
+            .collect();
+        assert_eq!(
+            times,
+            vec![
+                "2024-10-01T12:15:00+00:00",
+                "2024-10-01T12:30:00+00:00",
+                "2024-10-01T12:45:00+00:00"
+            ]
+        );
+        assert_eq!(schedule.to_string(), "0 */15 * * * *");
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(CronSchedule::parse("every minute").is_err());
+        // Without seconds
+        assert!(CronSchedule::parse("*/5 * * * *").is_err());
+        // Only in the past
+        assert!(CronSchedule::parse("0 0 0 1 1 * 2000").is_err());
+    }
+}
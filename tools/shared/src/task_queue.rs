@@ -1,16 +1,22 @@
 use anyhow::{bail, Result};
 use cosmwasm_std::Order;
 use lavs_apis::{
-    events::{task_queue_events::TaskCreatedEvent, traits::TypedEvent as _},
+    events::{
+        task_queue_events::{TaskCreatedEvent, TasksSweptEvent},
+        traits::TypedEvent as _,
+    },
     id::TaskId,
     interfaces::task_hooks::{HooksResponse, TaskHookType},
     tasks::{
         CompletedTaskOverview, ListCompletedResponse, ListOpenResponse, OpenTaskOverview,
-        TaskSpecificWhitelistResponse,
+        RequestorAllowlistResponse, TaskResponse, TaskSpecificWhitelistResponse,
     },
     time::Duration,
 };
-use lavs_task_queue::msg::{ConfigResponse, CustomExecuteMsg, CustomQueryMsg, QueryMsg, Requestor};
+use lavs_task_queue::{
+    msg::{ConfigResponse, CustomExecuteMsg, CustomQueryMsg, QueryMsg, Requestor},
+    ContractError as TaskQueueError,
+};
 use layer_climb::{prelude::*, proto::abci::TxResponse};
 
 use crate::{operator::OperatorQuerier, verifier::SimpleVerifierQuerier};
@@ -57,6 +63,16 @@ impl TaskQueue {
                 }
                 Vec::new()
             }
+            // Allowlist membership is checked by the contract
+            Requestor::Allowlist(_) => Vec::new(),
+            // Pay with the first accepted coin
+            Requestor::AllowlistWithPayment { coins, .. } | Requestor::OpenPaymentAnyOf(coins) => {
+                coins
+                    .into_iter()
+                    .take(1)
+                    .map(|coin| new_coin(coin.amount, coin.denom))
+                    .collect()
+            }
         };
 
         let tx_resp = self
@@ -86,6 +102,40 @@ impl TaskQueue {
         Ok((event.task_id, tx_resp))
     }
 
+    /// Expires up to `limit` tasks past their timeout, returning how many were expired.
+    /// Returns `None` if there are none, any other failure is an error.
+    pub async fn timeout_expired(&self, limit: Option<u32>) -> Result<Option<(u32, TxResponse)>> {
+        let res = self
+            .admin
+            .contract_execute(
+                &self.contract_addr,
+                &CustomExecuteMsg::TimeoutExpired { limit },
+                vec![],
+                None,
+            )
+            .await;
+        let tx_resp = match res {
+            Ok(tx_resp) => tx_resp,
+            Err(err) => {
+                let none_expired = TaskQueueError::NoExpiredTasks.to_string();
+                if err.chain().any(|e| e.to_string().contains(&none_expired)) {
+                    return Ok(None);
+                }
+                return Err(err);
+            }
+        };
+
+        let event: cosmwasm_std::Event = CosmosTxEvents::from(&tx_resp)
+            .event_first_by_type(TasksSweptEvent::NAME)?
+            .into();
+        let event: TasksSweptEvent = event.try_into()?;
+
+        tracing::info!("Expired {} tasks", event.count);
+        tracing::debug!("Tx hash: {}", tx_resp.txhash);
+
+        Ok(Some((event.count, tx_resp)))
+    }
+
     pub async fn add_hooks<T: Into<TaskHookType>>(
         &self,
         task_id: Option<TaskId>,
@@ -157,6 +207,26 @@ impl TaskQueue {
         tracing::debug!("Tx hash: {}", tx_resp.txhash);
         Ok(tx_resp)
     }
+
+    pub async fn update_requestor_allowlist(
+        &self,
+        to_add: Option<Vec<String>>,
+        to_remove: Option<Vec<String>>,
+    ) -> Result<TxResponse> {
+        let tx_resp = self
+            .admin
+            .contract_execute(
+                &self.contract_addr,
+                &CustomExecuteMsg::UpdateRequestorAllowlist { to_add, to_remove },
+                vec![],
+                None,
+            )
+            .await?;
+
+        tracing::info!("Updated requestor allowlist.");
+        tracing::debug!("Tx hash: {}", tx_resp.txhash);
+        Ok(tx_resp)
+    }
 }
 
 pub struct TaskQueueQuerier {
@@ -256,6 +326,32 @@ impl TaskQueueQuerier {
         Ok(all_tasks)
     }
 
+    pub async fn task(&self, id: TaskId) -> Result<TaskResponse> {
+        self.query_client
+            .contract_smart(
+                &self.contract_addr,
+                &QueryMsg::Custom(CustomQueryMsg::Task { id }),
+            )
+            .await
+    }
+
+    /// Open tasks with the highest tips first
+    pub async fn open_tasks_by_priority(
+        &self,
+        start_after: Option<TaskId>,
+        limit: Option<u32>,
+    ) -> Result<Vec<OpenTaskOverview>> {
+        let tasks: ListOpenResponse = self
+            .query_client
+            .contract_smart(
+                &self.contract_addr,
+                &QueryMsg::Custom(CustomQueryMsg::ListOpenByPriority { start_after, limit }),
+            )
+            .await?;
+
+        Ok(tasks.tasks)
+    }
+
     pub async fn view_hooks<T: Into<TaskHookType>>(
         &self,
         task_id: Option<TaskId>,
@@ -284,6 +380,19 @@ impl TaskQueueQuerier {
             )
             .await
     }
+
+    pub async fn view_requestor_allowlist(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<RequestorAllowlistResponse> {
+        self.query_client
+            .contract_smart(
+                &self.contract_addr,
+                &QueryMsg::Custom(CustomQueryMsg::RequestorAllowlist { start_after, limit }),
+            )
+            .await
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -321,6 +430,9 @@ impl TaskQueueView {
                 TaskView::Open(task) => {
                     log(&format!("  - Open Task: {}", task.id));
                     log(&format!("    Expires: {}", task.expires));
+                    if let Some(tip) = &task.tip {
+                        log(&format!("    Tip: {}", tip));
+                    }
                     log(&format!("    Payload: {}", data_json_string));
                 }
                 TaskView::Completed(task) => {
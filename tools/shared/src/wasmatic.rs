@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
-use crate::file::WasmFile;
+use crate::{file::WasmFile, schedule::CronSchedule};
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -21,6 +21,13 @@ pub enum Trigger {
     },
 }
 
+/// The sha256 digest wasmatic uses to identify a wasm file, like `sha256:<hex>`
+pub fn wasm_digest(wasm_binary: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(wasm_binary);
+    format!("sha256:{:x}", hasher.finalize())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub async fn deploy(
     http_client: reqwest::Client,
@@ -35,6 +42,10 @@ pub async fn deploy(
     testable: bool,
     on_deploy_success: impl Fn(&str),
 ) -> Result<()> {
+    if let Trigger::Cron { schedule } = &trigger {
+        CronSchedule::parse(schedule)?;
+    }
+
     if let Trigger::Queue {
         task_queue_addr, ..
     } = &trigger
@@ -77,11 +88,7 @@ pub async fn deploy(
         WasmFile::Bytes(wasm_binary) => {
             let mut json_body = body.clone();
 
-            // calculate sha256sum
-            let mut hasher = Sha256::new();
-            hasher.update(&wasm_binary);
-            let result = hasher.finalize();
-            json_body["digest"] = json!(format!("sha256:{:x}", result));
+            json_body["digest"] = json!(wasm_digest(&wasm_binary));
 
             futures::future::join_all(endpoints.iter().map(|endpoint| {
                 let http_client = http_client.clone();
@@ -224,7 +231,7 @@ pub struct AppResponse {
     pub digests: Vec<String>,
 }
 
-#[derive(Deserialize, Debug, Serialize, PartialEq)]
+#[derive(Deserialize, Debug, Serialize, PartialEq, Clone)]
 pub struct AppInfo {
     pub name: String,
     pub digest: String,
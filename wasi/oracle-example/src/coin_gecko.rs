@@ -1,7 +1,7 @@
-use layer_wasi::{Reactor, Request, WasiPollable};
+use layer_wasi::{Reactor, Request, Retry, WasiPollable};
 
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 #[derive(Deserialize, Debug)]
 pub struct CoinInfo {
@@ -21,7 +21,9 @@ impl CoinGeckoResponse {
 
 pub async fn get_btc_usd_price(reactor: &Reactor, api_key: &str) -> Result<Option<f32>, String> {
     let mut req = Request::get("https://api.coingecko.com/api/v3/exchange_rates")?;
-    req.headers = vec![("x-cg-pro-api-key".to_string(), api_key.to_owned())];
+    req.header("x-cg-pro-api-key", api_key)
+        .timeout(Duration::from_secs(10))
+        .retry(Retry::default());
     let res = reactor.send(req).await?;
 
     match res.status {